@@ -0,0 +1,41 @@
+-- Content-addressed image storage.
+-- Originals are stored once per content hash at images/{hash[0..2]}/{hash}.{ext}
+-- and shared by every item that references them.
+
+CREATE TABLE IF NOT EXISTS image_blobs (
+    hash        TEXT PRIMARY KEY NOT NULL,
+    path        TEXT NOT NULL,
+    size        INTEGER NOT NULL DEFAULT 0,
+    ref_count   INTEGER NOT NULL DEFAULT 0,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_image_blobs_path ON image_blobs(path);
+
+ALTER TABLE clipboard_items ADD COLUMN image_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_clipboard_items_image_hash ON clipboard_items(image_hash);
+
+-- Reference counts follow clipboard_items rows, so every delete path
+-- (single delete, clear history, retention) keeps them consistent.
+CREATE TRIGGER IF NOT EXISTS trg_image_blobs_ref_insert
+AFTER INSERT ON clipboard_items
+WHEN NEW.image_hash IS NOT NULL
+BEGIN
+    UPDATE image_blobs SET ref_count = ref_count + 1 WHERE hash = NEW.image_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_image_blobs_ref_delete
+AFTER DELETE ON clipboard_items
+WHEN OLD.image_hash IS NOT NULL
+BEGIN
+    UPDATE image_blobs SET ref_count = ref_count - 1 WHERE hash = OLD.image_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_image_blobs_ref_update
+AFTER UPDATE OF image_hash ON clipboard_items
+WHEN OLD.image_hash IS NOT NEW.image_hash
+BEGIN
+    UPDATE image_blobs SET ref_count = ref_count - 1 WHERE hash = OLD.image_hash;
+    UPDATE image_blobs SET ref_count = ref_count + 1 WHERE hash = NEW.image_hash;
+END;
@@ -0,0 +1,6 @@
+-- Record the original encoding of stored images so paste can offer it back.
+-- Everything captured before this migration was re-encoded to PNG.
+
+ALTER TABLE clipboard_items ADD COLUMN image_mime TEXT;
+
+UPDATE clipboard_items SET image_mime = 'image/png' WHERE image_path IS NOT NULL;
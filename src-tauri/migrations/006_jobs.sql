@@ -0,0 +1,20 @@
+-- Persistent background job queue (thumbnails and other derived data).
+-- Jobs survive restarts; rows left 'running' by a crash are re-queued at startup.
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind          TEXT NOT NULL,
+    payload       TEXT NOT NULL DEFAULT '{}',
+    status        TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'running', 'failed')),
+    attempts      INTEGER NOT NULL DEFAULT 0,
+    max_attempts  INTEGER NOT NULL DEFAULT 5,
+    run_after     TEXT NOT NULL DEFAULT (datetime('now')),
+    last_error    TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_status_run_after ON jobs(status, run_after);
+
+-- At most one queued job per kind + payload, so re-enqueueing is idempotent
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_kind_payload ON jobs(kind, payload);
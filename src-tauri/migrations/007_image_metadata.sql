@@ -0,0 +1,11 @@
+-- Image properties and EXIF details extracted on capture.
+-- Existing items are filled in by a startup backfill.
+
+ALTER TABLE clipboard_items ADD COLUMN image_width INTEGER;
+ALTER TABLE clipboard_items ADD COLUMN image_height INTEGER;
+ALTER TABLE clipboard_items ADD COLUMN image_format TEXT;
+ALTER TABLE clipboard_items ADD COLUMN image_color_type TEXT;
+ALTER TABLE clipboard_items ADD COLUMN exif_camera TEXT;
+ALTER TABLE clipboard_items ADD COLUMN exif_taken_at TEXT;
+ALTER TABLE clipboard_items ADD COLUMN exif_gps_latitude REAL;
+ALTER TABLE clipboard_items ADD COLUMN exif_gps_longitude REAL;
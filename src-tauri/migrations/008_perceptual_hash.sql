@@ -0,0 +1,14 @@
+-- Perceptual hash (dHash) of image items for near-duplicate detection.
+-- Near-duplicates can be stored as variants of a primary item (variant_of).
+
+ALTER TABLE clipboard_items ADD COLUMN perceptual_hash INTEGER;
+ALTER TABLE clipboard_items ADD COLUMN variant_of TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_clipboard_items_variant_of ON clipboard_items(variant_of);
+
+-- Deleting a primary item promotes its variants back to standalone items
+CREATE TRIGGER IF NOT EXISTS trg_clipboard_items_promote_variants
+AFTER DELETE ON clipboard_items
+BEGIN
+    UPDATE clipboard_items SET variant_of = NULL WHERE variant_of = OLD.id;
+END;
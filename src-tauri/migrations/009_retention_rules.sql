@@ -0,0 +1,14 @@
+-- Per-content-type retention rules, applied on top of the global retention policy.
+-- An empty source_app matches items from every app.
+
+CREATE TABLE IF NOT EXISTS retention_rules (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    content_type  TEXT NOT NULL CHECK(content_type IN ('plain_text', 'rich_text', 'image', 'file', 'link')),
+    source_app    TEXT NOT NULL DEFAULT '',
+    max_days      INTEGER,
+    max_count     INTEGER,
+    max_size_mb   INTEGER,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_retention_rules_scope ON retention_rules(content_type, source_app);
@@ -0,0 +1,4 @@
+-- Which selection an item was captured from: the regular clipboard or the
+-- X11/Wayland PRIMARY selection (select-to-copy).
+
+ALTER TABLE clipboard_items ADD COLUMN selection TEXT NOT NULL DEFAULT 'clipboard' CHECK(selection IN ('clipboard', 'primary'));
@@ -0,0 +1,33 @@
+-- Per-application capture rules. 'exclude' rules are never recorded; once any
+-- 'include' rule exists, only matching apps are recorded.
+-- `app` matches a source app's id, executable name or display name, ignoring case.
+
+CREATE TABLE IF NOT EXISTS app_capture_rules (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    app         TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    action      TEXT NOT NULL DEFAULT 'exclude' CHECK(action IN ('exclude', 'include')),
+    created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Password managers and remote desktop clients
+INSERT OR IGNORE INTO app_capture_rules (app) VALUES
+    ('1Password'),
+    ('com.1password.1password'),
+    ('com.agilebits.onepassword7'),
+    ('Bitwarden'),
+    ('com.bitwarden.desktop'),
+    ('KeePassXC'),
+    ('org.keepassxc.keepassxc'),
+    ('KeePass'),
+    ('Enpass'),
+    ('in.sinew.Enpass-Desktop'),
+    ('com.apple.keychainaccess'),
+    ('com.apple.Passwords'),
+    ('Seahorse'),
+    ('mstsc'),
+    ('com.microsoft.rdc.macos'),
+    ('Remmina'),
+    ('AnyDesk'),
+    ('com.philandro.anydesk'),
+    ('TeamViewer'),
+    ('com.teamviewer.TeamViewer');
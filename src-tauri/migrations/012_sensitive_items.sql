@@ -0,0 +1,8 @@
+-- Sensitive items (e.g. secrets marked concealed by a password manager) are masked
+-- in item lists, kept out of the search index and deleted once expires_at passes.
+
+ALTER TABLE clipboard_items ADD COLUMN is_sensitive BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE clipboard_items ADD COLUMN expires_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_clipboard_items_expires_at ON clipboard_items(expires_at)
+    WHERE expires_at IS NOT NULL;
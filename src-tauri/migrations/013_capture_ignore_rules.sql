@@ -0,0 +1,11 @@
+-- User-defined rules for content that is never recorded, checked before an item is stored.
+-- `regex`, `min_length`, `max_length` and `whitespace` apply to text; `extension` to files.
+-- `value` holds the pattern, character count or extension (unused for `whitespace`).
+
+CREATE TABLE IF NOT EXISTS capture_ignore_rules (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind        TEXT NOT NULL CHECK(kind IN ('regex', 'min_length', 'max_length', 'whitespace', 'extension')),
+    value       TEXT NOT NULL DEFAULT '',
+    enabled     BOOLEAN NOT NULL DEFAULT 1,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+);
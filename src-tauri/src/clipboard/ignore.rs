@@ -0,0 +1,146 @@
+use crate::db::models::{CaptureIgnoreRule, ContentType};
+use regex::Regex;
+
+/// Check a rule before it's saved: known kind, compilable pattern, numeric length
+/// and a non-empty extension.
+pub fn validate_rule(rule: &CaptureIgnoreRule) -> Result<(), String> {
+    let value = rule.value.trim();
+    match rule.kind.as_str() {
+        "regex" => Regex::new(value)
+            .map(|_| ())
+            .map_err(|e| format!("Invalid pattern: {}", e)),
+        "min_length" | "max_length" => value
+            .parse::<usize>()
+            .map(|_| ())
+            .map_err(|_| format!("Length must be a whole number: {}", value)),
+        "extension" if value.trim_start_matches('.').is_empty() => {
+            Err("Extension must not be empty".to_string())
+        }
+        "extension" | "whitespace" => Ok(()),
+        other => Err(format!("Unknown ignore rule kind: {}", other)),
+    }
+}
+
+/// The first enabled rule that ignores a copy, if any. `text` is the plain text
+/// for text types and the path for files; text rules never apply to files and
+/// extension rules only apply to files.
+pub fn first_ignore_rule<'a>(
+    rules: &'a [CaptureIgnoreRule],
+    content_type: &ContentType,
+    text: Option<&str>,
+) -> Option<&'a CaptureIgnoreRule> {
+    let text = text?;
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .find(|rule| rule_matches(rule, content_type, text))
+}
+
+fn rule_matches(rule: &CaptureIgnoreRule, content_type: &ContentType, text: &str) -> bool {
+    let value = rule.value.trim();
+    if *content_type == ContentType::File {
+        return rule.kind == "extension"
+            && file_extension(text)
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(value.trim_start_matches('.')));
+    }
+    if !content_type.is_text() {
+        return false;
+    }
+    let length = || value.parse::<usize>().ok();
+    match rule.kind.as_str() {
+        // Patterns are validated on save; one edited into the DB by hand matches nothing
+        "regex" => Regex::new(value).is_ok_and(|re| re.is_match(text)),
+        "min_length" => length().is_some_and(|min| text.chars().count() < min),
+        "max_length" => length().is_some_and(|max| text.chars().count() > max),
+        "whitespace" => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// Extension of the last path component, split on both separators so Windows
+/// paths work everywhere.
+fn file_extension(path: &str) -> Option<&str> {
+    let name = path.rsplit(['/', '\\']).next()?;
+    let (stem, ext) = name.rsplit_once('.')?;
+    (!stem.is_empty() && !ext.is_empty()).then_some(ext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: &str, value: &str) -> CaptureIgnoreRule {
+        CaptureIgnoreRule {
+            id: 0,
+            kind: kind.into(),
+            value: value.into(),
+            enabled: true,
+        }
+    }
+
+    fn fired(
+        rules: &[CaptureIgnoreRule],
+        content_type: &ContentType,
+        text: &str,
+    ) -> Option<String> {
+        first_ignore_rule(rules, content_type, Some(text)).map(|r| r.kind.clone())
+    }
+
+    #[test]
+    fn test_text_rules() {
+        let rules = vec![
+            rule("whitespace", ""),
+            rule("regex", r"^\d{6}$"),
+            rule("min_length", "3"),
+            rule("max_length", "20"),
+        ];
+        let text = &ContentType::PlainText;
+        assert_eq!(fired(&rules, text, " \n\t"), Some("whitespace".into()));
+        assert_eq!(fired(&rules, text, "482913"), Some("regex".into()));
+        assert_eq!(fired(&rules, text, "ok"), Some("min_length".into()));
+        // Lengths count characters, not bytes
+        assert_eq!(fired(&rules, text, "剪贴板"), None);
+        assert_eq!(
+            fired(&rules, text, &"x".repeat(21)),
+            Some("max_length".into())
+        );
+        assert_eq!(fired(&rules, text, "hello world"), None);
+        assert_eq!(
+            fired(&rules, &ContentType::Link, "ok"),
+            Some("min_length".into())
+        );
+        assert_eq!(fired(&rules, &ContentType::Image, "ok"), None);
+        assert!(first_ignore_rule(&rules, text, None).is_none());
+    }
+
+    #[test]
+    fn test_extension_rules_apply_to_files_only() {
+        let rules = vec![rule("extension", ".EXE"), rule("min_length", "100")];
+        let file = &ContentType::File;
+        assert_eq!(
+            fired(&rules, file, r"C:\Users\me\setup.exe"),
+            Some("extension".into())
+        );
+        assert_eq!(fired(&rules, file, "/home/me/notes.txt"), None);
+        assert_eq!(fired(&rules, file, "/home/me/.exe"), None);
+        assert_eq!(
+            fired(&rules, &ContentType::PlainText, "setup.exe"),
+            Some("min_length".into())
+        );
+    }
+
+    #[test]
+    fn test_disabled_rules_and_validation() {
+        let mut off = rule("whitespace", "");
+        off.enabled = false;
+        assert_eq!(fired(&[off], &ContentType::PlainText, "  "), None);
+
+        assert!(validate_rule(&rule("regex", r"^\d{6}$")).is_ok());
+        assert!(validate_rule(&rule("regex", "(")).is_err());
+        assert!(validate_rule(&rule("min_length", " 4 ")).is_ok());
+        assert!(validate_rule(&rule("max_length", "-1")).is_err());
+        assert!(validate_rule(&rule("extension", ".")).is_err());
+        assert!(validate_rule(&rule("whitespace", "")).is_ok());
+        assert!(validate_rule(&rule("color", "red")).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use crate::db::models::ImageMetadata;
+use std::io::Cursor;
+
+/// Setting key: strip EXIF/XMP from stored originals and pasted images.
+pub const STRIP_METADATA_KEY: &str = "strip_image_metadata";
+
+/// Read dimensions, format and color type from the image header, plus camera,
+/// capture time and GPS position from EXIF when present. Returns None if the
+/// data isn't a decodable image.
+pub fn extract_image_metadata(data: &[u8]) -> Option<ImageMetadata> {
+    use image::ImageDecoder;
+
+    let reader = image::ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?;
+    let format = reader.format()?;
+    let decoder = reader.into_decoder().ok()?;
+    let (width, height) = decoder.dimensions();
+
+    let mut meta = ImageMetadata {
+        width,
+        height,
+        format: format!("{:?}", format).to_lowercase(),
+        color_type: format!("{:?}", decoder.color_type()).to_lowercase(),
+        ..Default::default()
+    };
+
+    if let Ok(exif) = exif::Reader::new().read_from_container(&mut Cursor::new(data)) {
+        meta.camera = exif_camera(&exif);
+        meta.taken_at = exif_taken_at(&exif);
+        if let Some((lat, lon)) = exif_gps(&exif) {
+            meta.gps_latitude = Some(lat);
+            meta.gps_longitude = Some(lon);
+        }
+    }
+    Some(meta)
+}
+
+/// Forget camera, capture time and location (used when metadata stripping is on).
+pub fn clear_exif(meta: &mut ImageMetadata) {
+    meta.camera = None;
+    meta.taken_at = None;
+    meta.gps_latitude = None;
+    meta.gps_longitude = None;
+}
+
+fn exif_ascii(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    let exif::Value::Ascii(ref parts) = field.value else {
+        return None;
+    };
+    let text = String::from_utf8_lossy(parts.first()?)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// "Make Model", without repeating the make when the model already includes it.
+fn exif_camera(exif: &exif::Exif) -> Option<String> {
+    let make = exif_ascii(exif, exif::Tag::Make);
+    let model = exif_ascii(exif, exif::Tag::Model);
+    match (make, model) {
+        (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model),
+    }
+}
+
+/// Capture time as `YYYY-MM-DDTHH:MM:SS` (camera local time, no zone).
+fn exif_taken_at(exif: &exif::Exif) -> Option<String> {
+    [exif::Tag::DateTimeOriginal, exif::Tag::DateTime]
+        .into_iter()
+        .find_map(|tag| {
+            let field = exif.get_field(tag, exif::In::PRIMARY)?;
+            let exif::Value::Ascii(ref parts) = field.value else {
+                return None;
+            };
+            let dt = exif::DateTime::from_ascii(parts.first()?).ok()?;
+            Some(format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second
+            ))
+        })
+}
+
+/// GPS position in signed decimal degrees (south/west negative).
+fn exif_gps(exif: &exif::Exif) -> Option<(f64, f64)> {
+    let coord = |tag: exif::Tag, ref_tag: exif::Tag, negative: &str| -> Option<f64> {
+        let field = exif.get_field(tag, exif::In::PRIMARY)?;
+        let exif::Value::Rational(ref dms) = field.value else {
+            return None;
+        };
+        let deg = dms.first()?.to_f64()
+            + dms.get(1).map_or(0.0, |m| m.to_f64() / 60.0)
+            + dms.get(2).map_or(0.0, |s| s.to_f64() / 3600.0);
+        if !deg.is_finite() {
+            return None;
+        }
+        let sign = match exif_ascii(exif, ref_tag) {
+            Some(r) if r.eq_ignore_ascii_case(negative) => -1.0,
+            _ => 1.0,
+        };
+        Some(sign * deg)
+    };
+    let lat = coord(exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef, "S")?;
+    let lon = coord(exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef, "W")?;
+    Some((lat, lon))
+}
+
+/// Remove EXIF/XMP metadata from encoded image bytes. JPEG, PNG and WebP are
+/// rewritten at the container level so pixels stay byte-identical; TIFF keeps
+/// its metadata in the image directory and is re-encoded instead.
+/// Returns `Ok(None)` when there is nothing to strip.
+pub fn strip_image_metadata(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    match image::guess_format(data) {
+        Ok(image::ImageFormat::Jpeg) => strip_jpeg(data),
+        Ok(image::ImageFormat::Png) => strip_png(data),
+        Ok(image::ImageFormat::WebP) => strip_webp(data),
+        Ok(image::ImageFormat::Tiff) => {
+            let img = image::load_from_memory_with_format(data, image::ImageFormat::Tiff)
+                .map_err(|e| format!("Failed to decode TIFF: {}", e))?;
+            let mut buf = Vec::new();
+            img.write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Tiff)
+                .map_err(|e| format!("Failed to encode TIFF: {}", e))?;
+            Ok(Some(buf))
+        }
+        // GIF, BMP etc. carry no EXIF
+        _ => Ok(None),
+    }
+}
+
+/// Drop APP1 (EXIF, XMP) and APP13 (IPTC) segments before the scan data.
+fn strip_jpeg(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let malformed = || "Malformed JPEG".to_string();
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+    let mut stripped = false;
+
+    while pos + 1 < data.len() {
+        if data[pos] != 0xFF {
+            return Err(malformed());
+        }
+        let marker = data[pos + 1];
+        match marker {
+            // Fill byte
+            0xFF => pos += 1,
+            // Standalone markers without a length
+            0x01 | 0xD0..=0xD7 => {
+                out.extend_from_slice(&data[pos..pos + 2]);
+                pos += 2;
+            }
+            // Start of scan / end of image: the rest is entropy-coded data
+            0xDA | 0xD9 => {
+                out.extend_from_slice(&data[pos..]);
+                break;
+            }
+            _ => {
+                let len_bytes = data.get(pos + 2..pos + 4).ok_or_else(malformed)?;
+                let len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+                let end = pos + 2 + len;
+                if len < 2 || end > data.len() {
+                    return Err(malformed());
+                }
+                if marker == 0xE1 || marker == 0xED {
+                    stripped = true;
+                } else {
+                    out.extend_from_slice(&data[pos..end]);
+                }
+                pos = end;
+            }
+        }
+    }
+
+    Ok(stripped.then_some(out))
+}
+
+/// Whether a PNG text chunk holds embedded EXIF/XMP/IPTC (by keyword).
+fn is_png_metadata_text(chunk_data: &[u8]) -> bool {
+    let keyword_end = chunk_data.iter().position(|&b| b == 0).unwrap_or(0);
+    let keyword = String::from_utf8_lossy(&chunk_data[..keyword_end]).to_lowercase();
+    keyword == "xml:com.adobe.xmp" || keyword.starts_with("raw profile type")
+}
+
+/// Drop `eXIf` chunks and text chunks carrying XMP or raw EXIF/IPTC profiles.
+fn strip_png(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    const SIGNATURE_LEN: usize = 8;
+    let malformed = || "Malformed PNG".to_string();
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(data.get(..SIGNATURE_LEN).ok_or_else(malformed)?);
+    let mut pos = SIGNATURE_LEN;
+    let mut stripped = false;
+
+    while pos < data.len() {
+        let header = data.get(pos..pos + 8).ok_or_else(malformed)?;
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let kind = &header[4..8];
+        // length + type + data + crc
+        let end = pos + 12 + len;
+        let chunk_data = data.get(pos + 8..pos + 8 + len).ok_or_else(malformed)?;
+        if end > data.len() {
+            return Err(malformed());
+        }
+
+        let drop = kind == b"eXIf"
+            || (matches!(kind, b"tEXt" | b"zTXt" | b"iTXt") && is_png_metadata_text(chunk_data));
+        if drop {
+            stripped = true;
+        } else {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+        if kind == b"IEND" {
+            break;
+        }
+    }
+
+    Ok(stripped.then_some(out))
+}
+
+/// Drop `EXIF` and `XMP ` chunks and clear the matching VP8X flags.
+fn strip_webp(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    const VP8X_EXIF_FLAG: u8 = 0x08;
+    const VP8X_XMP_FLAG: u8 = 0x04;
+    let malformed = || "Malformed WebP".to_string();
+    if data.len() < 12 || &data[8..12] != b"WEBP" {
+        return Err(malformed());
+    }
+
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&data[..12]);
+    let mut pos = 12;
+    let mut stripped = false;
+
+    while pos + 8 <= data.len() {
+        let kind = &data[pos..pos + 4];
+        let len = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+            as usize;
+        // Chunks are padded to an even size
+        let end = (pos + 8 + len + (len & 1)).min(data.len());
+        if pos + 8 + len > data.len() {
+            return Err(malformed());
+        }
+
+        if kind == b"EXIF" || kind == b"XMP " {
+            stripped = true;
+        } else {
+            let start = out.len();
+            out.extend_from_slice(&data[pos..end]);
+            if kind == b"VP8X" && len > 0 {
+                out[start + 8] &= !(VP8X_EXIF_FLAG | VP8X_XMP_FLAG);
+            }
+        }
+        pos = end;
+    }
+
+    if !stripped {
+        return Ok(None);
+    }
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Ok(Some(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::{Field, In, Rational, Tag, Value};
+    use image::ImageEncoder;
+
+    /// Raw EXIF block with camera, capture time and a GPS position (Tokyo).
+    fn sample_exif() -> Vec<u8> {
+        let ascii = |s: &str| Value::Ascii(vec![s.as_bytes().to_vec()]);
+        let dms = |d: u32, m: u32, s: u32| {
+            Value::Rational(vec![
+                Rational { num: d, denom: 1 },
+                Rational { num: m, denom: 1 },
+                Rational { num: s, denom: 1 },
+            ])
+        };
+        let fields = [
+            Field {
+                tag: Tag::Make,
+                ifd_num: In::PRIMARY,
+                value: ascii("Canon"),
+            },
+            Field {
+                tag: Tag::Model,
+                ifd_num: In::PRIMARY,
+                value: ascii("Canon EOS R5"),
+            },
+            Field {
+                tag: Tag::DateTimeOriginal,
+                ifd_num: In::PRIMARY,
+                value: ascii("2024:05:06 07:08:09"),
+            },
+            Field {
+                tag: Tag::GPSLatitudeRef,
+                ifd_num: In::PRIMARY,
+                value: ascii("N"),
+            },
+            Field {
+                tag: Tag::GPSLatitude,
+                ifd_num: In::PRIMARY,
+                value: dms(35, 40, 30),
+            },
+            Field {
+                tag: Tag::GPSLongitudeRef,
+                ifd_num: In::PRIMARY,
+                value: ascii("W"),
+            },
+            Field {
+                tag: Tag::GPSLongitude,
+                ifd_num: In::PRIMARY,
+                value: dms(139, 45, 0),
+            },
+        ];
+        let mut writer = exif::experimental::Writer::new();
+        for field in &fields {
+            writer.push_field(field);
+        }
+        let mut buf = Cursor::new(Vec::new());
+        writer.write(&mut buf, false).unwrap();
+        buf.into_inner()
+    }
+
+    fn pixels() -> image::RgbImage {
+        image::RgbImage::from_fn(8, 4, |x, _| image::Rgb([x as u8 * 30, 0, 0]))
+    }
+
+    fn encode_with_exif(format: image::ImageFormat) -> Vec<u8> {
+        let img = pixels();
+        let mut buf = Vec::new();
+        let (w, h) = img.dimensions();
+        match format {
+            image::ImageFormat::Jpeg => {
+                let mut enc = image::codecs::jpeg::JpegEncoder::new(&mut buf);
+                enc.set_exif_metadata(sample_exif()).unwrap();
+                enc.write_image(&img, w, h, image::ExtendedColorType::Rgb8)
+                    .unwrap();
+            }
+            image::ImageFormat::Png => {
+                let mut enc = image::codecs::png::PngEncoder::new(&mut buf);
+                enc.set_exif_metadata(sample_exif()).unwrap();
+                enc.write_image(&img, w, h, image::ExtendedColorType::Rgb8)
+                    .unwrap();
+            }
+            image::ImageFormat::WebP => {
+                let mut enc = image::codecs::webp::WebPEncoder::new_lossless(&mut buf);
+                enc.set_exif_metadata(sample_exif()).unwrap();
+                enc.write_image(&img, w, h, image::ExtendedColorType::Rgb8)
+                    .unwrap();
+            }
+            _ => unreachable!(),
+        }
+        buf
+    }
+
+    #[test]
+    fn test_extract_image_metadata() {
+        let data = encode_with_exif(image::ImageFormat::Jpeg);
+        let meta = extract_image_metadata(&data).unwrap();
+        assert_eq!((meta.width, meta.height), (8, 4));
+        assert_eq!(meta.format, "jpeg");
+        assert_eq!(meta.color_type, "rgb8");
+        assert_eq!(meta.camera.as_deref(), Some("Canon EOS R5"));
+        assert_eq!(meta.taken_at.as_deref(), Some("2024-05-06T07:08:09"));
+        let lat = meta.gps_latitude.unwrap();
+        let lon = meta.gps_longitude.unwrap();
+        assert!((lat - 35.675).abs() < 1e-6, "{}", lat);
+        assert!((lon + 139.75).abs() < 1e-6, "{}", lon);
+    }
+
+    #[test]
+    fn test_extract_without_exif_and_invalid() {
+        let mut buf = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::new(3, 2))
+            .write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)
+            .unwrap();
+        let meta = extract_image_metadata(&buf).unwrap();
+        assert_eq!((meta.width, meta.height), (3, 2));
+        assert_eq!(meta.format, "png");
+        assert_eq!(meta.color_type, "rgba8");
+        assert!(meta.camera.is_none() && meta.gps_latitude.is_none());
+
+        assert!(extract_image_metadata(b"not an image").is_none());
+    }
+
+    #[test]
+    fn test_strip_removes_exif_keeps_pixels() {
+        for format in [
+            image::ImageFormat::Jpeg,
+            image::ImageFormat::Png,
+            image::ImageFormat::WebP,
+        ] {
+            let data = encode_with_exif(format);
+            assert!(extract_image_metadata(&data)
+                .unwrap()
+                .gps_latitude
+                .is_some());
+
+            let stripped = strip_image_metadata(&data).unwrap().expect("stripped");
+            let meta = extract_image_metadata(&stripped).unwrap();
+            assert!(meta.camera.is_none(), "{:?}", format);
+            assert!(meta.gps_latitude.is_none(), "{:?}", format);
+            assert_eq!((meta.width, meta.height), (8, 4));
+
+            let before = image::load_from_memory(&data).unwrap().to_rgb8();
+            let after = image::load_from_memory(&stripped).unwrap().to_rgb8();
+            assert_eq!(before, after, "{:?}", format);
+
+            // Nothing left to strip the second time
+            assert!(strip_image_metadata(&stripped).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_strip_passthrough_and_errors() {
+        let mut gif = Vec::new();
+        image::DynamicImage::ImageRgb8(pixels())
+            .write_to(&mut Cursor::new(&mut gif), image::ImageFormat::Gif)
+            .unwrap();
+        assert!(strip_image_metadata(&gif).unwrap().is_none());
+
+        let jpeg = encode_with_exif(image::ImageFormat::Jpeg);
+        assert!(strip_image_metadata(&jpeg[..40]).is_err());
+    }
+}
@@ -1,12 +1,21 @@
+use crate::db::models::{AppCaptureRule, ContentType};
+use crate::platform::SourceApp;
 use sha2::{Digest, Sha256};
-use std::io::Cursor;
+use std::path::PathBuf;
+use unicode_normalization::UnicodeNormalization;
+
+pub mod ignore;
+pub mod metadata;
+#[cfg(target_os = "linux")]
+pub mod owner;
+pub mod pause;
+#[cfg(target_os = "linux")]
+pub mod primary;
+pub mod sensitive;
 
 /// Default max item size: 10MB
 pub const DEFAULT_MAX_ITEM_SIZE_MB: usize = 10;
 
-/// Thumbnail max width in pixels.
-const THUMBNAIL_WIDTH: u32 = 400;
-
 /// Compute SHA-256 hash of content bytes, returning hex string.
 pub fn compute_hash(data: &[u8]) -> String {
     let mut hasher = Sha256::new();
@@ -14,58 +23,230 @@ pub fn compute_hash(data: &[u8]) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Setting key: how text content is hashed for dedup (`exact` | `normalized`).
+pub const TEXT_DEDUP_MODE_KEY: &str = "text_dedup_mode";
+
+/// How text copies are compared for dedup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDedupMode {
+    /// Hash the raw bytes.
+    Exact,
+    /// Hash `normalize_text` output, so copies differing only in line endings,
+    /// trailing whitespace or Unicode normalization are treated as one.
+    Normalized,
+}
+
+impl TextDedupMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("normalized") => Self::Normalized,
+            _ => Self::Exact,
+        }
+    }
+}
+
+/// Canonical form of text for dedup: NFC, LF line endings, no trailing
+/// whitespace on any line and no leading/trailing blank lines.
+/// Only used for hashing; the original text is what gets stored and pasted.
+pub fn normalize_text(text: &str) -> String {
+    let nfc: String = text.nfc().collect();
+    let unified = nfc.replace("\r\n", "\n").replace('\r', "\n");
+    let lines: Vec<&str> = unified.lines().map(str::trim_end).collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+/// Dedup hash for text content under the given mode. Falls back to the raw
+/// bytes when the content is not valid UTF-8.
+pub fn compute_text_hash(data: &[u8], mode: TextDedupMode) -> String {
+    match (mode, std::str::from_utf8(data)) {
+        (TextDedupMode::Normalized, Ok(text)) => compute_hash(normalize_text(text).as_bytes()),
+        _ => compute_hash(data),
+    }
+}
+
+/// Setting key: what to do with images perceptually close to a stored one (`off` | `bump` | `group`).
+pub const NEAR_DUPLICATE_MODE_KEY: &str = "near_duplicate_mode";
+
+/// Setting key: max dHash Hamming distance (0-64) treated as a near-duplicate.
+pub const SIMILARITY_THRESHOLD_KEY: &str = "image_similarity_threshold";
+
+/// Default near-duplicate threshold: tolerates re-encoding and recompression.
+pub const DEFAULT_SIMILARITY_THRESHOLD: u32 = 4;
+
+/// Handling of near-duplicate images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NearDuplicateMode {
+    /// Only exact content hashes are deduplicated.
+    Off,
+    /// Bump the existing item instead of storing the new copy.
+    Bump,
+    /// Store the new copy as a variant of the existing item.
+    Group,
+}
+
+impl NearDuplicateMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("bump") => Self::Bump,
+            Some("group") => Self::Group,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// Setting key: what to do with copies a password manager marks as secret (`skip` | `mask`).
+pub const CONCEALED_MODE_KEY: &str = "concealed_mode";
+
+/// Setting key: seconds before a sensitive item is deleted.
+pub const SENSITIVE_TTL_KEY: &str = "sensitive_ttl_secs";
+
+/// Default sensitive item lifetime, about as long as password managers keep secrets.
+pub const DEFAULT_SENSITIVE_TTL_SECS: i64 = 60;
+
+/// Clipboard types password managers add to mark a secret: KDE/Linux, the
+/// nspasteboard.org conventions on macOS and the Windows clipboard history opt-out.
+const CONCEALED_MARKERS: &[&str] = &[
+    "x-kde-passwordManagerHint",
+    "org.nspasteboard.ConcealedType",
+    "org.nspasteboard.TransientType",
+    "ExcludeClipboardContentFromMonitorProcessing",
+];
+
+/// Handling of copies marked as secret by their source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcealedMode {
+    /// Never record them.
+    Skip,
+    /// Record them as sensitive items: masked, unsearchable and deleted after the TTL.
+    Mask,
+}
+
+impl ConcealedMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("mask") => Self::Mask,
+            _ => Self::Skip,
+        }
+    }
+}
+
+/// Whether the clipboard types on offer include a password manager's secret marker.
+pub fn is_concealed<S: AsRef<str>>(types: &[S]) -> bool {
+    types
+        .iter()
+        .any(|t| CONCEALED_MARKERS.contains(&t.as_ref()))
+}
+
+/// Perceptual difference hash (dHash): a 9x8 grayscale downscale where each
+/// bit records whether a pixel is brighter than its right neighbour. Re-encoded
+/// or recompressed copies of an image land within a few bits of each other.
+pub fn compute_dhash(img: &image::DynamicImage) -> u64 {
+    let small = img
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | brighter as u64;
+        }
+    }
+    hash
+}
+
+/// Index of the candidate hash closest to `hash` within `threshold` bits.
+/// Ties go to the earliest candidate.
+pub fn closest_dhash(hash: u64, candidates: &[u64], threshold: u32) -> Option<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, (hash ^ c).count_ones()))
+        .filter(|&(_, distance)| distance <= threshold)
+        .min_by_key(|&(i, distance)| (distance, i))
+        .map(|(i, _)| i)
+}
+
+/// Classify copied text: a lone http(s) URL is a link (stored trimmed),
+/// anything else plain text.
+pub fn classify_text(text: String) -> (ContentType, String) {
+    let trimmed = text.trim();
+    if let Ok(parsed) = url::Url::parse(trimmed) {
+        if matches!(parsed.scheme(), "http" | "https") {
+            return (ContentType::Link, trimmed.to_string());
+        }
+    }
+    (ContentType::PlainText, text)
+}
+
+/// Whether copies from `source` should be recorded under `rules`. Exclude rules
+/// always win; once any include rule exists, only matching apps are recorded
+/// (an unidentified app then matches nothing).
+pub fn app_capture_allowed(rules: &[AppCaptureRule], source: &SourceApp) -> bool {
+    let matching = |action: &str| {
+        rules
+            .iter()
+            .any(|r| r.action == action && source.matches(&r.app))
+    };
+    if matching("exclude") {
+        return false;
+    }
+    !rules.iter().any(|r| r.action == "include") || matching("include")
+}
+
 /// Check if content size exceeds the limit.
 pub fn exceeds_size_limit(size: usize, limit_mb: usize) -> bool {
     size > limit_mb * 1024 * 1024
 }
 
-/// Generate a thumbnail from image bytes.
-/// Returns (thumbnail_bytes, original_width, original_height).
-/// The thumbnail is resized to THUMBNAIL_WIDTH while maintaining aspect ratio.
-/// Output format is PNG.
-pub fn generate_thumbnail(image_data: &[u8]) -> Result<Vec<u8>, String> {
-    let img =
-        image::load_from_memory(image_data).map_err(|e| format!("Failed to load image: {}", e))?;
-
-    let (w, h) = (img.width(), img.height());
-
-    let thumb = if w > THUMBNAIL_WIDTH {
-        let new_height = (THUMBNAIL_WIDTH as f64 / w as f64 * h as f64) as u32;
-        img.resize(
-            THUMBNAIL_WIDTH,
-            new_height,
-            image::imageops::FilterType::Lanczos3,
-        )
-    } else {
-        img
-    };
-
-    let mut buf = Vec::new();
-    thumb
-        .write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)
-        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+/// Detect the encoded format of clipboard image bytes.
+/// Returns `(extension, mime_type)`; unrecognized data falls back to PNG,
+/// which is what the clipboard plugin produces when no original is available.
+pub fn detect_image_format(image_data: &[u8]) -> (&'static str, &'static str) {
+    match image::guess_format(image_data) {
+        Ok(image::ImageFormat::Jpeg) => ("jpg", "image/jpeg"),
+        Ok(image::ImageFormat::Gif) => ("gif", "image/gif"),
+        Ok(image::ImageFormat::WebP) => ("webp", "image/webp"),
+        Ok(image::ImageFormat::Bmp) => ("bmp", "image/bmp"),
+        Ok(image::ImageFormat::Tiff) => ("tiff", "image/tiff"),
+        _ => ("png", "image/png"),
+    }
+}
 
-    Ok(buf)
+/// Content-addressed location of an original image: `images/{hash[0..2]}/{hash}.{ext}`.
+pub fn image_store_path(app_data_dir: &std::path::Path, hash: &str, ext: &str) -> PathBuf {
+    let shard = hash.get(..2).unwrap_or("00");
+    app_data_dir
+        .join("images")
+        .join(shard)
+        .join(format!("{}.{}", hash, ext))
 }
 
-/// Save original image to filesystem.
-/// Returns the saved file path.
+/// Held while a copy saves an original through to its item insert, and while
+/// released originals go from `image_blobs` removal to unlink. The two never
+/// interleave, so a file being released can't be reused by a concurrent save of
+/// the same image and then deleted under its new item.
+pub static IMAGE_STORE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Save original image to content-addressed storage.
+/// Identical content maps to the same file, so an existing file is reused
+/// instead of being written again. Returns the file path.
 pub fn save_original_image(
     app_data_dir: &std::path::Path,
     image_data: &[u8],
+    hash: &str,
     ext: &str,
 ) -> Result<String, String> {
-    let now = chrono::Utc::now();
-    let dir = app_data_dir
-        .join("images")
-        .join(now.format("%Y-%m").to_string());
+    let path = image_store_path(app_data_dir, hash, ext);
 
-    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create image dir: {}", e))?;
+    if !path.exists() {
+        let dir = path.parent().ok_or("Invalid image path")?;
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create image dir: {}", e))?;
 
-    let filename = format!("{}.{}", uuid::Uuid::new_v4(), ext);
-    let path = dir.join(&filename);
-
-    std::fs::write(&path, image_data).map_err(|e| format!("Failed to write image: {}", e))?;
+        // Write to a temp file first so a crash never leaves a truncated blob behind
+        let tmp = path.with_extension(format!("{}.tmp", ext));
+        std::fs::write(&tmp, image_data).map_err(|e| format!("Failed to write image: {}", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to move image: {}", e))?;
+    }
 
     Ok(path.to_string_lossy().to_string())
 }
@@ -73,6 +254,7 @@ pub fn save_original_image(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Cursor;
 
     #[test]
     fn test_compute_hash() {
@@ -85,6 +267,66 @@ mod tests {
         assert_eq!(hash1.len(), 64); // SHA-256 hex length
     }
 
+    #[test]
+    fn test_classify_text() {
+        assert_eq!(
+            classify_text("  https://example.com/a?b=1\n".into()),
+            (ContentType::Link, "https://example.com/a?b=1".into())
+        );
+        assert_eq!(
+            classify_text("ftp://example.com".into()),
+            (ContentType::PlainText, "ftp://example.com".into())
+        );
+        assert_eq!(
+            classify_text("see https://example.com".into()),
+            (ContentType::PlainText, "see https://example.com".into())
+        );
+    }
+
+    #[test]
+    fn test_is_concealed() {
+        assert!(is_concealed(&["text/plain", "x-kde-passwordManagerHint"]));
+        assert!(is_concealed(&[
+            "public.utf8-plain-text",
+            "org.nspasteboard.ConcealedType"
+        ]));
+        assert!(!is_concealed(&["text/plain", "UTF8_STRING"]));
+        assert!(!is_concealed::<&str>(&[]));
+        assert_eq!(
+            ConcealedMode::from_setting(Some("mask")),
+            ConcealedMode::Mask
+        );
+        assert_eq!(ConcealedMode::from_setting(None), ConcealedMode::Skip);
+    }
+
+    #[test]
+    fn test_app_capture_allowed() {
+        let rule = |app: &str, action: &str| AppCaptureRule {
+            app: app.into(),
+            action: action.into(),
+            ..Default::default()
+        };
+        let keepass = SourceApp::from_executable("/usr/bin/keepassxc", Some("KeePassXC"));
+        let editor = SourceApp::from_executable("/usr/bin/gedit", Some("Gedit"));
+        let unknown = SourceApp::default();
+
+        let denylist = [rule("keepassxc", "exclude")];
+        assert!(!app_capture_allowed(&denylist, &keepass));
+        assert!(app_capture_allowed(&denylist, &editor));
+        assert!(app_capture_allowed(&denylist, &unknown));
+
+        let allowlist = [rule("gedit", "include"), rule("KeePassXC", "exclude")];
+        assert!(app_capture_allowed(&allowlist, &editor));
+        assert!(!app_capture_allowed(&allowlist, &keepass));
+        assert!(!app_capture_allowed(&allowlist, &unknown));
+
+        assert!(!app_capture_allowed(
+            &[rule("gedit", "include"), rule("gedit", "exclude")],
+            &editor
+        ));
+        assert!(app_capture_allowed(&[], &unknown));
+    }
+
     #[test]
     fn test_exceeds_size_limit() {
         let limit_mb = DEFAULT_MAX_ITEM_SIZE_MB;
@@ -97,40 +339,31 @@ mod tests {
         assert!(exceeds_size_limit(20 * 1024 * 1024 + 1, 20));
     }
 
-    #[test]
-    fn test_generate_thumbnail() {
-        // Create a simple 800x600 red image
-        let img = image::RgbImage::from_fn(800, 600, |_, _| image::Rgb([255u8, 0, 0]));
+    fn encode(format: image::ImageFormat) -> Vec<u8> {
+        let img = image::RgbImage::from_fn(4, 4, |_, _| image::Rgb([0u8, 0, 255]));
         let mut buf = Vec::new();
-        let dyn_img = image::DynamicImage::ImageRgb8(img);
-        dyn_img
-            .write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)
+        image::DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut buf), format)
             .unwrap();
-
-        let thumb_bytes = generate_thumbnail(&buf).unwrap();
-        assert!(!thumb_bytes.is_empty());
-
-        // Verify thumbnail dimensions
-        let thumb = image::load_from_memory(&thumb_bytes).unwrap();
-        assert_eq!(thumb.width(), 400);
-        assert_eq!(thumb.height(), 300); // 400/800 * 600 = 300
+        buf
     }
 
     #[test]
-    fn test_thumbnail_small_image_no_resize() {
-        // Create a 200x100 image (smaller than THUMBNAIL_WIDTH)
-        let img = image::RgbImage::from_fn(200, 100, |_, _| image::Rgb([0u8, 255, 0]));
-        let mut buf = Vec::new();
-        let dyn_img = image::DynamicImage::ImageRgb8(img);
-        dyn_img
-            .write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)
-            .unwrap();
-
-        let thumb_bytes = generate_thumbnail(&buf).unwrap();
-        let thumb = image::load_from_memory(&thumb_bytes).unwrap();
-        // Should not be resized since it's smaller than 400px
-        assert_eq!(thumb.width(), 200);
-        assert_eq!(thumb.height(), 100);
+    fn test_detect_image_format() {
+        assert_eq!(
+            detect_image_format(&encode(image::ImageFormat::Png)),
+            ("png", "image/png")
+        );
+        assert_eq!(
+            detect_image_format(&encode(image::ImageFormat::Jpeg)),
+            ("jpg", "image/jpeg")
+        );
+        assert_eq!(
+            detect_image_format(&encode(image::ImageFormat::Gif)),
+            ("gif", "image/gif")
+        );
+        // Unknown bytes fall back to PNG
+        assert_eq!(detect_image_format(b"not an image"), ("png", "image/png"));
     }
 
     #[test]
@@ -139,28 +372,133 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp_dir);
 
         let image_data = vec![0u8; 100];
-        let path = save_original_image(&temp_dir, &image_data, "png").unwrap();
+        let hash = compute_hash(&image_data);
+        let path = save_original_image(&temp_dir, &image_data, &hash, "png").unwrap();
 
         assert!(std::path::Path::new(&path).exists());
-        assert!(path.ends_with(".png"));
+        assert!(path.ends_with(&format!("{}.png", hash)));
         assert!(path.contains("images/"));
+        assert!(path.contains(&format!("/{}/", &hash[..2])));
 
         // Cleanup
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
 
     #[test]
-    fn test_generate_thumbnail_invalid_data() {
-        let invalid_bytes = b"this is not an image";
-        let result = generate_thumbnail(invalid_bytes);
-        assert!(result.is_err());
+    fn test_save_original_image_dedups_by_hash() {
+        let temp_dir = std::env::temp_dir().join("recopy-test-images-dedup");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+
+        let image_data = vec![7u8; 64];
+        let hash = compute_hash(&image_data);
+        let first = save_original_image(&temp_dir, &image_data, &hash, "png").unwrap();
+        let second = save_original_image(&temp_dir, &image_data, &hash, "png").unwrap();
+        assert_eq!(first, second);
+
+        // Only one file (and no leftover temp file) in the shard directory
+        let shard_dir = std::path::Path::new(&first).parent().unwrap();
+        assert_eq!(std::fs::read_dir(shard_dir).unwrap().count(), 1);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_dhash_survives_recompression() {
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(320, 200, |x, y| {
+            image::Rgb([
+                (x % 256) as u8,
+                (y * 255 / 200) as u8,
+                ((x * y) % 251) as u8,
+            ])
+        }));
+        let hash = compute_dhash(&img);
+
+        let mut jpeg = Vec::new();
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 40);
+        img.write_with_encoder(encoder).unwrap();
+        let recompressed = image::load_from_memory(&jpeg).unwrap();
+        let scaled = img.resize(160, 100, image::imageops::FilterType::Lanczos3);
+
+        assert!((hash ^ compute_dhash(&recompressed)).count_ones() <= DEFAULT_SIMILARITY_THRESHOLD);
+        assert!((hash ^ compute_dhash(&scaled)).count_ones() <= DEFAULT_SIMILARITY_THRESHOLD);
+
+        let flipped = img.fliph();
+        assert!((hash ^ compute_dhash(&flipped)).count_ones() > DEFAULT_SIMILARITY_THRESHOLD);
+    }
+
+    #[test]
+    fn test_closest_dhash() {
+        let candidates = [0b1111_0000u64, 0b1111_0001, 0b1111_0011, 0xFFFF];
+        assert_eq!(closest_dhash(0b1111_0001, &candidates, 4), Some(1));
+        assert_eq!(closest_dhash(0b1111_0111, &candidates, 1), Some(2));
+        // Equal distance: earliest wins
+        assert_eq!(closest_dhash(0b1111_0010, &candidates, 4), Some(0));
+        assert_eq!(closest_dhash(u64::MAX, &candidates, 4), None);
+        assert_eq!(closest_dhash(1, &[], 64), None);
+    }
+
+    #[test]
+    fn test_near_duplicate_mode_from_setting() {
+        assert_eq!(
+            NearDuplicateMode::from_setting(Some("bump")),
+            NearDuplicateMode::Bump
+        );
+        assert_eq!(
+            NearDuplicateMode::from_setting(Some("group")),
+            NearDuplicateMode::Group
+        );
+        assert_eq!(
+            NearDuplicateMode::from_setting(Some("off")),
+            NearDuplicateMode::Off
+        );
+        assert_eq!(
+            NearDuplicateMode::from_setting(None),
+            NearDuplicateMode::Off
+        );
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("a  \r\nb\t\r\n"), "a\nb");
+        assert_eq!(normalize_text("a\rb"), "a\nb");
+        assert_eq!(normalize_text("\n\n  indented\n\n"), "  indented");
+        // Decomposed "é" (e + combining acute) composes to U+00E9
+        assert_eq!(normalize_text("cafe\u{301}"), "caf\u{e9}");
+        assert_eq!(normalize_text(" \n\t"), "");
     }
 
     #[test]
-    fn test_generate_thumbnail_empty_data() {
-        let empty_bytes: &[u8] = b"";
-        let result = generate_thumbnail(empty_bytes);
-        assert!(result.is_err());
+    fn test_compute_text_hash_modes() {
+        let lf = b"fn main() {\n    x();\n}";
+        let crlf = b"fn main() {  \r\n    x();\r\n}\r\n";
+
+        assert_ne!(
+            compute_text_hash(lf, TextDedupMode::Exact),
+            compute_text_hash(crlf, TextDedupMode::Exact)
+        );
+        assert_eq!(
+            compute_text_hash(lf, TextDedupMode::Normalized),
+            compute_text_hash(crlf, TextDedupMode::Normalized)
+        );
+        // Already-normalized text hashes the same in both modes
+        assert_eq!(
+            compute_text_hash(lf, TextDedupMode::Exact),
+            compute_text_hash(lf, TextDedupMode::Normalized)
+        );
+        // Invalid UTF-8 falls back to the raw bytes
+        assert_eq!(
+            compute_text_hash(&[0xff, 0x20], TextDedupMode::Normalized),
+            compute_hash(&[0xff, 0x20])
+        );
+        assert_eq!(
+            TextDedupMode::from_setting(Some("normalized")),
+            TextDedupMode::Normalized
+        );
+        assert_eq!(
+            TextDedupMode::from_setting(Some("bogus")),
+            TextDedupMode::Exact
+        );
+        assert_eq!(TextDedupMode::from_setting(None), TextDedupMode::Exact);
     }
 
     #[test]
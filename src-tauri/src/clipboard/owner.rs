@@ -0,0 +1,607 @@
+//! Clipboard ownership on Linux, so copied content survives the source app exiting.
+//!
+//! After an item is stored, every target the current owner offers is read and
+//! Recopy takes over CLIPBOARD, serving those targets itself. X11 is served from a
+//! dedicated thread; on Wayland `wl-clipboard-rs` serves through data-control.
+
+use crate::platform::{detect_session, Session};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+/// Settings key enabling clipboard ownership (`true` | `false`).
+pub const CLIPBOARD_OWNER_KEY: &str = "clipboard_owner";
+
+/// How long the source app gets to hand over each target.
+const TARGET_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Targets that describe the selection or trigger side effects rather than hold content.
+const META_TARGETS: &[&str] = &[
+    "TARGETS",
+    "MULTIPLE",
+    "TIMESTAMP",
+    "SAVE_TARGETS",
+    "DELETE",
+    "INSERT_SELECTION",
+    "INSERT_PROPERTY",
+    "LENGTH",
+];
+
+/// Offered by password managers for secrets. Such content is left to expire with its
+/// source instead of being kept alive.
+const PASSWORD_HINT: &str = "x-kde-passwordManagerHint";
+
+/// Whether a target carries content worth re-serving.
+fn should_capture(name: &str) -> bool {
+    !META_TARGETS.contains(&name) && name != PASSWORD_HINT
+}
+
+/// Whether the offer marks its content as a secret.
+fn is_password(names: &[String]) -> bool {
+    names.iter().any(|n| n == PASSWORD_HINT)
+}
+
+/// Ownership setting and the request channel to the owner thread.
+pub struct ClipboardOwner {
+    enabled: AtomicBool,
+    /// Byte budget of each take-over request, consumed by the owner thread.
+    requests: Mutex<Option<mpsc::Sender<usize>>>,
+}
+
+impl ClipboardOwner {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            requests: Mutex::new(None),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Take over CLIPBOARD with at most `limit` bytes of captured data.
+    /// No-op unless enabled; the owner thread starts on first use.
+    pub fn take_over(&self, limit: usize) {
+        if !self.enabled() {
+            return;
+        }
+        let mut requests = self.requests.lock().unwrap();
+        if requests.is_none() {
+            *requests = spawn_owner();
+        }
+        if let Some(tx) = requests.as_ref() {
+            if tx.send(limit).is_err() {
+                *requests = None;
+            }
+        }
+    }
+}
+
+fn spawn_owner() -> Option<mpsc::Sender<usize>> {
+    let (tx, rx) = mpsc::channel::<usize>();
+    let session = detect_session();
+    let spawned = std::thread::Builder::new()
+        .name("clipboard-owner".into())
+        .spawn(move || {
+            match session {
+                Session::Wayland => wayland::run(&rx),
+                Session::X11 | Session::Unknown => match x11::Owner::new() {
+                    Ok(mut owner) => owner.run(&rx),
+                    Err(e) => log::warn!("Clipboard ownership unavailable: {}", e),
+                },
+            }
+            // Keep accepting requests so an unavailable backend is only reported once
+            for _ in rx {}
+        });
+    match spawned {
+        Ok(_) => Some(tx),
+        Err(e) => {
+            log::error!("Failed to start clipboard owner: {}", e);
+            None
+        }
+    }
+}
+
+/// Latest pending request, dropping ones queued while the previous take-over ran.
+fn latest(first: usize, rx: &mpsc::Receiver<usize>) -> usize {
+    rx.try_iter().last().unwrap_or(first)
+}
+
+mod x11 {
+    use super::{is_password, latest, should_capture, TARGET_TIMEOUT};
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+    use x11rb::connection::{Connection, RequestConnection};
+    use x11rb::protocol::xproto::{
+        Atom, AtomEnum, ConnectionExt as _, CreateWindowAux, EventMask, PropMode, Property,
+        SelectionNotifyEvent, SelectionRequestEvent, Window, WindowClass, SELECTION_NOTIFY_EVENT,
+    };
+    use x11rb::protocol::Event;
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::wrapper::ConnectionExt as _;
+    use x11rb::{CURRENT_TIME, NONE};
+
+    /// How often requests from other clients are answered while idle.
+    const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+    x11rb::atom_manager! {
+        Atoms: AtomsCookie {
+            CLIPBOARD,
+            TARGETS,
+            INCR,
+            RECOPY_TRANSFER,
+        }
+    }
+
+    /// A target served while Recopy owns CLIPBOARD.
+    struct Served {
+        target: Atom,
+        ty: Atom,
+        format: u8,
+        data: Vec<u8>,
+    }
+
+    pub(super) struct Owner {
+        conn: RustConnection,
+        window: Window,
+        atoms: Atoms,
+        served: Vec<Served>,
+    }
+
+    type Result<T> = std::result::Result<T, String>;
+
+    fn err(e: impl std::fmt::Display) -> String {
+        e.to_string()
+    }
+
+    impl Owner {
+        pub(super) fn new() -> Result<Self> {
+            let (conn, screen) = x11rb::connect(None).map_err(err)?;
+            let root = conn.setup().roots[screen].root;
+            let window = conn.generate_id().map_err(err)?;
+            conn.create_window(
+                0,
+                window,
+                root,
+                0,
+                0,
+                1,
+                1,
+                0,
+                WindowClass::INPUT_ONLY,
+                0,
+                &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+            )
+            .map_err(err)?;
+            let atoms = Atoms::new(&conn).map_err(err)?.reply().map_err(err)?;
+            conn.flush().map_err(err)?;
+            Ok(Self {
+                conn,
+                window,
+                atoms,
+                served: Vec::new(),
+            })
+        }
+
+        pub(super) fn run(&mut self, rx: &mpsc::Receiver<usize>) {
+            loop {
+                match rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(limit) => {
+                        if let Err(e) = self.take_over(latest(limit, rx)) {
+                            log::warn!("Failed to take clipboard ownership: {}", e);
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+                if let Err(e) = self.dispatch_pending() {
+                    log::warn!("Clipboard owner connection failed: {}", e);
+                    return;
+                }
+            }
+        }
+
+        fn dispatch_pending(&mut self) -> Result<()> {
+            while let Some(event) = self.conn.poll_for_event().map_err(err)? {
+                self.handle(event)?;
+            }
+            Ok(())
+        }
+
+        fn handle(&mut self, event: Event) -> Result<()> {
+            match event {
+                Event::SelectionRequest(req) => self.serve(&req),
+                Event::SelectionClear(e) if e.selection == self.atoms.CLIPBOARD => {
+                    self.served.clear();
+                    Ok(())
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Wait for an event matching `pred`, answering other requests meanwhile.
+        fn wait_for(
+            &mut self,
+            deadline: Instant,
+            pred: impl Fn(&Event) -> bool,
+        ) -> Result<Option<Event>> {
+            loop {
+                while let Some(event) = self.conn.poll_for_event().map_err(err)? {
+                    if pred(&event) {
+                        return Ok(Some(event));
+                    }
+                    self.handle(event)?;
+                }
+                if Instant::now() >= deadline {
+                    return Ok(None);
+                }
+                std::thread::sleep(Duration::from_millis(2));
+            }
+        }
+
+        /// Read one target of CLIPBOARD from its owner, as (type, format, data).
+        /// Returns None if the owner refuses, times out or the data exceeds `budget`.
+        fn convert(&mut self, target: Atom, budget: usize) -> Result<Option<(Atom, u8, Vec<u8>)>> {
+            let (window, prop) = (self.window, self.atoms.RECOPY_TRANSFER);
+            self.conn
+                .convert_selection(window, self.atoms.CLIPBOARD, target, prop, CURRENT_TIME)
+                .map_err(err)?;
+            self.conn.flush().map_err(err)?;
+
+            let deadline = Instant::now() + TARGET_TIMEOUT;
+            let notify = self.wait_for(
+                deadline,
+                |e| matches!(e, Event::SelectionNotify(n) if n.requestor == window),
+            )?;
+            match notify {
+                Some(Event::SelectionNotify(n)) if n.property != NONE => {}
+                _ => return Ok(None),
+            }
+
+            // Check the size before fetching so oversized targets are never transferred
+            let head = self
+                .conn
+                .get_property(false, window, prop, AtomEnum::ANY, 0, 0)
+                .map_err(err)?
+                .reply()
+                .map_err(err)?;
+            if head.type_ == self.atoms.INCR {
+                return self.read_incr(budget, deadline);
+            }
+            let size = head.bytes_after as usize;
+            if size > budget {
+                self.conn.delete_property(window, prop).map_err(err)?;
+                return Ok(None);
+            }
+            let reply = self
+                .conn
+                .get_property(
+                    true,
+                    window,
+                    prop,
+                    AtomEnum::ANY,
+                    0,
+                    size.div_ceil(4) as u32,
+                )
+                .map_err(err)?
+                .reply()
+                .map_err(err)?;
+            Ok(Some((reply.type_, reply.format, reply.value)))
+        }
+
+        /// Incremental transfer: the owner sends chunks, each acknowledged by deleting
+        /// the property, until an empty chunk marks the end.
+        fn read_incr(
+            &mut self,
+            budget: usize,
+            deadline: Instant,
+        ) -> Result<Option<(Atom, u8, Vec<u8>)>> {
+            let (window, prop) = (self.window, self.atoms.RECOPY_TRANSFER);
+            self.conn.delete_property(window, prop).map_err(err)?;
+            self.conn.flush().map_err(err)?;
+
+            let mut data = Vec::new();
+            let mut kind = None;
+            loop {
+                let chunk_ready = self.wait_for(deadline, |e| {
+                    matches!(e, Event::PropertyNotify(p)
+                        if p.window == window && p.atom == prop && p.state == Property::NEW_VALUE)
+                })?;
+                if chunk_ready.is_none() {
+                    return Ok(None);
+                }
+                let chunk = self
+                    .conn
+                    .get_property(true, window, prop, AtomEnum::ANY, 0, u32::MAX / 4)
+                    .map_err(err)?
+                    .reply()
+                    .map_err(err)?;
+                self.conn.flush().map_err(err)?;
+                if chunk.value.is_empty() {
+                    return Ok(kind.map(|(ty, format)| (ty, format, data)));
+                }
+                if data.len() + chunk.value.len() > budget {
+                    // Stop acknowledging; the owner gives up on its own
+                    return Ok(None);
+                }
+                kind = Some((chunk.type_, chunk.format));
+                data.extend_from_slice(&chunk.value);
+            }
+        }
+
+        fn take_over(&mut self, limit: usize) -> Result<()> {
+            let clipboard = self.atoms.CLIPBOARD;
+            let owner = self
+                .conn
+                .get_selection_owner(clipboard)
+                .map_err(err)?
+                .reply()
+                .map_err(err)?
+                .owner;
+            if owner == NONE || owner == self.window {
+                return Ok(());
+            }
+
+            let Some((_, _, raw)) = self.convert(self.atoms.TARGETS, limit)? else {
+                return Ok(());
+            };
+            let targets: Vec<Atom> = raw
+                .chunks_exact(4)
+                .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            let cookies = targets
+                .iter()
+                .map(|&t| self.conn.get_atom_name(t))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(err)?;
+            let mut names = Vec::with_capacity(cookies.len());
+            for cookie in cookies {
+                let name = cookie.reply().map_err(err)?.name;
+                names.push(String::from_utf8_lossy(&name).into_owned());
+            }
+            if is_password(&names) {
+                return Ok(());
+            }
+
+            let mut budget = limit;
+            let mut served = Vec::new();
+            for (target, name) in targets.into_iter().zip(&names) {
+                if !should_capture(name) || served.iter().any(|s: &Served| s.target == target) {
+                    continue;
+                }
+                if let Some((ty, format, data)) = self.convert(target, budget)? {
+                    budget -= data.len();
+                    served.push(Served {
+                        target,
+                        ty,
+                        format,
+                        data,
+                    });
+                }
+            }
+            if served.is_empty() {
+                return Ok(());
+            }
+
+            crate::set_skip_next_clipboard_change();
+            self.conn
+                .set_selection_owner(self.window, clipboard, CURRENT_TIME)
+                .map_err(err)?;
+            let now_owner = self
+                .conn
+                .get_selection_owner(clipboard)
+                .map_err(err)?
+                .reply()
+                .map_err(err)?
+                .owner;
+            if now_owner != self.window {
+                crate::clear_skip_next_clipboard_change();
+                return Err("Selection ownership was not granted".to_string());
+            }
+            log::info!("Took clipboard ownership ({} targets)", served.len());
+            self.served = served;
+            Ok(())
+        }
+
+        fn serve(&mut self, req: &SelectionRequestEvent) -> Result<()> {
+            // Obsolete clients leave the property unset and expect the target name
+            let property = if req.property == NONE {
+                req.target
+            } else {
+                req.property
+            };
+            let max_bytes = self.conn.maximum_request_bytes().saturating_sub(32);
+            let granted = if req.selection != self.atoms.CLIPBOARD || self.served.is_empty() {
+                false
+            } else if req.target == self.atoms.TARGETS {
+                let mut targets = vec![self.atoms.TARGETS];
+                targets.extend(self.served.iter().map(|s| s.target));
+                self.conn
+                    .change_property32(
+                        PropMode::REPLACE,
+                        req.requestor,
+                        property,
+                        AtomEnum::ATOM,
+                        &targets,
+                    )
+                    .map_err(err)?;
+                true
+            } else if let Some(s) = self.served.iter().find(|s| s.target == req.target) {
+                // Larger data would need an INCR transfer, which is not offered
+                if s.data.len() > max_bytes {
+                    false
+                } else {
+                    let format = if matches!(s.format, 8 | 16 | 32) {
+                        s.format
+                    } else {
+                        8
+                    };
+                    let units = s.data.len() / (format as usize / 8);
+                    self.conn
+                        .change_property(
+                            PropMode::REPLACE,
+                            req.requestor,
+                            property,
+                            s.ty,
+                            format,
+                            units as u32,
+                            &s.data,
+                        )
+                        .map_err(err)?;
+                    true
+                }
+            } else {
+                false
+            };
+
+            let notify = SelectionNotifyEvent {
+                response_type: SELECTION_NOTIFY_EVENT,
+                sequence: 0,
+                time: req.time,
+                requestor: req.requestor,
+                selection: req.selection,
+                target: req.target,
+                property: if granted { property } else { NONE },
+            };
+            self.conn
+                .send_event(false, req.requestor, EventMask::NO_EVENT, notify)
+                .map_err(err)?;
+            self.conn.flush().map_err(err)?;
+            Ok(())
+        }
+    }
+}
+
+mod wayland {
+    use super::{is_password, latest, should_capture, TARGET_TIMEOUT};
+    use std::io::Read;
+    use std::os::fd::AsRawFd;
+    use std::sync::mpsc;
+    use std::time::Instant;
+    use wl_clipboard_rs::{copy, paste};
+
+    pub(super) fn run(rx: &mpsc::Receiver<usize>) {
+        while let Ok(limit) = rx.recv() {
+            if let Err(e) = take_over(latest(limit, rx)) {
+                log::warn!("Failed to take clipboard ownership: {}", e);
+            }
+        }
+    }
+
+    /// Read a transfer pipe to the end, giving up past `budget` bytes or `TARGET_TIMEOUT`.
+    fn read_pipe(mut pipe: impl Read + AsRawFd, budget: usize) -> Option<Vec<u8>> {
+        let deadline = Instant::now() + TARGET_TIMEOUT;
+        let mut data = Vec::new();
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            let mut fd = libc::pollfd {
+                fd: pipe.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: `fd` is a valid pollfd for the duration of the call
+            let ready = unsafe { libc::poll(&mut fd, 1, wait.as_millis() as libc::c_int) };
+            if ready <= 0 {
+                return None;
+            }
+            match pipe.read(&mut buf) {
+                Ok(0) => return Some(data),
+                Ok(n) if data.len() + n <= budget => data.extend_from_slice(&buf[..n]),
+                _ => return None,
+            }
+        }
+    }
+
+    fn take_over(limit: usize) -> Result<(), String> {
+        let offered =
+            paste::get_mime_types_ordered(paste::ClipboardType::Regular, paste::Seat::Unspecified)
+                .map_err(|e| e.to_string())?;
+        if is_password(&offered) {
+            return Ok(());
+        }
+
+        let mut budget = limit;
+        let mut sources = Vec::new();
+        for mime in offered.into_iter().filter(|m| should_capture(m)) {
+            let pipe = match paste::get_contents(
+                paste::ClipboardType::Regular,
+                paste::Seat::Unspecified,
+                paste::MimeType::Specific(&mime),
+            ) {
+                Ok((pipe, _)) => pipe,
+                Err(e) => {
+                    log::debug!("Skipping clipboard type {}: {}", mime, e);
+                    continue;
+                }
+            };
+            if let Some(data) = read_pipe(pipe, budget) {
+                budget -= data.len();
+                sources.push(copy::MimeSource {
+                    source: copy::Source::Bytes(data.into()),
+                    mime_type: copy::MimeType::Specific(mime),
+                });
+            }
+        }
+        if sources.is_empty() {
+            return Ok(());
+        }
+
+        crate::set_skip_next_clipboard_change();
+        let mut options = copy::Options::new();
+        options.omit_additional_text_mime_types(true);
+        // Served from a background thread until another app copies
+        options.copy_multi(sources).map_err(|e| {
+            crate::clear_skip_next_clipboard_change();
+            e.to_string()
+        })?;
+        log::info!("Took clipboard ownership");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_capture() {
+        assert!(should_capture("UTF8_STRING"));
+        assert!(should_capture("text/html"));
+        assert!(should_capture("image/png"));
+        assert!(!should_capture("TARGETS"));
+        assert!(!should_capture("SAVE_TARGETS"));
+        assert!(!should_capture("DELETE"));
+        assert!(!should_capture(PASSWORD_HINT));
+    }
+
+    #[test]
+    fn test_is_password() {
+        assert!(!is_password(&["text/plain".into(), "UTF8_STRING".into()]));
+        assert!(is_password(&["text/plain".into(), PASSWORD_HINT.into()]));
+    }
+
+    #[test]
+    fn test_latest_request_wins() {
+        let (tx, rx) = mpsc::channel();
+        assert_eq!(latest(1, &rx), 1);
+        tx.send(2).unwrap();
+        tx.send(3).unwrap();
+        assert_eq!(latest(1, &rx), 3);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_disabled_owner_ignores_requests() {
+        let owner = ClipboardOwner::new(false);
+        owner.take_over(1024);
+        assert!(owner.requests.lock().unwrap().is_none());
+        owner.set_enabled(true);
+        assert!(owner.enabled());
+    }
+}
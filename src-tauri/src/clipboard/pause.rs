@@ -0,0 +1,128 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Capture state returned by `get_capture_status` and emitted as `capture-paused`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CaptureStatus {
+    pub paused: bool,
+    /// UTC time capture resumes on its own (`YYYY-MM-DD HH:MM:SS`);
+    /// None while paused until resumed by hand.
+    pub resumes_at: Option<String>,
+}
+
+#[derive(Default)]
+struct PauseInner {
+    paused: bool,
+    until: Option<Instant>,
+    resumes_at: Option<String>,
+    /// Bumped on every pause so a superseded auto-resume timer does nothing.
+    generation: u64,
+}
+
+impl PauseInner {
+    fn clear(&mut self) -> bool {
+        let was_paused = self.paused;
+        self.paused = false;
+        self.until = None;
+        self.resumes_at = None;
+        was_paused
+    }
+}
+
+/// Whether clipboard capture is paused (incognito), held for the app's lifetime
+/// so it outlives the panel being hidden and shown again.
+#[derive(Default)]
+pub struct CapturePause(Mutex<PauseInner>);
+
+impl CapturePause {
+    /// Pause capture, indefinitely or for `duration`. Returns a token for
+    /// `resume_if_current`, used by the auto-resume timer.
+    pub fn pause(&self, duration: Option<Duration>) -> u64 {
+        let mut inner = self.0.lock().unwrap();
+        inner.paused = true;
+        inner.until = duration.map(|d| Instant::now() + d);
+        inner.resumes_at = duration.map(|d| {
+            let at = chrono::Utc::now() + chrono::Duration::from_std(d).unwrap_or_default();
+            at.format("%Y-%m-%d %H:%M:%S").to_string()
+        });
+        inner.generation += 1;
+        inner.generation
+    }
+
+    /// Resume capture. Returns true if it was paused.
+    pub fn resume(&self) -> bool {
+        self.0.lock().unwrap().clear()
+    }
+
+    /// Resume only if no newer pause replaced the one that issued `generation`.
+    pub fn resume_if_current(&self, generation: u64) -> bool {
+        let mut inner = self.0.lock().unwrap();
+        inner.generation == generation && inner.clear()
+    }
+
+    /// Whether copies should be dropped right now. A timed pause that has run
+    /// out counts as resumed even before its timer fires.
+    pub fn is_paused(&self) -> bool {
+        let inner = self.0.lock().unwrap();
+        inner.paused && inner.until.map_or(true, |until| Instant::now() < until)
+    }
+
+    pub fn status(&self) -> CaptureStatus {
+        let inner = self.0.lock().unwrap();
+        CaptureStatus {
+            paused: inner.paused,
+            resumes_at: inner.resumes_at.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_and_resume() {
+        let pause = CapturePause::default();
+        assert!(!pause.is_paused());
+        assert!(!pause.resume());
+
+        pause.pause(None);
+        assert!(pause.is_paused());
+        assert_eq!(
+            pause.status(),
+            CaptureStatus {
+                paused: true,
+                resumes_at: None
+            }
+        );
+
+        assert!(pause.resume());
+        assert!(!pause.is_paused());
+        assert_eq!(pause.status(), CaptureStatus::default());
+    }
+
+    #[test]
+    fn test_timed_pause() {
+        let pause = CapturePause::default();
+        pause.pause(Some(Duration::from_secs(600)));
+        assert!(pause.is_paused());
+        assert!(pause.status().resumes_at.is_some());
+
+        // An elapsed pause no longer drops copies
+        pause.pause(Some(Duration::ZERO));
+        assert!(!pause.is_paused());
+    }
+
+    #[test]
+    fn test_stale_timer_does_not_resume() {
+        let pause = CapturePause::default();
+        let first = pause.pause(Some(Duration::from_secs(60)));
+        let second = pause.pause(None);
+
+        assert!(!pause.resume_if_current(first));
+        assert!(pause.is_paused());
+        assert!(pause.resume_if_current(second));
+        assert!(!pause.is_paused());
+    }
+}
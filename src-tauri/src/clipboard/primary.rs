@@ -0,0 +1,377 @@
+//! PRIMARY selection (select-to-copy, middle-click paste) on Linux.
+//!
+//! The clipboard plugin only watches CLIPBOARD, so PRIMARY is polled on a
+//! dedicated thread through `arboard` (X11, or the Wayland data-control protocol).
+
+use crate::commands::clipboard as clip_cmd;
+use crate::db::{models::SelectionOrigin, queries, DbPool};
+use arboard::{GetExtLinux, LinuxClipboardKind, SetExtLinux};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Settings key enabling capture of PRIMARY into history (`true` | `false`).
+pub const PRIMARY_CAPTURE_KEY: &str = "primary_capture";
+
+/// Settings key for mirroring between PRIMARY and CLIPBOARD
+/// (`off` | `to_clipboard` | `from_clipboard` | `both`).
+pub const PRIMARY_SYNC_KEY: &str = "primary_sync";
+
+/// How often PRIMARY is read.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long PRIMARY must stay unchanged before it counts as one selection,
+/// so a drag-select is recorded once instead of at every intermediate step.
+const SETTLE_TIME: Duration = Duration::from_millis(750);
+
+/// Mirroring between PRIMARY and CLIPBOARD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    Off,
+    /// Settled selections are also written to CLIPBOARD.
+    ToClipboard,
+    /// Text copied to CLIPBOARD is also written to PRIMARY.
+    FromClipboard,
+    Both,
+}
+
+impl SyncMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("to_clipboard") => Self::ToClipboard,
+            Some("from_clipboard") => Self::FromClipboard,
+            Some("both") => Self::Both,
+            _ => Self::Off,
+        }
+    }
+
+    pub fn to_clipboard(self) -> bool {
+        matches!(self, Self::ToClipboard | Self::Both)
+    }
+
+    pub fn from_clipboard(self) -> bool {
+        matches!(self, Self::FromClipboard | Self::Both)
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::ToClipboard,
+            2 => Self::FromClipboard,
+            3 => Self::Both,
+            _ => Self::Off,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::Off => 0,
+            Self::ToClipboard => 1,
+            Self::FromClipboard => 2,
+            Self::Both => 3,
+        }
+    }
+}
+
+/// Reports PRIMARY contents once they have settled.
+#[derive(Debug, Default)]
+struct Debouncer {
+    /// Latest non-blank value and when it first appeared.
+    pending: Option<(String, Instant)>,
+    /// Last value reported (or written by Recopy), never reported twice in a row.
+    last: Option<String>,
+}
+
+impl Debouncer {
+    /// Feed the current PRIMARY contents. Returns the text once it has stayed
+    /// unchanged for `SETTLE_TIME`.
+    fn observe(&mut self, value: Option<String>, now: Instant) -> Option<String> {
+        let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+            self.pending = None;
+            return None;
+        };
+        match &self.pending {
+            Some((pending, since)) if *pending == value => {
+                if now.saturating_duration_since(*since) < SETTLE_TIME
+                    || self.last.as_deref() == Some(value.as_str())
+                {
+                    return None;
+                }
+            }
+            _ => {
+                self.pending = Some((value, now));
+                return None;
+            }
+        }
+        self.last = Some(value.clone());
+        Some(value)
+    }
+
+    /// Treat `value` as already reported (Recopy wrote it to PRIMARY itself).
+    fn mark_seen(&mut self, value: &str, now: Instant) {
+        self.pending = Some((value.to_string(), now));
+        self.last = Some(value.to_string());
+    }
+}
+
+/// Live PRIMARY settings and the channel to the watcher thread.
+pub struct PrimarySelection {
+    capture: AtomicBool,
+    sync: AtomicU8,
+    /// Text to write into PRIMARY, consumed by the watcher thread.
+    writes: Mutex<Option<mpsc::Sender<String>>>,
+}
+
+impl PrimarySelection {
+    pub fn new(capture: bool, sync: SyncMode) -> Self {
+        Self {
+            capture: AtomicBool::new(capture),
+            sync: AtomicU8::new(sync.as_u8()),
+            writes: Mutex::new(None),
+        }
+    }
+
+    pub fn capture(&self) -> bool {
+        self.capture.load(Ordering::Relaxed)
+    }
+
+    pub fn set_capture(&self, capture: bool) {
+        self.capture.store(capture, Ordering::Relaxed);
+    }
+
+    pub fn sync(&self) -> SyncMode {
+        SyncMode::from_u8(self.sync.load(Ordering::Relaxed))
+    }
+
+    pub fn set_sync(&self, sync: SyncMode) {
+        self.sync.store(sync.as_u8(), Ordering::Relaxed);
+    }
+
+    /// Copy text that reached CLIPBOARD into PRIMARY, if mirroring that way is enabled.
+    pub fn mirror_from_clipboard(&self, text: &str) {
+        if !self.sync().from_clipboard() || text.trim().is_empty() {
+            return;
+        }
+        if let Some(tx) = self.writes.lock().unwrap().as_ref() {
+            let _ = tx.send(text.to_string());
+        }
+    }
+}
+
+/// Build the PRIMARY state from the stored settings.
+pub async fn load_primary_selection(db: &DbPool) -> PrimarySelection {
+    let capture = queries::get_setting(&db.0, PRIMARY_CAPTURE_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    let sync = queries::get_setting(&db.0, PRIMARY_SYNC_KEY)
+        .await
+        .ok()
+        .flatten();
+    PrimarySelection::new(capture, SyncMode::from_setting(sync.as_deref()))
+}
+
+/// Start polling PRIMARY. The selection is only read while capture or
+/// mirroring to CLIPBOARD is enabled.
+pub fn start_primary_watcher(app: AppHandle) {
+    let (write_tx, write_rx) = mpsc::channel::<String>();
+    *app.state::<PrimarySelection>().writes.lock().unwrap() = Some(write_tx);
+
+    // Settled selections are handled one at a time on the async runtime
+    let (settled_tx, mut settled_rx) = tokio::sync::mpsc::channel::<String>(8);
+    let app_worker = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(text) = settled_rx.recv().await {
+            handle_primary_text(&app_worker, text).await;
+        }
+    });
+
+    let spawned = std::thread::Builder::new()
+        .name("primary-selection".into())
+        .spawn(move || {
+            // Opened on first use; kept alive so PRIMARY written by Recopy stays served
+            let mut clipboard: Option<arboard::Clipboard> = None;
+            let mut debouncer = Debouncer::default();
+            loop {
+                let write = match write_rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(text) => Some(text),
+                    Err(mpsc::RecvTimeoutError::Timeout) => None,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                };
+                let state = app.state::<PrimarySelection>();
+                if write.is_none() && !state.capture() && !state.sync().to_clipboard() {
+                    continue;
+                }
+                if clipboard.is_none() {
+                    match arboard::Clipboard::new() {
+                        Ok(c) => clipboard = Some(c),
+                        Err(e) => {
+                            log::warn!("PRIMARY selection unavailable: {}", e);
+                            return;
+                        }
+                    }
+                }
+                let Some(cb) = clipboard.as_mut() else {
+                    continue;
+                };
+                let current = cb.get().clipboard(LinuxClipboardKind::Primary).text().ok();
+
+                if let Some(text) = write {
+                    debouncer.mark_seen(&text, Instant::now());
+                    // Taking ownership of PRIMARY clears the highlight in the source
+                    // app, so leave it alone when it already holds the text
+                    if current.as_deref() != Some(text.as_str()) {
+                        if let Err(e) = cb.set().clipboard(LinuxClipboardKind::Primary).text(text) {
+                            log::warn!("Failed to write PRIMARY selection: {}", e);
+                        }
+                    }
+                    continue;
+                }
+
+                if let Some(text) = debouncer.observe(current, Instant::now()) {
+                    if settled_tx.blocking_send(text).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("Failed to start PRIMARY selection watcher: {}", e);
+    }
+}
+
+/// Record a settled selection and mirror it into CLIPBOARD as configured.
+async fn handle_primary_text(app: &AppHandle, text: String) {
+    let state = app.state::<PrimarySelection>();
+
+    let source = crate::platform::source_app_provider()
+        .frontmost_app()
+        .unwrap_or_default();
+    if !clip_cmd::source_app_allowed(app, &source).await {
+        log::info!("Skipping PRIMARY selection from excluded app {}", source.id);
+        return;
+    }
+
+    // A capture pause stops recording; mirroring into CLIPBOARD still follows the setting
+    let paused = app.state::<super::pause::CapturePause>().is_paused();
+    if state.capture() && !paused {
+        let (content_type, stored) = super::classify_text(text.clone());
+        log::info!("PRIMARY selection changed: type={}", content_type.as_str());
+        let result = clip_cmd::process_clipboard_change(
+            app,
+            content_type,
+            stored.as_bytes().to_vec(),
+            Some(stored),
+            None,
+            None,
+            None,
+            source.id,
+            source.name,
+            SelectionOrigin::Primary,
+            false,
+        )
+        .await;
+        match result {
+            Ok(Some(stored)) => {
+                clip_cmd::emit_clipboard_changed(app, &stored.id);
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to process PRIMARY selection: {}", e),
+        }
+    }
+
+    if state.sync().to_clipboard() {
+        // The selection is recorded (or deliberately not) above, never again as a copy
+        crate::set_skip_next_clipboard_change();
+        if let Err(e) = tauri_plugin_clipboard_x::write_text(text).await {
+            crate::clear_skip_next_clipboard_change();
+            log::warn!("Failed to mirror PRIMARY into CLIPBOARD: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_debouncer_reports_settled_selection_once() {
+        let start = Instant::now();
+        let mut d = Debouncer::default();
+
+        // A drag-select grows the selection on every poll
+        for (i, text) in ["h", "he", "hel", "hello"].iter().enumerate() {
+            assert_eq!(
+                d.observe(Some(text.to_string()), ms(start, i as u64 * 250)),
+                None
+            );
+        }
+        // Not settled yet
+        assert_eq!(d.observe(Some("hello".into()), ms(start, 1000)), None);
+        assert_eq!(
+            d.observe(Some("hello".into()), ms(start, 1500)),
+            Some("hello".into())
+        );
+        // Reported only once while unchanged
+        assert_eq!(d.observe(Some("hello".into()), ms(start, 2000)), None);
+        assert_eq!(d.observe(Some("hello".into()), ms(start, 5000)), None);
+
+        // A new selection settles on its own
+        assert_eq!(d.observe(Some("world".into()), ms(start, 5250)), None);
+        assert_eq!(
+            d.observe(Some("world".into()), ms(start, 6000)),
+            Some("world".into())
+        );
+    }
+
+    #[test]
+    fn test_debouncer_ignores_blank_and_own_writes() {
+        let start = Instant::now();
+        let mut d = Debouncer::default();
+
+        assert_eq!(d.observe(None, start), None);
+        assert_eq!(d.observe(Some("  \n".into()), start), None);
+        assert_eq!(d.observe(Some("  \n".into()), ms(start, 2000)), None);
+
+        d.mark_seen("mirrored", ms(start, 2000));
+        assert_eq!(d.observe(Some("mirrored".into()), ms(start, 4000)), None);
+
+        // A blank gap resets the pending value
+        assert_eq!(d.observe(Some("abc".into()), ms(start, 4000)), None);
+        assert_eq!(d.observe(None, ms(start, 4500)), None);
+        assert_eq!(d.observe(Some("abc".into()), ms(start, 5000)), None);
+        assert_eq!(
+            d.observe(Some("abc".into()), ms(start, 5750)),
+            Some("abc".into())
+        );
+    }
+
+    #[test]
+    fn test_sync_mode() {
+        assert_eq!(SyncMode::from_setting(Some("both")), SyncMode::Both);
+        assert_eq!(SyncMode::from_setting(Some("bogus")), SyncMode::Off);
+        assert_eq!(SyncMode::from_setting(None), SyncMode::Off);
+        assert!(SyncMode::ToClipboard.to_clipboard());
+        assert!(!SyncMode::ToClipboard.from_clipboard());
+        assert!(SyncMode::Both.to_clipboard() && SyncMode::Both.from_clipboard());
+        for mode in [
+            SyncMode::Off,
+            SyncMode::ToClipboard,
+            SyncMode::FromClipboard,
+            SyncMode::Both,
+        ] {
+            assert_eq!(SyncMode::from_u8(mode.as_u8()), mode);
+        }
+
+        let state = PrimarySelection::new(false, SyncMode::Off);
+        state.set_sync(SyncMode::FromClipboard);
+        state.set_capture(true);
+        assert_eq!(state.sync(), SyncMode::FromClipboard);
+        assert!(state.capture());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::clipboard::pause::{CapturePause, CaptureStatus};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Get whether clipboard capture is paused and when it resumes.
+#[tauri::command]
+pub fn get_capture_status(pause: State<'_, CapturePause>) -> CaptureStatus {
+    pause.status()
+}
+
+/// Stop recording clipboard changes, for `duration_secs` or until `resume_capture`
+/// when omitted or zero. Emits `capture-paused`, then `capture-resumed` once a
+/// timed pause runs out.
+#[tauri::command]
+pub fn pause_capture(
+    app: AppHandle,
+    pause: State<'_, CapturePause>,
+    duration_secs: Option<u64>,
+) -> CaptureStatus {
+    let duration = duration_secs
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs);
+    let generation = pause.pause(duration);
+    let status = pause.status();
+    log::info!("Clipboard capture paused until {:?}", status.resumes_at);
+    let _ = app.emit("capture-paused", status.clone());
+
+    if let Some(duration) = duration {
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(duration).await;
+            if app.state::<CapturePause>().resume_if_current(generation) {
+                log::info!("Clipboard capture resumed after timed pause");
+                let _ = app.emit("capture-resumed", ());
+            }
+        });
+    }
+    status
+}
+
+/// Resume recording clipboard changes. Emits `capture-resumed` if capture was paused.
+#[tauri::command]
+pub fn resume_capture(app: AppHandle, pause: State<'_, CapturePause>) -> CaptureStatus {
+    if pause.resume() {
+        log::info!("Clipboard capture resumed");
+        let _ = app.emit("capture-resumed", ());
+    }
+    pause.status()
+}
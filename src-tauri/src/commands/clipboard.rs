@@ -1,11 +1,18 @@
-use crate::clipboard as clip_util;
+use crate::clipboard::sensitive::{
+    SensitiveDetector, SENSITIVE_DETECTION_KEY, SENSITIVE_PATTERNS_KEY,
+};
+use crate::clipboard::{self as clip_util, ignore, metadata, NearDuplicateMode, TextDedupMode};
 use crate::db::{
     models::{
-        ClipboardItem, ContentType, FilePreviewData, ItemDetail, NewClipboardItem, PreviewClosing,
-        PreviewResponse, PreviewState,
+        AppCaptureRule, CaptureIgnoreRule, ClipboardItem, ContentType, FilePreviewData, ItemDetail,
+        ItemFilter, NewClipboardItem, PreviewClosing, PreviewResponse, PreviewState,
+        RetentionPreview, RetentionRule, SelectionOrigin,
     },
     queries, DbPool,
 };
+use crate::jobs::{self, ThumbnailJob};
+use crate::lock::{AppLock, LOCK_HASH_KEY, LOCK_IDLE_KEY};
+use crate::thumbnails::{is_image_file, IMAGE_EXTENSIONS};
 use std::process::Command;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tauri::{AppHandle, Emitter, Manager, State};
@@ -16,10 +23,12 @@ static HUD_GENERATION: AtomicU64 = AtomicU64::new(0);
 #[tauri::command]
 pub async fn get_clipboard_items(
     db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
     content_type: Option<String>,
     limit: Option<i64>,
     offset: Option<i64>,
 ) -> Result<Vec<ClipboardItem>, String> {
+    lock.ensure_unlocked()?;
     let limit = limit.unwrap_or(50);
     let offset = offset.unwrap_or(0);
     let ct = content_type.as_deref();
@@ -29,29 +38,363 @@ pub async fn get_clipboard_items(
         .map_err(|e| e.to_string())
 }
 
-/// Search clipboard items.
+/// Search clipboard items. `content_type` and `favorites_only` override the same fields of `filter`.
 #[tauri::command]
 pub async fn search_clipboard_items(
     db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
     query: String,
     content_type: Option<String>,
     limit: Option<i64>,
     favorites_only: Option<bool>,
+    filter: Option<ItemFilter>,
 ) -> Result<Vec<ClipboardItem>, String> {
+    lock.ensure_unlocked()?;
     let limit = limit.unwrap_or(50);
-    let ct = content_type.as_deref();
+    let mut filter = filter.unwrap_or_default();
+    if content_type.is_some() {
+        filter.content_type = content_type;
+    }
+    if let Some(favorites_only) = favorites_only {
+        filter.favorites_only = favorites_only;
+    }
 
-    queries::search_items(&db.0, &query, ct, limit, favorites_only.unwrap_or(false))
+    queries::search_items(&db.0, &query, &filter, limit)
         .await
         .map_err(|e| e.to_string())
 }
 
-/// Get the thumbnail for a single clipboard item (lazy loading).
+/// Serve a cached thumbnail for the `thumb://` URI scheme.
+/// URL form: `thumb://localhost/{item_id}?size=400` (`http://thumb.localhost/...` on Windows).
+/// Responds 404 while the thumbnail hasn't been generated yet, 403 while the app is locked.
+pub async fn thumbnail_protocol_response(
+    app: &AppHandle,
+    request: &tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    let respond = |status: u16, mime: &str, body: Vec<u8>| {
+        tauri::http::Response::builder()
+            .status(status)
+            .header("Content-Type", mime)
+            .header("Access-Control-Allow-Origin", "*")
+            .body(body)
+            .unwrap_or_default()
+    };
+
+    if app.state::<AppLock>().ensure_unlocked().is_err() {
+        return respond(403, "text/plain", b"App is locked".to_vec());
+    }
+
+    let id = request.uri().path().trim_start_matches('/').to_string();
+    let size = request
+        .uri()
+        .query()
+        .and_then(|q| q.split('&').find_map(|kv| kv.strip_prefix("size=")))
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(crate::thumbnails::DEFAULT_THUMBNAIL_SIZE);
+
+    let (Ok(app_data_dir), Some(db)) = (app.path().app_data_dir(), app.try_state::<DbPool>())
+    else {
+        return respond(500, "text/plain", Vec::new());
+    };
+    let hash = match queries::get_content_hash(&db.0, &id).await {
+        Ok(Some(h)) => h,
+        Ok(None) => return respond(404, "text/plain", Vec::new()),
+        Err(e) => return respond(500, "text/plain", e.to_string().into_bytes()),
+    };
+
+    let Some((path, mime)) = crate::thumbnails::find_thumbnail(&app_data_dir, &hash, size) else {
+        return respond(404, "text/plain", Vec::new());
+    };
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => respond(200, mime, bytes),
+        Err(_) => respond(404, "text/plain", Vec::new()),
+    }
+}
+
+/// Render the thumbnail cache for a source file (original image or image file).
+/// Errors if the file can't be read or decoded yet (e.g. still being written).
+pub(crate) async fn write_thumbnails_from_file(
+    app_data_dir: &std::path::Path,
+    hash: &str,
+    source: &str,
+) -> Result<(), String> {
+    let data = tokio::fs::read(source)
+        .await
+        .map_err(|e| format!("Failed to read image file for thumbnail: {}", e))?;
+    let dir = app_data_dir.to_path_buf();
+    let hash = hash.to_string();
+    let ext = std::path::Path::new(source)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase());
+    tokio::task::spawn_blocking(move || {
+        crate::thumbnails::write_thumbnails(&dir, &hash, &data, ext.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Queue thumbnail regeneration for every image and image-file item.
+/// Progress is reported through `job-progress` events; returns the number of jobs queued.
 #[tauri::command]
-pub async fn get_thumbnail(db: State<'_, DbPool>, id: String) -> Result<Option<Vec<u8>>, String> {
-    queries::get_thumbnail(&db.0, &id)
+pub async fn regenerate_thumbnails(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+) -> Result<u32, String> {
+    lock.ensure_unlocked()?;
+    let sources = queries::get_thumbnail_sources(&db.0)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    let mut count = 0u32;
+    for (content_type, hash, source) in sources {
+        if content_type == "file" && !is_image_file(&source) {
+            continue;
+        }
+        let job = ThumbnailJob {
+            item_id: None,
+            hash,
+            source,
+        };
+        jobs::enqueue(&app, jobs::THUMBNAIL_JOB, &job).await?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Move thumbnail blobs from the `thumbnail` column into the disk cache.
+/// Prefers re-rendering from the stored original (all sizes); falls back to the
+/// blob itself when the original is gone. Runs at startup in batches.
+pub async fn migrate_thumbnail_blobs(app: &AppHandle) {
+    const BATCH: i64 = 50;
+
+    let app_data_dir = match app.path().app_data_dir() {
+        Ok(d) => d,
+        Err(e) => {
+            log::warn!("migrate_thumbnail_blobs: could not get app_data_dir: {}", e);
+            return;
+        }
+    };
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p,
+        None => return,
+    };
+
+    let mut migrated = 0u32;
+    loop {
+        let batch = match queries::get_legacy_thumbnails(&pool.0, BATCH).await {
+            Ok(b) if !b.is_empty() => b,
+            Ok(_) => break,
+            Err(e) => {
+                log::warn!("migrate_thumbnail_blobs: failed to query DB: {}", e);
+                return;
+            }
+        };
+
+        for (id, hash, image_path, blob) in batch {
+            let from_original = match &image_path {
+                Some(p) => write_thumbnails_from_file(&app_data_dir, &hash, p)
+                    .await
+                    .is_ok(),
+                None => false,
+            };
+            if !from_original {
+                let dir = app_data_dir.clone();
+                let hash = hash.clone();
+                let res = tokio::task::spawn_blocking(move || {
+                    crate::thumbnails::write_thumbnails(&dir, &hash, &blob, None)
+                })
+                .await;
+                if !matches!(res, Ok(Ok(()))) {
+                    log::warn!(
+                        "migrate_thumbnail_blobs: dropping undecodable blob of {}",
+                        id
+                    );
+                }
+            }
+            // Clear even on failure so a corrupt blob can't stall the migration
+            if let Err(e) = queries::clear_thumbnail_blob(&pool.0, &id).await {
+                log::warn!("migrate_thumbnail_blobs: failed to clear {}: {}", id, e);
+                return;
+            }
+            migrated += 1;
+        }
+    }
+
+    if migrated > 0 {
+        log::info!(
+            "migrate_thumbnail_blobs: moved {} thumbnail(s) to the disk cache",
+            migrated
+        );
+    }
+}
+
+/// Remove cached thumbnails that no longer belong to any item (best-effort).
+/// Returns the number of files removed.
+pub async fn cleanup_orphan_thumbnails(app: &AppHandle) -> u32 {
+    let (Ok(app_data_dir), Some(pool)) = (app.path().app_data_dir(), app.try_state::<DbPool>())
+    else {
+        return 0;
+    };
+    let known: std::collections::HashSet<String> =
+        match queries::get_thumbnail_hashes(&pool.0).await {
+            Ok(hashes) => hashes.into_iter().collect(),
+            Err(e) => {
+                log::warn!("cleanup_orphan_thumbnails: failed to query DB: {}", e);
+                return 0;
+            }
+        };
+
+    let removed = tokio::task::spawn_blocking(move || {
+        crate::thumbnails::remove_orphan_thumbnails(&app_data_dir, &known)
+    })
+    .await
+    .unwrap_or(0);
+    if removed > 0 {
+        log::info!(
+            "cleanup_orphan_thumbnails: removed {} orphan file(s)",
+            removed
+        );
+    }
+    removed
+}
+
+/// Whether stored originals and pasted images should be stripped of EXIF/XMP.
+async fn strip_metadata_enabled(db: &DbPool) -> bool {
+    queries::get_setting(&db.0, metadata::STRIP_METADATA_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// Record metadata for image items captured before it was extracted (best-effort).
+pub async fn backfill_image_metadata(app: &AppHandle) {
+    let Some(db) = app.try_state::<DbPool>() else {
+        return;
+    };
+    let items = match queries::get_items_missing_image_metadata(&db.0).await {
+        Ok(items) => items,
+        Err(e) => {
+            log::warn!("backfill_image_metadata: failed to query DB: {}", e);
+            return;
+        }
+    };
+    if items.is_empty() {
+        return;
+    }
+
+    let strip = strip_metadata_enabled(&db).await;
+    let mut filled = 0u32;
+    for (id, path) in items {
+        let Ok(data) = tokio::fs::read(&path).await else {
+            continue;
+        };
+        let meta = tokio::task::spawn_blocking(move || metadata::extract_image_metadata(&data))
+            .await
+            .ok()
+            .flatten();
+        let Some(mut meta) = meta else {
+            continue;
+        };
+        if strip {
+            metadata::clear_exif(&mut meta);
+        }
+        if queries::set_image_metadata(&db.0, &id, &meta).await.is_ok() {
+            filled += 1;
+        }
+    }
+    log::info!("backfill_image_metadata: recorded {} item(s)", filled);
+}
+
+/// Max number of recent images a new capture is compared against.
+const SIMILARITY_CANDIDATE_LIMIT: i64 = 500;
+
+/// Find a stored image perceptually close to a new capture, per the near-duplicate
+/// settings. Returns the configured mode with the match's (id, content_hash).
+async fn find_near_duplicate(
+    db: &DbPool,
+    phash: u64,
+    aspect_ratio: f64,
+) -> Option<(NearDuplicateMode, String, String)> {
+    let mode = queries::get_setting(&db.0, clip_util::NEAR_DUPLICATE_MODE_KEY)
+        .await
+        .ok()
+        .flatten();
+    let mode = NearDuplicateMode::from_setting(mode.as_deref());
+    // A zero hash means a flat image: every solid color would match every other
+    if mode == NearDuplicateMode::Off || phash == 0 {
+        return None;
+    }
+    let threshold = queries::get_setting(&db.0, clip_util::SIMILARITY_THRESHOLD_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(clip_util::DEFAULT_SIMILARITY_THRESHOLD)
+        .min(64);
+
+    let candidates = match queries::get_similarity_candidates(
+        &db.0,
+        Some(aspect_ratio),
+        SIMILARITY_CANDIDATE_LIMIT,
+    )
+    .await
+    {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            log::warn!("Near-duplicate lookup failed: {}", e);
+            return None;
+        }
+    };
+    let hashes: Vec<u64> = candidates.iter().map(|(_, _, h)| *h).collect();
+    let index = clip_util::closest_dhash(phash, &hashes, threshold)?;
+    let (id, content_hash, _) = candidates.into_iter().nth(index)?;
+    Some((mode, id, content_hash))
+}
+
+/// Compute perceptual hashes for image items captured before near-duplicate
+/// detection existed (best-effort). They are not grouped retroactively.
+pub async fn backfill_perceptual_hashes(app: &AppHandle) {
+    let Some(db) = app.try_state::<DbPool>() else {
+        return;
+    };
+    let items = match queries::get_items_missing_perceptual_hash(&db.0).await {
+        Ok(items) => items,
+        Err(e) => {
+            log::warn!("backfill_perceptual_hashes: failed to query DB: {}", e);
+            return;
+        }
+    };
+    if items.is_empty() {
+        return;
+    }
+
+    let mut filled = 0u32;
+    for (id, path) in items {
+        let Ok(data) = tokio::fs::read(&path).await else {
+            continue;
+        };
+        let phash = tokio::task::spawn_blocking(move || {
+            crate::thumbnails::decode_image(&data, None)
+                .ok()
+                .map(|img| clip_util::compute_dhash(&img))
+        })
+        .await
+        .ok()
+        .flatten();
+        let Some(phash) = phash else {
+            continue;
+        };
+        if queries::set_perceptual_hash(&db.0, &id, phash, None)
+            .await
+            .is_ok()
+        {
+            filled += 1;
+        }
+    }
+    log::info!("backfill_perceptual_hashes: recorded {} item(s)", filled);
 }
 
 /// Internal helper to load full item detail from DB.
@@ -61,8 +404,24 @@ async fn load_item_detail(db: &DbPool, id: &str) -> Result<ItemDetail, String> {
         .map_err(|e| e.to_string())?
         .ok_or("Item not found")?;
 
-    let (content_type, plain_text, rich_content, image_path, file_path, file_name, content_size) =
-        row;
+    let (
+        content_type,
+        plain_text,
+        rich_content,
+        image_path,
+        image_mime,
+        file_path,
+        file_name,
+        content_size,
+    ) = row;
+
+    let image_metadata = if content_type == "image" {
+        queries::get_image_metadata(&db.0, id)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        None
+    };
 
     Ok(ItemDetail {
         id: id.to_string(),
@@ -70,56 +429,111 @@ async fn load_item_detail(db: &DbPool, id: &str) -> Result<ItemDetail, String> {
         plain_text,
         rich_content,
         image_path,
+        image_mime,
         file_path,
         file_name,
         content_size,
+        image_metadata,
     })
 }
 
 /// Get full item detail for preview (includes rich_content).
 #[tauri::command]
-pub async fn get_item_detail(db: State<'_, DbPool>, id: String) -> Result<ItemDetail, String> {
+pub async fn get_item_detail(
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    id: String,
+) -> Result<ItemDetail, String> {
+    lock.ensure_unlocked()?;
     load_item_detail(&db, &id).await
 }
 
-/// Delete a clipboard item and remove its original image file if present.
+/// Get the near-duplicate images grouped under an item.
 #[tauri::command]
-pub async fn delete_clipboard_item(db: State<'_, DbPool>, id: String) -> Result<(), String> {
-    // Capture image_path before deleting the DB row
+pub async fn get_item_variants(
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    id: String,
+) -> Result<Vec<ClipboardItem>, String> {
+    lock.ensure_unlocked()?;
+    queries::get_item_variants(&db.0, &id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a clipboard item and remove its original image file and thumbnails if present.
+#[tauri::command]
+pub async fn delete_clipboard_item(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    id: String,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    // Capture image_path and content hash before deleting the DB row
     let image_path = queries::get_image_path_by_id(&db.0, &id)
         .await
         .map_err(|e| e.to_string())?;
+    let hash = queries::get_content_hash(&db.0, &id)
+        .await
+        .map_err(|e| e.to_string())?;
 
     queries::delete_item(&db.0, &id)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Async file removal — best-effort, does not fail the command
-    if let Some(path) = image_path {
-        tokio::spawn(async move {
-            if let Err(e) = tokio::fs::remove_file(&path).await {
-                log::warn!("Failed to delete image file {}: {}", path, e);
-            }
-        });
+    if let (Some(hash), Ok(dir)) = (hash, app.path().app_data_dir()) {
+        tokio::task::spawn_blocking(move || crate::thumbnails::remove_thumbnails(&dir, &hash));
     }
 
+    // Only removes the file if no other item shares it
+    remove_released_images(&db.0, image_path.into_iter().collect()).await;
+
     Ok(())
 }
 
+/// Delete original image files whose last referencing item was just removed.
+/// `candidate_paths` are the image paths of the deleted items; shared originals
+/// that are still referenced elsewhere are kept. File removal is best-effort.
+pub async fn remove_released_images(pool: &sqlx::SqlitePool, candidate_paths: Vec<String>) {
+    if candidate_paths.is_empty() {
+        return;
+    }
+
+    // Until the files are gone, a new copy of the same image must not reuse them
+    let _store = clip_util::IMAGE_STORE_LOCK.lock().await;
+    let released = match queries::release_image_paths(pool, candidate_paths).await {
+        Ok(paths) => paths,
+        Err(e) => {
+            // Leave the files for orphan GC rather than risk deleting shared ones
+            log::warn!("Failed to release image files: {}", e);
+            return;
+        }
+    };
+
+    for path in released {
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            log::warn!("Failed to delete image file {}: {}", path, e);
+        }
+    }
+}
+
 /// Paste a clipboard item: write to system clipboard, optionally simulate Cmd+V.
 #[tauri::command]
 pub async fn paste_clipboard_item(
     app: AppHandle,
     db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
     id: String,
     auto_paste: Option<bool>,
 ) -> Result<(), String> {
+    lock.ensure_unlocked()?;
     let row = queries::get_item_by_id(&db.0, &id)
         .await
         .map_err(|e| e.to_string())?
         .ok_or("Item not found")?;
 
-    let (content_type, plain_text, rich_content, image_path, file_path) = row;
+    let (content_type, plain_text, rich_content, image_path, image_mime, file_path) = row;
 
     // Skip self-monitoring for this clipboard write (clear flag on failure)
     crate::set_skip_next_clipboard_change();
@@ -129,6 +543,7 @@ pub async fn paste_clipboard_item(
         &plain_text,
         &rich_content,
         &image_path,
+        &image_mime,
         &file_path,
     )
     .await
@@ -140,7 +555,7 @@ pub async fn paste_clipboard_item(
     if auto_paste.unwrap_or(true) {
         // Resign keyboard focus so the previous app receives the Cmd+V
         crate::platform::platform_resign_before_paste(&app);
-        simulate_paste();
+        simulate_paste(&app).await;
         // Now hide the panel (via hide_main_window to emit recopy-hide event)
         crate::hide_main_window(&app);
     }
@@ -153,14 +568,16 @@ pub async fn paste_clipboard_item(
 pub async fn paste_as_plain_text(
     app: AppHandle,
     db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
     id: String,
 ) -> Result<(), String> {
+    lock.ensure_unlocked()?;
     let row = queries::get_item_by_id(&db.0, &id)
         .await
         .map_err(|e| e.to_string())?
         .ok_or("Item not found")?;
 
-    let (_content_type, plain_text, _rich_content, _image_path, _file_path) = row;
+    let (_content_type, plain_text, _rich_content, _image_path, _image_mime, _file_path) = row;
 
     // Skip self-monitoring for this clipboard write (clear flag on failure)
     crate::set_skip_next_clipboard_change();
@@ -171,7 +588,7 @@ pub async fn paste_as_plain_text(
 
     // Resign keyboard focus so the previous app receives the Cmd+V
     crate::platform::platform_resign_before_paste(&app);
-    simulate_paste();
+    simulate_paste(&app).await;
     // Now hide the panel (via hide_main_window to emit recopy-hide event)
     crate::hide_main_window(&app);
     Ok(())
@@ -179,7 +596,12 @@ pub async fn paste_as_plain_text(
 
 /// Toggle favorite status of a clipboard item.
 #[tauri::command]
-pub async fn toggle_favorite(db: State<'_, DbPool>, id: String) -> Result<bool, String> {
+pub async fn toggle_favorite(
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    id: String,
+) -> Result<bool, String> {
+    lock.ensure_unlocked()?;
     let current: (bool,) = sqlx::query_as("SELECT is_favorited FROM clipboard_items WHERE id = ?")
         .bind(&id)
         .fetch_one(&db.0)
@@ -199,28 +621,51 @@ pub async fn toggle_favorite(db: State<'_, DbPool>, id: String) -> Result<bool,
 
 /// Write content to system clipboard based on type.
 async fn write_to_clipboard(
-    _app: &AppHandle,
+    app: &AppHandle,
     content_type: &str,
     plain_text: &str,
     rich_content: &Option<Vec<u8>>,
     image_path: &Option<String>,
+    image_mime: &Option<String>,
     file_path: &Option<String>,
 ) -> Result<(), String> {
     match content_type {
         "image" => {
-            if let Some(path) = image_path {
+            if let Some(original) = image_path {
+                let path = &clipboard_image_path(app, original).await?;
                 let file_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
-                log::info!("Pasting image from path: {} ({}B)", path, file_size);
+                let mime = image_mime.as_deref().unwrap_or("image/png");
+                log::info!(
+                    "Pasting image from path: {} ({}B, {})",
+                    path,
+                    file_size,
+                    mime
+                );
                 #[cfg(target_os = "macos")]
                 {
-                    crate::platform::platform_write_image_to_pasteboard(path)?;
+                    crate::platform::platform_write_image_to_pasteboard(path, mime)?;
                 }
-                #[cfg(not(target_os = "macos"))]
+                #[cfg(any(target_os = "windows", target_os = "linux"))]
+                {
+                    // The plugin only writes a bitmap; offer the original encoding alongside it
+                    if mime == "image/png" {
+                        tauri_plugin_clipboard_x::write_image(path.clone())
+                            .await
+                            .map_err(|e| format!("Failed to write image: {}", e))?;
+                    } else {
+                        crate::platform::platform_write_image_to_clipboard(path, mime)?;
+                    }
+                }
+                #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
                 {
                     tauri_plugin_clipboard_x::write_image(path.clone())
                         .await
                         .map_err(|e| format!("Failed to write image: {}", e))?;
                 }
+                // The clipboard holds its own copy now
+                if path != original {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
             } else {
                 log::warn!("Paste image: image_path is None!");
             }
@@ -254,8 +699,46 @@ async fn write_to_clipboard(
     Ok(())
 }
 
+/// The image file to put on the clipboard. With `strip_image_metadata` on, originals
+/// that still carry EXIF/XMP (e.g. captured before the setting was enabled) are
+/// copied to a metadata-free temp file first.
+async fn clipboard_image_path(app: &AppHandle, path: &str) -> Result<String, String> {
+    if !strip_metadata_enabled(&app.state::<DbPool>()).await {
+        return Ok(path.to_string());
+    }
+    let data = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    let stripped = tokio::task::spawn_blocking(move || metadata::strip_image_metadata(&data))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to strip image metadata: {}", e))?;
+    let Some(stripped) = stripped else {
+        return Ok(path.to_string());
+    };
+
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png");
+    let tmp_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("tmp");
+    tokio::fs::create_dir_all(&tmp_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let tmp = tmp_dir.join(format!("paste.{}", ext));
+    tokio::fs::write(&tmp, stripped)
+        .await
+        .map_err(|e| format!("Failed to write stripped image: {}", e))?;
+    Ok(tmp.to_string_lossy().to_string())
+}
+
 /// Simulate Ctrl+V / Cmd+V paste via platform-specific mechanism.
-fn simulate_paste() {
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+async fn simulate_paste(app: &AppHandle) {
     #[cfg(target_os = "macos")]
     {
         simulate_paste_cgevent();
@@ -264,6 +747,33 @@ fn simulate_paste() {
     {
         crate::platform::simulate_paste_keys();
     }
+    #[cfg(target_os = "linux")]
+    {
+        let config = linux_paste_config(&app.state::<DbPool>()).await;
+        let result =
+            tokio::task::spawn_blocking(move || crate::platform::simulate_paste_keys(&config))
+                .await;
+        if let Ok(Err(e)) = result {
+            log::warn!("Auto-paste failed: {}", e);
+        }
+    }
+}
+
+/// Linux paste backend, key combination and fallback command from settings.
+#[cfg(target_os = "linux")]
+async fn linux_paste_config(db: &DbPool) -> crate::platform::PasteConfig {
+    use crate::platform::{PasteBackend, PasteConfig, PasteKeys};
+    let get =
+        |key: &'static str| async move { queries::get_setting(&db.0, key).await.ok().flatten() };
+    PasteConfig {
+        backend: PasteBackend::from_setting(
+            get(crate::platform::PASTE_BACKEND_KEY).await.as_deref(),
+        ),
+        keys: PasteKeys::from_setting(get(crate::platform::PASTE_KEYS_KEY).await.as_deref()),
+        command: get(crate::platform::PASTE_COMMAND_KEY)
+            .await
+            .unwrap_or_default(),
+    }
 }
 
 /// Simulate Cmd+V using macOS CGEvent API (CoreGraphics).
@@ -376,10 +886,12 @@ fn simulate_paste_cgevent() {
 #[tauri::command]
 pub async fn get_favorited_items(
     db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
     content_type: Option<String>,
     limit: Option<i64>,
     offset: Option<i64>,
 ) -> Result<Vec<ClipboardItem>, String> {
+    lock.ensure_unlocked()?;
     queries::get_favorited_items(
         &db.0,
         content_type.as_deref(),
@@ -400,7 +912,8 @@ pub async fn get_settings(db: State<'_, DbPool>) -> Result<serde_json::Value, St
         .map_err(|e| e.to_string())?;
 
     let mut map = serde_json::Map::new();
-    for (key, value) in settings {
+    // Never expose the app lock hash to the frontend
+    for (key, value) in settings.into_iter().filter(|(k, _)| k != LOCK_HASH_KEY) {
         map.insert(key, serde_json::Value::String(value));
     }
     Ok(serde_json::Value::Object(map))
@@ -409,6 +922,9 @@ pub async fn get_settings(db: State<'_, DbPool>) -> Result<serde_json::Value, St
 /// Get a single setting value.
 #[tauri::command]
 pub async fn get_setting(db: State<'_, DbPool>, key: String) -> Result<Option<String>, String> {
+    if key == LOCK_HASH_KEY {
+        return Ok(None);
+    }
     queries::get_setting(&db.0, &key)
         .await
         .map_err(|e| e.to_string())
@@ -422,6 +938,15 @@ pub async fn set_setting(
     key: String,
     value: String,
 ) -> Result<(), String> {
+    // The lock hash can only be changed through set_app_lock
+    if key == LOCK_HASH_KEY {
+        return Err("Use set_app_lock to change the app lock".to_string());
+    }
+    // Reject secret patterns that wouldn't compile at capture time
+    if key == SENSITIVE_PATTERNS_KEY {
+        SensitiveDetector::new(&value)?;
+    }
+
     queries::set_setting(&db.0, &key, &value)
         .await
         .map_err(|e| e.to_string())?;
@@ -431,6 +956,30 @@ pub async fn set_setting(
         update_window_effects_for_theme(&app, &value);
     }
 
+    if key == LOCK_IDLE_KEY {
+        if let Ok(minutes) = value.parse::<u64>() {
+            app.state::<AppLock>().set_idle_minutes(minutes);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::clipboard::primary::{
+            PrimarySelection, SyncMode, PRIMARY_CAPTURE_KEY, PRIMARY_SYNC_KEY,
+        };
+        if key == PRIMARY_CAPTURE_KEY {
+            app.state::<PrimarySelection>().set_capture(value == "true");
+        }
+        if key == PRIMARY_SYNC_KEY {
+            app.state::<PrimarySelection>()
+                .set_sync(SyncMode::from_setting(Some(&value)));
+        }
+        if key == crate::clipboard::owner::CLIPBOARD_OWNER_KEY {
+            app.state::<crate::clipboard::owner::ClipboardOwner>()
+                .set_enabled(value == "true");
+        }
+    }
+
     Ok(())
 }
 
@@ -474,7 +1023,12 @@ pub async fn register_shortcut(app: AppHandle, db: State<'_, DbPool>) -> Result<
 
 /// Clear all clipboard history (preserve favorites), removing image files from disk.
 #[tauri::command]
-pub async fn clear_history(db: State<'_, DbPool>) -> Result<i64, String> {
+pub async fn clear_history(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+) -> Result<i64, String> {
+    lock.ensure_unlocked()?;
     // Collect image paths before deleting rows
     let image_paths = queries::get_non_favorited_image_paths(&db.0)
         .await
@@ -484,99 +1038,330 @@ pub async fn clear_history(db: State<'_, DbPool>) -> Result<i64, String> {
         .await
         .map_err(|e| e.to_string())?;
 
-    // Async file removal — best-effort
-    if !image_paths.is_empty() {
-        tokio::spawn(async move {
-            for path in image_paths {
-                if let Err(e) = tokio::fs::remove_file(&path).await {
-                    log::warn!("Failed to delete image file {}: {}", path, e);
-                }
-            }
-        });
+    remove_released_images(&db.0, image_paths).await;
+    tauri::async_runtime::spawn(async move { cleanup_orphan_thumbnails(&app).await });
+
+    Ok(count)
+}
+
+/// Clear the non-favorited items matched by `filter`, removing released image files from disk.
+/// Returns the deleted count.
+#[tauri::command]
+pub async fn clear_history_filtered(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    filter: ItemFilter,
+) -> Result<i64, String> {
+    lock.ensure_unlocked()?;
+    if let Some(ct) = &filter.content_type {
+        if ContentType::from_str(ct).is_none() {
+            return Err(format!("Unknown content type: {}", ct));
+        }
+    }
+    if let Some(selection) = &filter.selection {
+        if !matches!(selection.as_str(), "clipboard" | "primary") {
+            return Err(format!("Unknown selection: {}", selection));
+        }
     }
 
+    let image_paths = queries::get_filtered_image_paths(&db.0, &filter)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let count = queries::clear_filtered(&db.0, &filter)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    remove_released_images(&db.0, image_paths).await;
+    tauri::async_runtime::spawn(async move { cleanup_orphan_thumbnails(&app).await });
+
     Ok(count)
 }
 
 /// Run retention cleanup based on current settings, removing image files from disk.
 #[tauri::command]
-pub async fn run_retention_cleanup(db: State<'_, DbPool>) -> Result<i64, String> {
-    let policy = queries::get_setting(&db.0, "retention_policy")
+pub async fn run_retention_cleanup(
+    app: AppHandle,
+    lock: State<'_, AppLock>,
+) -> Result<i64, String> {
+    lock.ensure_unlocked()?;
+    apply_retention_policy(&app).await
+}
+
+/// List per-type retention rules.
+#[tauri::command]
+pub async fn get_retention_rules(db: State<'_, DbPool>) -> Result<Vec<RetentionRule>, String> {
+    queries::get_retention_rules(&db.0)
         .await
-        .map_err(|e| e.to_string())?
-        .unwrap_or_else(|| "unlimited".to_string());
+        .map_err(|e| e.to_string())
+}
 
-    let days = queries::get_setting(&db.0, "retention_days")
+/// Create a retention rule, or update the one with the same content type and source app.
+/// Returns the rule id.
+#[tauri::command]
+pub async fn save_retention_rule(
+    db: State<'_, DbPool>,
+    rule: RetentionRule,
+) -> Result<i64, String> {
+    if ContentType::from_str(&rule.content_type).is_none() {
+        return Err(format!("Unknown content type: {}", rule.content_type));
+    }
+    let rule = RetentionRule {
+        source_app: rule.source_app.trim().to_string(),
+        ..rule
+    };
+    queries::upsert_retention_rule(&db.0, &rule)
         .await
-        .map_err(|e| e.to_string())?
-        .and_then(|v| v.parse::<i64>().ok())
-        .unwrap_or(0);
+        .map_err(|e| e.to_string())
+}
 
-    let count = queries::get_setting(&db.0, "retention_count")
+/// Delete a retention rule.
+#[tauri::command]
+pub async fn delete_retention_rule(db: State<'_, DbPool>, id: i64) -> Result<(), String> {
+    queries::delete_retention_rule(&db.0, id)
         .await
-        .map_err(|e| e.to_string())?
-        .and_then(|v| v.parse::<i64>().ok())
-        .unwrap_or(0);
+        .map_err(|e| e.to_string())
+}
 
-    // Collect image paths before deleting rows
-    let image_paths = queries::get_retention_overflow_image_paths(&db.0, &policy, days, count)
+/// List per-app capture rules.
+#[tauri::command]
+pub async fn get_app_capture_rules(db: State<'_, DbPool>) -> Result<Vec<AppCaptureRule>, String> {
+    queries::get_app_capture_rules(&db.0)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create an app capture rule, or update the action of the rule for the same app.
+/// Returns the rule id.
+#[tauri::command]
+pub async fn save_app_capture_rule(
+    db: State<'_, DbPool>,
+    rule: AppCaptureRule,
+) -> Result<i64, String> {
+    let app = rule.app.trim();
+    if app.is_empty() {
+        return Err("App must not be empty".to_string());
+    }
+    if !matches!(rule.action.as_str(), "exclude" | "include") {
+        return Err(format!("Unknown capture action: {}", rule.action));
+    }
+    let rule = AppCaptureRule {
+        app: app.to_string(),
+        ..rule
+    };
+    queries::upsert_app_capture_rule(&db.0, &rule)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete an app capture rule.
+#[tauri::command]
+pub async fn delete_app_capture_rule(db: State<'_, DbPool>, id: i64) -> Result<(), String> {
+    queries::delete_app_capture_rule(&db.0, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List capture ignore rules in the order they're checked.
+#[tauri::command]
+pub async fn get_capture_ignore_rules(
+    db: State<'_, DbPool>,
+) -> Result<Vec<CaptureIgnoreRule>, String> {
+    queries::get_capture_ignore_rules(&db.0)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create a capture ignore rule (id 0) or update an existing one. Returns the rule id.
+#[tauri::command]
+pub async fn save_capture_ignore_rule(
+    db: State<'_, DbPool>,
+    rule: CaptureIgnoreRule,
+) -> Result<i64, String> {
+    ignore::validate_rule(&rule)?;
+    let rule = CaptureIgnoreRule {
+        value: rule.value.trim().to_string(),
+        ..rule
+    };
+    queries::save_capture_ignore_rule(&db.0, &rule)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a capture ignore rule.
+#[tauri::command]
+pub async fn delete_capture_ignore_rule(db: State<'_, DbPool>, id: i64) -> Result<(), String> {
+    queries::delete_capture_ignore_rule(&db.0, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The ignore rule that would skip `sample` when copied as `content_type`
+/// (a path for files), or None if it would be recorded.
+#[tauri::command]
+pub async fn test_capture_rules(
+    db: State<'_, DbPool>,
+    content_type: ContentType,
+    sample: String,
+) -> Result<Option<CaptureIgnoreRule>, String> {
+    let rules = queries::get_capture_ignore_rules(&db.0)
         .await
         .map_err(|e| e.to_string())?;
+    Ok(ignore::first_ignore_rule(&rules, &content_type, Some(sample.as_str())).cloned())
+}
+
+/// Number of affected items included in a retention preview.
+const RETENTION_PREVIEW_SAMPLE: i64 = 20;
+
+/// Retention policy parameters: (policy, days, count, size budget in MB).
+/// Explicit overrides win over the stored settings.
+async fn retention_params(
+    db: &DbPool,
+    policy: Option<String>,
+    days: Option<i64>,
+    count: Option<i64>,
+    size_mb: Option<i64>,
+) -> Result<(String, i64, i64, i64), String> {
+    async fn setting_i64(db: &DbPool, key: &str) -> Result<i64, String> {
+        Ok(queries::get_setting(&db.0, key)
+            .await
+            .map_err(|e| e.to_string())?
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(0))
+    }
 
-    let deleted = queries::cleanup_by_retention(&db.0, &policy, days, count)
+    let policy = match policy {
+        Some(p) => p,
+        None => queries::get_setting(&db.0, "retention_policy")
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| "unlimited".to_string()),
+    };
+    let days = match days {
+        Some(d) => d,
+        None => setting_i64(db, "retention_days").await?,
+    };
+    let count = match count {
+        Some(c) => c,
+        None => setting_i64(db, "retention_count").await?,
+    };
+    let size_mb = match size_mb {
+        Some(s) => s,
+        None => setting_i64(db, "retention_size_mb").await?,
+    };
+    Ok((policy, days, count, size_mb))
+}
+
+/// The `size` policy's budget in bytes (0 when unset, or for other policies).
+/// Usage is measured logically by the queries, so no VACUUM is needed between runs.
+fn retention_size_budget(policy: &str, size_mb: i64) -> i64 {
+    if policy == "size" && size_mb > 0 {
+        size_mb * 1024 * 1024
+    } else {
+        0
+    }
+}
+
+/// Report what a retention run would delete, without deleting anything.
+/// Arguments override the stored settings, so a policy can be checked before enabling it.
+#[tauri::command]
+pub async fn preview_retention(
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    policy: Option<String>,
+    days: Option<i64>,
+    count: Option<i64>,
+    size_mb: Option<i64>,
+) -> Result<RetentionPreview, String> {
+    let (policy, days, count, size_mb) =
+        retention_params(&db, policy, days, count, size_mb).await?;
+    let mut preview = queries::preview_retention(
+        &db.0,
+        &policy,
+        days,
+        count,
+        retention_size_budget(&policy, size_mb),
+        RETENTION_PREVIEW_SAMPLE,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    // Counts are fine to show while locked, item contents are not
+    if lock.ensure_unlocked().is_err() {
+        preview.sample.clear();
+    }
+    Ok(preview)
+}
+
+/// Apply the configured retention policy (`days`, `count` or `size`) and the
+/// per-type retention rules. Returns the number of deleted items.
+pub async fn apply_retention_policy(app: &AppHandle) -> Result<i64, String> {
+    let db = app.state::<DbPool>();
+    let (policy, days, count, size_mb) = retention_params(&db, None, None, None, None).await?;
+    let size_budget = retention_size_budget(&policy, size_mb);
+
+    // Collect image paths before deleting rows
+    let image_paths =
+        queries::get_retention_overflow_image_paths(&db.0, &policy, days, count, size_budget)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let deleted = queries::cleanup_by_retention(&db.0, &policy, days, count, size_budget)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Async file removal — best-effort
-    if !image_paths.is_empty() {
-        tokio::spawn(async move {
-            for path in image_paths {
-                if let Err(e) = tokio::fs::remove_file(&path).await {
-                    log::warn!("Failed to delete image file {}: {}", path, e);
-                }
-            }
-        });
+    remove_released_images(&db.0, image_paths).await;
+    if deleted > 0 {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { cleanup_orphan_thumbnails(&app).await });
     }
 
     Ok(deleted)
 }
 
 /// Scan for orphan image files on disk not referenced in the DB and delete them.
-/// Called once at startup as a best-effort GC; errors are only logged, never fatal.
-pub async fn cleanup_orphan_images(app: &AppHandle) {
+/// Run by the maintenance scheduler as a best-effort GC; errors are only logged, never fatal.
+/// Returns the number of files removed.
+pub async fn cleanup_orphan_images(app: &AppHandle) -> u32 {
     let app_data_dir = match app.path().app_data_dir() {
         Ok(d) => d,
         Err(e) => {
             log::warn!("cleanup_orphan_images: could not get app_data_dir: {}", e);
-            return;
+            return 0;
         }
     };
 
     let pool = match app.try_state::<crate::db::DbPool>() {
         Some(p) => p,
-        None => return,
+        None => return 0,
     };
 
+    // A file saved by a copy that isn't inserted yet would look orphaned
+    let _store = clip_util::IMAGE_STORE_LOCK.lock().await;
+    if let Err(e) = queries::purge_unreferenced_image_blobs(&pool.0).await {
+        log::warn!("cleanup_orphan_images: failed to purge image blobs: {}", e);
+    }
+
     let known_paths: std::collections::HashSet<String> =
         match queries::get_all_image_paths(&pool.0).await {
             Ok(paths) => paths.into_iter().collect(),
             Err(e) => {
                 log::warn!("cleanup_orphan_images: failed to query DB: {}", e);
-                return;
+                return 0;
             }
         };
 
     let images_dir = app_data_dir.join("images");
     if !images_dir.exists() {
-        return;
+        return 0;
     }
 
-    // Walk `images/{YYYY-MM}/` subdirectories
+    // Walk `images/{shard}/` subdirectories
     let month_dirs = match std::fs::read_dir(&images_dir) {
         Ok(d) => d,
         Err(e) => {
             log::warn!("cleanup_orphan_images: cannot read images dir: {}", e);
-            return;
+            return 0;
         }
     };
 
@@ -613,6 +1398,96 @@ pub async fn cleanup_orphan_images(app: &AppHandle) {
             orphan_count
         );
     }
+    orphan_count
+}
+
+/// Move originals saved before content-addressed storage (`images/YYYY-MM/{uuid}.ext`)
+/// into `images/{hash[0..2]}/{hash}.ext`, merging byte-identical files.
+/// Runs at startup before orphan GC; items whose file is missing are left untouched.
+pub async fn migrate_legacy_images(app: &AppHandle) {
+    let app_data_dir = match app.path().app_data_dir() {
+        Ok(d) => d,
+        Err(e) => {
+            log::warn!("migrate_legacy_images: could not get app_data_dir: {}", e);
+            return;
+        }
+    };
+
+    let pool = match app.try_state::<crate::db::DbPool>() {
+        Some(p) => p,
+        None => return,
+    };
+
+    let legacy = match queries::get_legacy_image_items(&pool.0).await {
+        Ok(items) => items,
+        Err(e) => {
+            log::warn!("migrate_legacy_images: failed to query DB: {}", e);
+            return;
+        }
+    };
+    if legacy.is_empty() {
+        return;
+    }
+
+    let mut migrated = 0u32;
+    for (id, old_path) in legacy {
+        let data = match tokio::fs::read(&old_path).await {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("migrate_legacy_images: cannot read {}: {}", old_path, e);
+                continue;
+            }
+        };
+
+        let ext = std::path::Path::new(&old_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("png")
+            .to_lowercase();
+        let dir = app_data_dir.clone();
+        let stored = tokio::task::spawn_blocking(move || {
+            let hash = clip_util::compute_hash(&data);
+            clip_util::save_original_image(&dir, &data, &hash, &ext)
+                .map(|path| (path, hash, data.len() as i64))
+        })
+        .await;
+
+        let (new_path, hash, size) = match stored {
+            Ok(Ok(v)) => v,
+            Ok(Err(e)) => {
+                log::warn!("migrate_legacy_images: failed to store {}: {}", old_path, e);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("migrate_legacy_images: task failed: {}", e);
+                continue;
+            }
+        };
+
+        if let Err(e) = queries::set_item_image(&pool.0, &id, &new_path, &hash, size).await {
+            log::warn!("migrate_legacy_images: failed to update item {}: {}", id, e);
+            continue;
+        }
+
+        if new_path != old_path {
+            let _ = tokio::fs::remove_file(&old_path).await;
+        }
+        migrated += 1;
+    }
+
+    // Remove month directories emptied by the move (remove_dir fails on non-empty dirs)
+    if let Ok(entries) = std::fs::read_dir(app_data_dir.join("images")) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                let _ = std::fs::remove_dir(entry.path());
+            }
+        }
+    }
+
+    log::info!(
+        "migrate_legacy_images: moved {} image(s) to content-addressed storage",
+        migrated
+    );
 }
 
 /// Show the preview window with adaptive sizing based on content.
@@ -625,8 +1500,11 @@ pub async fn show_preview_window(
     db: State<'_, DbPool>,
     preview_state: State<'_, PreviewState>,
     closing: State<'_, PreviewClosing>,
+    lock: State<'_, AppLock>,
     id: String,
 ) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+
     // Cancel any in-progress close animation
     closing.0.store(false, std::sync::atomic::Ordering::SeqCst);
 
@@ -906,15 +1784,14 @@ const TEXT_EXTENSIONS: &[&str] = &[
     "cfg",
 ];
 
-/// Image file extensions that should be rendered as images in preview.
-const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "ico", "svg"];
-
 /// Read file content for preview (first N bytes, up to 200 lines).
 #[tauri::command]
 pub async fn read_file_preview(
+    lock: State<'_, AppLock>,
     path: String,
     max_bytes: Option<usize>,
 ) -> Result<FilePreviewData, String> {
+    lock.ensure_unlocked()?;
     let max = max_bytes.unwrap_or(50 * 1024); // default 50KB
     let path_ref = std::path::Path::new(&path);
 
@@ -1021,18 +1898,117 @@ pub async fn open_settings_window(app: AppHandle, db: State<'_, DbPool>) -> Resu
     Ok(())
 }
 
-/// Check if a file path has an image extension.
-fn is_image_file(path: &str) -> bool {
-    let lower = path.to_lowercase();
-    lower.ends_with(".png")
-        || lower.ends_with(".jpg")
-        || lower.ends_with(".jpeg")
-        || lower.ends_with(".gif")
-        || lower.ends_with(".webp")
-        || lower.ends_with(".bmp")
-        || lower.ends_with(".tiff")
-        || lower.ends_with(".tif")
-        || lower.ends_with(".ico")
+/// Whether the app capture rules allow recording a copy from `source`.
+/// Rules that can't be loaded deny the copy, unless there are none at all.
+pub async fn source_app_allowed(app: &AppHandle, source: &crate::platform::SourceApp) -> bool {
+    let db = app.state::<DbPool>();
+    match queries::get_app_capture_rules(&db.0).await {
+        Ok(rules) => clip_util::app_capture_allowed(&rules, source),
+        Err(e) => {
+            // An unreadable exclusion list must not let excluded apps through
+            log::error!("Failed to load app capture rules: {}", e);
+            matches!(queries::has_app_capture_rules(&db.0).await, Ok(false))
+        }
+    }
+}
+
+/// How long sensitive items are kept, from settings.
+async fn sensitive_ttl_secs(db: &DbPool) -> i64 {
+    queries::get_setting(&db.0, clip_util::SENSITIVE_TTL_KEY)
+        .await
+        .unwrap_or(None)
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(clip_util::DEFAULT_SENSITIVE_TTL_SECS)
+}
+
+/// Mark a stored item sensitive and schedule its deletion after the configured TTL.
+async fn mark_item_sensitive(app: &AppHandle, db: &DbPool, id: &str) -> Result<(), String> {
+    let ttl = sensitive_ttl_secs(db).await;
+    queries::mark_sensitive(&db.0, id, ttl)
+        .await
+        .map_err(|e| e.to_string())?;
+    schedule_expiry_sweep(app.clone(), std::time::Duration::from_secs(ttl as u64));
+    Ok(())
+}
+
+/// Whether secret detection is on and flags `text`. Invalid custom patterns
+/// (saved before validation existed or edited in the DB) fall back to the built-in rules.
+async fn detect_sensitive(db: &DbPool, text: Option<&str>) -> bool {
+    let Some(text) = text else {
+        return false;
+    };
+    let enabled = queries::get_setting(&db.0, SENSITIVE_DETECTION_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    if !enabled {
+        return false;
+    }
+    let patterns = queries::get_setting(&db.0, SENSITIVE_PATTERNS_KEY)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let detector = SensitiveDetector::new(&patterns).unwrap_or_else(|e| {
+        log::warn!("Ignoring custom sensitive patterns: {}", e);
+        SensitiveDetector::new("").expect("no custom patterns")
+    });
+    match detector.detect(text) {
+        Some(kind) => {
+            log::info!("Copied text looks like a secret ({:?})", kind);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Run `sweep_expired_items` after `delay`.
+pub fn schedule_expiry_sweep(app: AppHandle, delay: std::time::Duration) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        sweep_expired_items(&app).await;
+    });
+}
+
+/// Tell the frontend the history changed. The item id is left out while the
+/// app is locked, so a locked window learns nothing about new copies.
+pub fn emit_clipboard_changed(app: &AppHandle, id: &str) {
+    let payload = if app.state::<AppLock>().is_locked() {
+        serde_json::json!({})
+    } else {
+        serde_json::json!({ "id": id })
+    };
+    let _ = app.emit("clipboard-changed", payload);
+}
+
+/// Delete expired items, then schedule another sweep if more are pending.
+pub async fn sweep_expired_items(app: &AppHandle) {
+    let db = app.state::<DbPool>();
+    match queries::delete_expired_items(&db.0).await {
+        Ok(0) => {}
+        Ok(count) => {
+            log::info!("Deleted {} expired items", count);
+            let _ = app.emit("clipboard-changed", serde_json::json!({}));
+        }
+        Err(e) => log::error!("Failed to delete expired items: {}", e),
+    }
+    match queries::next_expiry_secs(&db.0).await {
+        Ok(Some(secs)) => schedule_expiry_sweep(
+            app.clone(),
+            std::time::Duration::from_secs(secs.max(1) as u64),
+        ),
+        Ok(None) => {}
+        Err(e) => log::error!("Failed to look up next expiry: {}", e),
+    }
+}
+
+/// A copy that `process_clipboard_change` recorded or bumped.
+pub struct StoredClip {
+    pub id: String,
+    /// Stored as a sensitive item; callers keep it out of PRIMARY and clipboard ownership.
+    pub sensitive: bool,
 }
 
 /// Process and store a new clipboard entry from the monitoring system.
@@ -1048,7 +2024,9 @@ pub async fn process_clipboard_change(
     file_name: Option<String>,
     source_app: String,
     source_app_name: String,
-) -> Result<Option<String>, String> {
+    selection: SelectionOrigin,
+    sensitive: bool,
+) -> Result<Option<StoredClip>, String> {
     let db = app.state::<DbPool>();
 
     // Size check with dynamic limit from settings
@@ -1066,33 +2044,172 @@ pub async fn process_clipboard_change(
         return Ok(None);
     }
 
-    // Compute hash for dedup
-    let hash = clip_util::compute_hash(&content);
+    // User ignore rules (one-time codes, stray whitespace, unwanted file types)
+    match queries::get_capture_ignore_rules(&db.0).await {
+        Ok(rules) => {
+            if let Some(rule) =
+                ignore::first_ignore_rule(&rules, &content_type, plain_text.as_deref())
+            {
+                log::info!(
+                    "Clipboard content matches ignore rule {} ({}), skipping",
+                    rule.id,
+                    rule.kind
+                );
+                return Ok(None);
+            }
+        }
+        Err(e) => log::error!("Failed to load capture ignore rules: {}", e),
+    }
+
+    // Copies the source didn't mark may still look like secrets
+    let sensitive =
+        sensitive || (content_type.is_text() && detect_sensitive(&db, plain_text.as_deref()).await);
+
+    // Compute hash for dedup (text types may hash a normalized form)
+    let hash = if content_type.is_text() {
+        let mode = TextDedupMode::from_setting(
+            queries::get_setting(&db.0, clip_util::TEXT_DEDUP_MODE_KEY)
+                .await
+                .unwrap_or(None)
+                .as_deref(),
+        );
+        clip_util::compute_text_hash(&content, mode)
+    } else {
+        clip_util::compute_hash(&content)
+    };
 
-    // Dedup check
-    if let Some(existing_id) = queries::find_and_bump_by_hash(&db.0, &hash)
+    // Dedup check. Items stored before normalization was enabled carry the raw
+    // hash, so fall back to it when the normalized hash differs.
+    let mut existing = queries::find_and_bump_by_hash(&db.0, &hash)
         .await
-        .map_err(|e| e.to_string())?
-    {
+        .map_err(|e| e.to_string())?;
+    let raw_hash = clip_util::compute_hash(&content);
+    if existing.is_none() && raw_hash != hash {
+        existing = queries::find_and_bump_by_hash(&db.0, &raw_hash)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(existing_id) = existing {
         log::info!("Duplicate content detected, bumped item {}", existing_id);
-        return Ok(Some(existing_id));
+        // Text types hash their plain text, so a rich copy can land on a plain
+        // item (or vice versa): keep the formatted version either way
+        if content_type == ContentType::RichText {
+            if let Some(rich) = &rich_content {
+                if queries::merge_rich_content(&db.0, &existing_id, rich)
+                    .await
+                    .map_err(|e| e.to_string())?
+                {
+                    log::info!("Merged rich text into plain item {}", existing_id);
+                }
+            }
+        }
+        // An explicit copy of a selected item promotes it to the clipboard;
+        // selecting text that was already copied keeps its origin
+        if selection == SelectionOrigin::Clipboard {
+            queries::set_selection_origin(&db.0, &existing_id, selection)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        // A secret that was already recorded in the clear is hidden from now on
+        if sensitive {
+            mark_item_sensitive(app, &db, &existing_id).await?;
+        }
+        return Ok(Some(StoredClip {
+            id: existing_id,
+            sensitive,
+        }));
+    }
+
+    // Decode images once: the perceptual hash and the thumbnail cache share the result
+    let decoded = if content_type == ContentType::Image {
+        let content_for_img = content.clone();
+        tokio::task::spawn_blocking(move || {
+            crate::thumbnails::decode_image(&content_for_img, None)
+                .map_err(|e| log::warn!("Failed to generate thumbnail: {}", e))
+                .ok()
+        })
+        .await
+        .unwrap_or(None)
+    } else {
+        None
+    };
+    let perceptual_hash = decoded.as_ref().map(clip_util::compute_dhash);
+
+    // Near-duplicate check: re-encoded or recompressed copies of a stored image
+    let mut variant_of = None;
+    if let (Some(img), Some(phash)) = (&decoded, perceptual_hash) {
+        let aspect_ratio = img.width() as f64 / img.height().max(1) as f64;
+        if let Some((mode, existing_id, existing_hash)) =
+            find_near_duplicate(&db, phash, aspect_ratio).await
+        {
+            if mode == NearDuplicateMode::Bump {
+                queries::find_and_bump_by_hash(&db.0, &existing_hash)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                log::info!("Near-duplicate image detected, bumped item {}", existing_id);
+                return Ok(Some(StoredClip {
+                    id: existing_id,
+                    sensitive,
+                }));
+            }
+            // The primary is bumped once the variant is grouped under it
+            log::info!("Near-duplicate image grouped under item {}", existing_id);
+            variant_of = Some(existing_id);
+        }
     }
 
-    // Process image: generate thumbnail and save original (off the async runtime)
+    // A saved original must be registered by the insert below before any release
+    // of the same file can run
+    let store_guard = if content_type == ContentType::Image {
+        Some(clip_util::IMAGE_STORE_LOCK.lock().await)
+    } else {
+        None
+    };
+
+    // Process image: render the thumbnail cache and save original (off the async runtime)
     // Note: For file-type images, thumbnail is generated asynchronously after insert (see below)
-    let (thumbnail, image_path) = if content_type == ContentType::Image {
+    let (image_path, image_metadata) = if content_type == ContentType::Image {
         let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
         let content_for_img = content.clone();
+        let hash_for_img = hash.clone();
+        let strip = strip_metadata_enabled(&db).await;
         tokio::task::spawn_blocking(move || {
-            let thumb = clip_util::generate_thumbnail(&content_for_img).ok();
-            let path = clip_util::save_original_image(&app_data, &content_for_img, "png").ok();
-            (thumb, path)
+            if let Some(img) = decoded {
+                if let Err(e) =
+                    crate::thumbnails::write_image_thumbnails(&app_data, &hash_for_img, &img)
+                {
+                    log::warn!("Failed to generate thumbnail: {}", e);
+                }
+            }
+            let mut meta = metadata::extract_image_metadata(&content_for_img);
+            let original = if strip {
+                if let Some(meta) = meta.as_mut() {
+                    metadata::clear_exif(meta);
+                }
+                match metadata::strip_image_metadata(&content_for_img) {
+                    Ok(stripped) => stripped.unwrap_or(content_for_img),
+                    Err(e) => {
+                        log::warn!("Failed to strip image metadata: {}", e);
+                        content_for_img
+                    }
+                }
+            } else {
+                content_for_img
+            };
+            // Keep the original encoding (JPEG stays small, GIF keeps its animation),
+            // stored under its content hash so re-copies share one file on disk
+            let (ext, mime) = clip_util::detect_image_format(&original);
+            let path =
+                clip_util::save_original_image(&app_data, &original, &hash_for_img, ext).ok();
+            (path.map(|p| (p, mime.to_string())), meta)
         })
         .await
         .unwrap_or((None, None))
     } else {
         (None, None)
     };
+    let (image_path, image_mime) = image_path.unzip();
+    let image_hash = image_path.as_ref().map(|_| hash.clone());
 
     // For file items, use the actual file size instead of the path string length
     let content_size = if content_type == ContentType::File {
@@ -1107,23 +2224,33 @@ pub async fn process_clipboard_change(
         content.len() as i64
     };
 
+    // Secrets are flagged sensitive in the same transaction as the insert, so list
+    // and search queries never return them in the clear
+    let sensitive_ttl = if sensitive {
+        Some(sensitive_ttl_secs(&db).await)
+    } else {
+        None
+    };
     let new_item = NewClipboardItem {
         content_type,
         plain_text: plain_text.unwrap_or_default(),
         rich_content,
-        thumbnail,
         image_path,
+        image_hash,
+        image_mime,
         file_path,
         file_name,
         source_app,
         source_app_name,
         content_size,
         content_hash: hash,
+        sensitive_ttl_secs: sensitive_ttl,
     };
 
     let id = queries::insert_item(&db.0, &new_item)
         .await
         .map_err(|e| e.to_string())?;
+    drop(store_guard);
 
     log::info!(
         "New clipboard item stored: {} ({})",
@@ -1131,47 +2258,40 @@ pub async fn process_clipboard_change(
         new_item.content_type.as_str()
     );
 
-    // Background: generate thumbnail for image files (non-blocking)
+    if let Some(meta) = image_metadata {
+        if let Err(e) = queries::set_image_metadata(&db.0, &id, &meta).await {
+            log::warn!("Failed to record image metadata: {}", e);
+        }
+    }
+    if let Some(ttl) = sensitive_ttl {
+        schedule_expiry_sweep(app.clone(), std::time::Duration::from_secs(ttl as u64));
+    }
+    if selection != SelectionOrigin::Clipboard {
+        if let Err(e) = queries::set_selection_origin(&db.0, &id, selection).await {
+            log::warn!("Failed to record selection origin: {}", e);
+        }
+    }
+    if let Some(phash) = perceptual_hash {
+        if let Err(e) = queries::set_perceptual_hash(&db.0, &id, phash, variant_of.as_deref()).await
+        {
+            log::warn!("Failed to record perceptual hash: {}", e);
+        }
+    }
+
+    // Background: queue thumbnail generation for image files (retried if the file isn't ready)
     if new_item.content_type == ContentType::File {
-        if let Some(ref fp) = new_item.file_path {
-            if is_image_file(fp) {
-                let pool = db.0.clone();
-                let id_clone = id.clone();
-                let fp_clone = fp.clone();
-                let app_clone = app.clone();
-                tauri::async_runtime::spawn(async move {
-                    let data = match tokio::fs::read(&fp_clone).await {
-                        Ok(d) => d,
-                        Err(e) => {
-                            log::warn!("Failed to read image file for thumbnail: {}", e);
-                            return;
-                        }
-                    };
-                    let thumb = match tokio::task::spawn_blocking(move || {
-                        clip_util::generate_thumbnail(&data)
-                    })
-                    .await
-                    {
-                        Ok(Ok(t)) => t,
-                        _ => {
-                            log::warn!("Failed to generate thumbnail for file");
-                            return;
-                        }
-                    };
-                    if let Err(e) = queries::update_thumbnail(&pool, &id_clone, &thumb).await {
-                        log::warn!("Failed to update thumbnail: {}", e);
-                        return;
-                    }
-                    // Notify frontend to refresh with the new thumbnail
-                    let _ =
-                        app_clone.emit("clipboard-changed", serde_json::json!({ "id": id_clone }));
-                    log::info!("Thumbnail generated for file item: {}", id_clone);
-                });
+        if let Some(job) = new_item
+            .file_path
+            .as_deref()
+            .and_then(|fp| ThumbnailJob::for_file(&id, &new_item.content_hash, fp))
+        {
+            if let Err(e) = jobs::enqueue(&app, jobs::THUMBNAIL_JOB, &job).await {
+                log::warn!("Failed to queue thumbnail job: {}", e);
             }
         }
     }
 
-    Ok(Some(id))
+    Ok(Some(StoredClip { id, sensitive }))
 }
 
 /// Update main window visual effects to match the given theme.
@@ -1281,6 +2401,12 @@ pub async fn open_url(url: String) -> Result<(), String> {
 #[tauri::command]
 pub async fn get_storage_size(app: AppHandle) -> Result<u64, String> {
     let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(compute_storage_size(&app_data))
+}
+
+/// Bytes used on disk by the database (with WAL/SHM) and the `images/` and
+/// `thumbnails/` directories.
+pub fn compute_storage_size(app_data: &std::path::Path) -> u64 {
     let mut total: u64 = 0;
 
     // Database file + WAL/SHM
@@ -1290,28 +2416,27 @@ pub async fn get_storage_size(app: AppHandle) -> Result<u64, String> {
         }
     }
 
-    // Images directory (recursive)
-    let images_dir = app_data.join("images");
-    if images_dir.exists() {
-        fn dir_size(path: &std::path::Path) -> u64 {
-            let mut size = 0;
-            if let Ok(entries) = std::fs::read_dir(path) {
-                for entry in entries.flatten() {
-                    if let Ok(ft) = entry.file_type() {
-                        if ft.is_file() {
-                            size += entry.metadata().map(|m| m.len()).unwrap_or(0);
-                        } else if ft.is_dir() {
-                            size += dir_size(&entry.path());
-                        }
+    // Originals and the thumbnail cache (recursive)
+    fn dir_size(path: &std::path::Path) -> u64 {
+        let mut size = 0;
+        if let Ok(entries) = std::fs::read_dir(path) {
+            for entry in entries.flatten() {
+                if let Ok(ft) = entry.file_type() {
+                    if ft.is_file() {
+                        size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    } else if ft.is_dir() {
+                        size += dir_size(&entry.path());
                     }
                 }
             }
-            size
         }
-        total += dir_size(&images_dir);
+        size
+    }
+    for dir in ["images", "thumbnails"] {
+        total += dir_size(&app_data.join(dir));
     }
 
-    Ok(total)
+    total
 }
 
 /// Set tray icon visibility at runtime (macOS only).
@@ -0,0 +1,125 @@
+use crate::db::{queries, DbPool};
+use crate::lock::{self, AppLock};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+/// Current app lock state returned to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub idle_minutes: u64,
+}
+
+/// Get whether the app lock is configured and currently engaged.
+#[tauri::command]
+pub fn get_app_lock_status(lock: State<'_, AppLock>) -> AppLockStatus {
+    AppLockStatus {
+        enabled: lock.is_enabled(),
+        locked: lock.is_locked(),
+        idle_minutes: lock.idle_minutes(),
+    }
+}
+
+/// Verify a secret against the stored hash without blocking the async runtime.
+async fn check_secret(db: &DbPool, secret: String) -> Result<bool, String> {
+    let Some(stored) = queries::get_setting(&db.0, lock::LOCK_HASH_KEY)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(true);
+    };
+    tokio::task::spawn_blocking(move || lock::verify_secret(&secret, &stored))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Unlock history access with the PIN/passphrase.
+#[tauri::command]
+pub async fn unlock(
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    secret: String,
+) -> Result<(), String> {
+    if !lock.is_enabled() {
+        return Ok(());
+    }
+
+    if !check_secret(&db, secret).await? {
+        // Slow down brute-force attempts
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        return Err("Incorrect PIN or passphrase".to_string());
+    }
+
+    lock.unlock();
+    Ok(())
+}
+
+/// Lock the app immediately (e.g. from a "Lock now" action).
+#[tauri::command]
+pub fn lock_app(app: AppHandle, lock: State<'_, AppLock>) {
+    if lock.is_enabled() {
+        lock.lock();
+        let _ = app.emit("app-locked", ());
+    }
+}
+
+/// Set, change or remove the PIN/passphrase.
+/// `current_secret` is required when a lock is already configured.
+/// Passing `secret: None` disables the lock.
+#[tauri::command]
+pub async fn set_app_lock(
+    db: State<'_, DbPool>,
+    lock: State<'_, AppLock>,
+    secret: Option<String>,
+    current_secret: Option<String>,
+) -> Result<(), String> {
+    if lock.is_enabled() && !check_secret(&db, current_secret.unwrap_or_default()).await? {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        return Err("Incorrect PIN or passphrase".to_string());
+    }
+
+    match secret {
+        Some(secret) => {
+            if secret.chars().count() < lock::MIN_SECRET_LEN {
+                return Err(format!(
+                    "PIN or passphrase must be at least {} characters",
+                    lock::MIN_SECRET_LEN
+                ));
+            }
+            let hash = tokio::task::spawn_blocking(move || lock::hash_secret(&secret))
+                .await
+                .map_err(|e| e.to_string())?;
+            queries::set_setting(&db.0, lock::LOCK_HASH_KEY, &hash)
+                .await
+                .map_err(|e| e.to_string())?;
+            lock.set_enabled(true);
+            // The user just proved knowledge of the secret — stay unlocked
+            lock.unlock();
+        }
+        None => {
+            queries::delete_setting(&db.0, lock::LOCK_HASH_KEY)
+                .await
+                .map_err(|e| e.to_string())?;
+            lock.set_enabled(false);
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the lock state from settings at startup.
+pub async fn load_app_lock(db: &DbPool) -> AppLock {
+    let enabled = queries::get_setting(&db.0, lock::LOCK_HASH_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some();
+    let idle_minutes = queries::get_setting(&db.0, lock::LOCK_IDLE_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(lock::DEFAULT_IDLE_MINUTES);
+    AppLock::new(enabled, idle_minutes)
+}
@@ -0,0 +1,14 @@
+use crate::maintenance::{Maintenance, MaintenanceStatus};
+use tauri::State;
+
+/// Get the status of the last background maintenance run.
+#[tauri::command]
+pub fn get_maintenance_status(maintenance: State<'_, Maintenance>) -> MaintenanceStatus {
+    maintenance.status()
+}
+
+/// Run retention, GC and database maintenance now instead of waiting for the next interval.
+#[tauri::command]
+pub fn run_maintenance(maintenance: State<'_, Maintenance>) {
+    maintenance.trigger();
+}
@@ -1 +1,4 @@
+pub mod capture;
 pub mod clipboard;
+pub mod lock;
+pub mod maintenance;
@@ -22,7 +22,14 @@ impl ContentType {
         }
     }
 
-    #[allow(dead_code)]
+    /// Types whose content bytes are UTF-8 text.
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            ContentType::PlainText | ContentType::RichText | ContentType::Link
+        )
+    }
+
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "plain_text" => Some(ContentType::PlainText),
@@ -35,15 +42,31 @@ impl ContentType {
     }
 }
 
-/// A clipboard item stored in the database.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Selection a clipboard item was captured from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionOrigin {
+    /// The regular clipboard (explicit copy).
+    Clipboard,
+    /// The X11/Wayland PRIMARY selection (select-to-copy).
+    Primary,
+}
+
+impl SelectionOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SelectionOrigin::Clipboard => "clipboard",
+            SelectionOrigin::Primary => "primary",
+        }
+    }
+}
+
+/// A clipboard item stored in the database, read with `ITEM_COLUMNS` in `queries.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ClipboardItem {
     pub id: String,
     pub content_type: String,
     pub plain_text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub thumbnail: Option<Vec<u8>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub image_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_path: Option<String>,
@@ -56,6 +79,13 @@ pub struct ClipboardItem {
     pub is_favorited: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// Selection the item was captured from (`clipboard` | `primary`).
+    pub selection: String,
+    /// Hidden on read (list and preview queries return empty text; the stored text
+    /// is kept for paste), unsearchable and deleted once it expires.
+    pub is_sensitive: bool,
+    /// Near-duplicate images grouped under this item.
+    pub variant_count: i64,
 }
 
 /// Full item detail returned for preview (includes rich_content as string).
@@ -68,11 +98,36 @@ pub struct ItemDetail {
     pub rich_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_path: Option<String>,
+    /// MIME type of the stored original (e.g. `image/gif`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_mime: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_name: Option<String>,
     pub content_size: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_metadata: Option<ImageMetadata>,
+}
+
+/// Properties of a stored image, read from its header and EXIF on capture.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    /// Container format, e.g. `jpeg`.
+    pub format: String,
+    /// Decoded color type, e.g. `rgba8`.
+    pub color_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camera: Option<String>,
+    /// EXIF capture time (`YYYY-MM-DDTHH:MM:SS`, camera local time).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taken_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gps_latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gps_longitude: Option<f64>,
 }
 
 /// Shared state holding the current preview item detail.
@@ -96,19 +151,122 @@ pub struct FilePreviewData {
     pub total_lines: usize,
 }
 
+/// A claimed background job from the `jobs` queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub id: i64,
+    pub kind: String,
+    /// JSON payload, interpreted per `kind`.
+    pub payload: String,
+    /// Attempts so far, including the one in progress.
+    pub attempts: i64,
+    pub max_attempts: i64,
+}
+
+/// Snapshot of the job queue, reported with `job-progress` events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobCounts {
+    pub pending: i64,
+    pub running: i64,
+    pub failed: i64,
+}
+
+/// Item filter shared by search and filtered clear. Unset fields match everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemFilter {
+    pub content_type: Option<String>,
+    pub favorites_only: bool,
+    /// Exact source app identifier.
+    pub source_app: Option<String>,
+    /// Only items in this group.
+    pub group_id: Option<String>,
+    /// Only items that belong to no group.
+    pub ungrouped: bool,
+    /// Only items created more than this many days ago.
+    pub older_than_days: Option<i64>,
+    /// Only items captured from this selection (`clipboard` | `primary`).
+    pub selection: Option<String>,
+}
+
+/// A retention rule for one content type, optionally narrowed to a source app.
+/// Each limit is optional; favorites are never removed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionRule {
+    #[serde(default)]
+    pub id: i64,
+    pub content_type: String,
+    /// Empty matches every source app.
+    #[serde(default)]
+    pub source_app: String,
+    pub max_days: Option<i64>,
+    pub max_count: Option<i64>,
+    pub max_size_mb: Option<i64>,
+}
+
+/// Items a retention run would delete, as reported by `preview_retention`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionPreview {
+    pub total_items: i64,
+    /// Stored bytes freed (text, rich content and images; files only store a path).
+    pub total_bytes: i64,
+    pub by_type: Vec<RetentionTypeSummary>,
+    /// Most recently used of the affected items.
+    pub sample: Vec<ClipboardItem>,
+}
+
+/// Per-content-type share of a `RetentionPreview`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionTypeSummary {
+    pub content_type: String,
+    pub items: i64,
+    pub bytes: i64,
+}
+
+/// Whether copies from an app are recorded. Any `include` rule turns the
+/// list into an allowlist; `exclude` rules always win.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppCaptureRule {
+    #[serde(default)]
+    pub id: i64,
+    /// Source app id, executable name or display name (case-insensitive).
+    pub app: String,
+    /// `exclude` | `include`
+    pub action: String,
+}
+
+/// Content that is never recorded, checked before an item is stored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CaptureIgnoreRule {
+    #[serde(default)]
+    pub id: i64,
+    /// `regex` | `min_length` | `max_length` | `whitespace` | `extension`
+    pub kind: String,
+    /// Pattern, character count or file extension; unused for `whitespace`.
+    #[serde(default)]
+    pub value: String,
+    pub enabled: bool,
+}
+
 /// Payload for inserting a new clipboard item.
 pub struct NewClipboardItem {
     pub content_type: ContentType,
     pub plain_text: String,
     pub rich_content: Option<Vec<u8>>,
-    pub thumbnail: Option<Vec<u8>>,
     pub image_path: Option<String>,
+    /// Content hash of the stored original in `image_blobs` (images only).
+    pub image_hash: Option<String>,
+    /// MIME type of the stored original, detected from its bytes (images only).
+    pub image_mime: Option<String>,
     pub file_path: Option<String>,
     pub file_name: Option<String>,
     pub source_app: String,
     pub source_app_name: String,
     pub content_size: i64,
     pub content_hash: String,
+    /// Store the item as sensitive: hidden on read, kept out of search and deleted
+    /// after this many seconds (see `mark_sensitive`).
+    pub sensitive_ttl_secs: Option<i64>,
 }
 
 #[cfg(test)]
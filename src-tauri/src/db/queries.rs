@@ -1,7 +1,10 @@
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 use uuid::Uuid;
 
-use super::models::{ClipboardItem, NewClipboardItem};
+use super::models::{
+    AppCaptureRule, CaptureIgnoreRule, ClipboardItem, ImageMetadata, ItemFilter, Job, JobCounts,
+    NewClipboardItem, RetentionPreview, RetentionRule, RetentionTypeSummary, SelectionOrigin,
+};
 
 /// Insert a new clipboard item and sync FTS index (transactional).
 pub async fn insert_item(
@@ -12,34 +15,53 @@ pub async fn insert_item(
 
     let mut tx = pool.begin().await?;
 
+    // Register the content-addressed original first so the insert trigger can count the reference
+    if let (Some(hash), Some(path)) = (&item.image_hash, &item.image_path) {
+        sqlx::query(
+            "INSERT INTO image_blobs (hash, path, size) VALUES (?, ?, ?) ON CONFLICT(hash) DO NOTHING",
+        )
+        .bind(hash)
+        .bind(path)
+        .bind(item.content_size)
+        .execute(&mut *tx)
+        .await?;
+    }
+
     sqlx::query(
-        "INSERT INTO clipboard_items (id, content_type, plain_text, rich_content, thumbnail, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO clipboard_items (id, content_type, plain_text, rich_content, image_path, image_hash, image_mime, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_sensitive, expires_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now', ?))",
     )
     .bind(&id)
     .bind(item.content_type.as_str())
     .bind(&item.plain_text)
     .bind(&item.rich_content)
-    .bind(&item.thumbnail)
     .bind(&item.image_path)
+    .bind(&item.image_hash)
+    .bind(&item.image_mime)
     .bind(&item.file_path)
     .bind(item.file_name.as_deref().unwrap_or(""))
     .bind(&item.source_app)
     .bind(&item.source_app_name)
     .bind(item.content_size)
     .bind(&item.content_hash)
+    .bind(item.sensitive_ttl_secs.is_some())
+    // A NULL modifier makes datetime() NULL, so ordinary items never expire
+    .bind(item.sensitive_ttl_secs.map(|ttl| format!("+{} seconds", ttl.max(0))))
     .execute(&mut *tx)
     .await?;
 
-    sqlx::query(
-        "INSERT INTO clipboard_fts (item_id, plain_text, file_name, source_app_name) VALUES (?, ?, ?, ?)",
-    )
-    .bind(&id)
-    .bind(&item.plain_text)
-    .bind(item.file_name.as_deref().unwrap_or(""))
-    .bind(&item.source_app_name)
-    .execute(&mut *tx)
-    .await?;
+    // Secrets never enter the search index, not even briefly
+    if item.sensitive_ttl_secs.is_none() {
+        sqlx::query(
+            "INSERT INTO clipboard_fts (item_id, plain_text, file_name, source_app_name) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&item.plain_text)
+        .bind(item.file_name.as_deref().unwrap_or(""))
+        .bind(&item.source_app_name)
+        .execute(&mut *tx)
+        .await?;
+    }
 
     tx.commit().await?;
 
@@ -47,21 +69,54 @@ pub async fn insert_item(
 }
 
 /// Check if a clipboard item with the given hash already exists.
-/// If so, bump its updated_at and return its id.
+/// If so, bump its updated_at (and its primary's, for a grouped variant) and return its id.
 pub async fn find_and_bump_by_hash(
     pool: &SqlitePool,
     hash: &str,
 ) -> Result<Option<String>, sqlx::Error> {
-    let row: Option<(String,)> = sqlx::query_as(
-        "UPDATE clipboard_items SET updated_at = datetime('now') WHERE content_hash = ? RETURNING id",
+    let row: Option<(String, Option<String>)> = sqlx::query_as(
+        "UPDATE clipboard_items SET updated_at = datetime('now') WHERE content_hash = ? RETURNING id, variant_of",
     )
     .bind(hash)
     .fetch_optional(pool)
     .await?;
 
-    Ok(row.map(|(id,)| id))
+    // A hidden variant surfaces through its primary
+    if let Some((_, Some(primary))) = &row {
+        sqlx::query("UPDATE clipboard_items SET updated_at = datetime('now') WHERE id = ?")
+            .bind(primary)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(row.map(|(id, _)| id))
+}
+
+/// Upgrade a plain text item to rich text when the same text arrives again with
+/// formatting, so the merged item keeps the richest representation.
+/// Returns false when the item is not plain text (already rich, a link, ...).
+pub async fn merge_rich_content(
+    pool: &SqlitePool,
+    id: &str,
+    rich_content: &[u8],
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE clipboard_items SET content_type = 'rich_text', rich_content = ? WHERE id = ? AND content_type = 'plain_text'",
+    )
+    .bind(rich_content)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
 }
 
+/// Columns read into a `ClipboardItem` (via its `FromRow` derive). Sensitive
+/// items come back with their text masked.
+const ITEM_COLUMNS: &str = "id, content_type, CASE WHEN is_sensitive THEN '' ELSE plain_text END AS plain_text,
+    image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash,
+    is_favorited, created_at, updated_at, selection, is_sensitive,
+    (SELECT COUNT(*) FROM clipboard_items v WHERE v.variant_of = clipboard_items.id) AS variant_count";
+
 /// Get clipboard items with optional type filter, ordered by updated_at desc.
 /// Excludes thumbnail blobs for fast IPC transfer.
 pub async fn get_items(
@@ -70,46 +125,26 @@ pub async fn get_items(
     limit: i64,
     offset: i64,
 ) -> Result<Vec<ClipboardItem>, sqlx::Error> {
-    let items = if let Some(ct) = content_type {
-        sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, Option<String>, String, String, i64, String, bool, String, String)>(
-            "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-             FROM clipboard_items WHERE content_type = ? ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?",
-        )
+    if let Some(ct) = content_type {
+        sqlx::query_as(&format!(
+            "SELECT {} FROM clipboard_items WHERE variant_of IS NULL AND content_type = ? ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?",
+            ITEM_COLUMNS
+        ))
         .bind(ct)
         .bind(limit)
         .bind(offset)
         .fetch_all(pool)
-        .await?
+        .await
     } else {
-        sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, Option<String>, String, String, i64, String, bool, String, String)>(
-            "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-             FROM clipboard_items ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?",
-        )
+        sqlx::query_as(&format!(
+            "SELECT {} FROM clipboard_items WHERE variant_of IS NULL ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?",
+            ITEM_COLUMNS
+        ))
         .bind(limit)
         .bind(offset)
         .fetch_all(pool)
-        .await?
-    };
-
-    Ok(items
-        .into_iter()
-        .map(|r| ClipboardItem {
-            id: r.0,
-            content_type: r.1,
-            plain_text: r.2,
-            thumbnail: None,
-            image_path: r.3,
-            file_path: r.4,
-            file_name: r.5,
-            source_app: r.6,
-            source_app_name: r.7,
-            content_size: r.8,
-            content_hash: r.9,
-            is_favorited: r.10,
-            created_at: r.11,
-            updated_at: r.12,
-        })
-        .collect())
+        .await
+    }
 }
 
 /// Get a single clipboard item by id.
@@ -123,11 +158,12 @@ pub async fn get_item_by_id(
         Option<Vec<u8>>,
         Option<String>,
         Option<String>,
+        Option<String>,
     )>,
     sqlx::Error,
 > {
     sqlx::query_as(
-        "SELECT content_type, plain_text, rich_content, image_path, file_path FROM clipboard_items WHERE id = ?",
+        "SELECT content_type, plain_text, rich_content, image_path, image_mime, file_path FROM clipboard_items WHERE id = ?",
     )
     .bind(id)
     .fetch_optional(pool)
@@ -135,6 +171,7 @@ pub async fn get_item_by_id(
 }
 
 /// Get full item detail for preview (includes rich_content as UTF-8 string).
+/// Sensitive items come back with their text and rich content masked.
 pub async fn get_item_detail(
     pool: &SqlitePool,
     id: &str,
@@ -146,6 +183,7 @@ pub async fn get_item_detail(
         Option<String>,
         Option<String>,
         Option<String>,
+        Option<String>,
         i64,
     )>,
     sqlx::Error,
@@ -159,18 +197,21 @@ pub async fn get_item_detail(
         Option<String>,
         Option<String>,
         Option<String>,
+        Option<String>,
         i64,
     )> = sqlx::query_as(
-        "SELECT content_type, plain_text, rich_content, image_path, file_path, file_name, content_size
+        "SELECT content_type, CASE WHEN is_sensitive THEN '' ELSE plain_text END,
+                CASE WHEN is_sensitive THEN NULL ELSE rich_content END,
+                image_path, image_mime, file_path, file_name, content_size
          FROM clipboard_items WHERE id = ?",
     )
     .bind(id)
     .fetch_optional(pool)
     .await?;
 
-    Ok(row.map(|(ct, pt, rc, ip, fp, fn_, cs)| {
+    Ok(row.map(|(ct, pt, rc, ip, im, fp, fn_, cs)| {
         let rich_str = rc.map(|bytes| String::from_utf8_lossy(&bytes).to_string());
-        (ct, pt, rich_str, ip, fp, fn_, cs)
+        (ct, pt, rich_str, ip, im, fp, fn_, cs)
     }))
 }
 
@@ -199,44 +240,204 @@ pub async fn get_non_favorited_image_paths(pool: &SqlitePool) -> Result<Vec<Stri
     Ok(rows.into_iter().map(|(p,)| p).collect())
 }
 
-/// Return image_paths for items that would be removed by the given retention policy.
-pub async fn get_retention_overflow_image_paths(
-    pool: &SqlitePool,
+/// Bytes an item occupies in storage. File items only store a path, so they count
+/// as zero but are still removed in age order by size limits.
+const STORED_BYTES: &str = "CASE WHEN content_type = 'file' THEN 0 ELSE content_size END
+    + COALESCE(length(rich_content), 0)";
+
+/// Bytes an item's own row holds under the global `size` policy. Stored images
+/// are accounted through `image_blobs` instead, once per file however many items share it.
+const ROW_BYTES: &str =
+    "CASE WHEN content_type = 'file' OR image_hash IS NOT NULL THEN 0 ELSE content_size END
+    + COALESCE(length(rich_content), 0)";
+
+/// Logical storage measured by the `size` policy: item content plus each image file
+/// still referenced, counted once. Unlike file sizes on disk it drops as soon as rows
+/// are deleted, without waiting for a VACUUM.
+pub async fn logical_storage_bytes(conn: &mut SqliteConnection) -> Result<i64, sqlx::Error> {
+    let row: (i64,) = sqlx::query_as(&format!(
+        "SELECT (SELECT COALESCE(SUM({}), 0) FROM clipboard_items)
+              + (SELECT COALESCE(SUM(size), 0) FROM image_blobs WHERE ref_count > 0)",
+        ROW_BYTES
+    ))
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(row.0)
+}
+
+/// Oldest non-favorited items to delete so `logical_storage_bytes` fits `budget_bytes`.
+/// A shared image only counts as freed once every item referencing it is selected, so
+/// a run ends under budget and the next run with unchanged data deletes nothing.
+async fn size_overflow_ids(
+    conn: &mut SqliteConnection,
+    budget_bytes: i64,
+) -> Result<Vec<(String,)>, sqlx::Error> {
+    let excess = logical_storage_bytes(conn).await? - budget_bytes;
+    if excess <= 0 {
+        return Ok(vec![]);
+    }
+
+    // (id, row bytes, image hash, image file size, image reference count)
+    let candidates: Vec<(String, i64, Option<String>, i64, i64)> = sqlx::query_as(&format!(
+        "SELECT c.id, {}, c.image_hash, COALESCE(b.size, 0), COALESCE(b.ref_count, 0)
+         FROM clipboard_items c LEFT JOIN image_blobs b ON b.hash = c.image_hash
+         WHERE c.is_favorited = 0
+         ORDER BY c.updated_at ASC, c.id ASC",
+        ROW_BYTES
+    ))
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut freed = 0;
+    let mut released: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    let mut ids = Vec::new();
+    for (id, row_bytes, image_hash, image_size, ref_count) in candidates {
+        if freed >= excess {
+            break;
+        }
+        freed += row_bytes;
+        if let Some(hash) = image_hash {
+            let removed = released.entry(hash).or_insert(0);
+            *removed += 1;
+            if *removed == ref_count {
+                freed += image_size;
+            }
+        }
+        ids.push((id,));
+    }
+    Ok(ids)
+}
+
+/// (id, content_type, source_app, max_days, max_count, max_size_mb)
+type RetentionRuleRow = (i64, String, String, Option<i64>, Option<i64>, Option<i64>);
+
+const RETENTION_RULES_SELECT: &str =
+    "SELECT id, content_type, source_app, max_days, max_count, max_size_mb
+     FROM retention_rules ORDER BY content_type, source_app";
+
+/// Ids of non-favorited items removed by the global retention policy and by every
+/// row of `retention_rules`. `size_budget_bytes` is the `size` policy's budget
+/// (0 = no limit). Shared by cleanup and the image path collection so both
+/// see the same predicates.
+async fn retention_overflow_ids(
+    conn: &mut SqliteConnection,
     policy: &str,
     days: i64,
     count: i64,
+    size_budget_bytes: i64,
 ) -> Result<Vec<String>, sqlx::Error> {
-    let rows: Vec<(String,)> = match policy {
+    let mut rows: Vec<(String,)> = match policy {
         "days" if days > 0 => {
             sqlx::query_as(
-                "SELECT image_path FROM clipboard_items
-                 WHERE is_favorited = 0
-                   AND created_at < datetime('now', ? || ' days')
-                   AND image_path IS NOT NULL",
+                "SELECT id FROM clipboard_items
+                 WHERE is_favorited = 0 AND created_at < datetime('now', ? || ' days')",
             )
             .bind(format!("-{}", days))
-            .fetch_all(pool)
+            .fetch_all(&mut *conn)
             .await?
         }
         "count" if count > 0 => {
             sqlx::query_as(
-                "SELECT image_path FROM clipboard_items
+                "SELECT id FROM clipboard_items
                  WHERE is_favorited = 0
-                   AND id NOT IN (
-                       SELECT id FROM clipboard_items
-                       WHERE is_favorited = 0
-                       ORDER BY updated_at DESC, id DESC
-                       LIMIT ?
-                   )
-                   AND image_path IS NOT NULL",
+                 ORDER BY updated_at DESC, id DESC
+                 LIMIT -1 OFFSET ?",
             )
             .bind(count)
-            .fetch_all(pool)
+            .fetch_all(&mut *conn)
             .await?
         }
+        // Oldest first until storage fits the budget
+        "size" if size_budget_bytes > 0 => size_overflow_ids(&mut *conn, size_budget_bytes).await?,
         _ => vec![],
     };
 
+    let rules: Vec<RetentionRuleRow> = sqlx::query_as(RETENTION_RULES_SELECT)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    const RULE_SCOPE: &str = "is_favorited = 0 AND content_type = ? AND (? = '' OR source_app = ?)";
+    for (_, content_type, source_app, max_days, max_count, max_size_mb) in rules {
+        if let Some(days) = max_days.filter(|d| *d > 0) {
+            rows.extend(
+                sqlx::query_as::<_, (String,)>(&format!(
+                    "SELECT id FROM clipboard_items
+                     WHERE {} AND created_at < datetime('now', ? || ' days')",
+                    RULE_SCOPE
+                ))
+                .bind(&content_type)
+                .bind(&source_app)
+                .bind(&source_app)
+                .bind(format!("-{}", days))
+                .fetch_all(&mut *conn)
+                .await?,
+            );
+        }
+        if let Some(count) = max_count.filter(|c| *c > 0) {
+            rows.extend(
+                sqlx::query_as::<_, (String,)>(&format!(
+                    "SELECT id FROM clipboard_items WHERE {}
+                     ORDER BY updated_at DESC, id DESC
+                     LIMIT -1 OFFSET ?",
+                    RULE_SCOPE
+                ))
+                .bind(&content_type)
+                .bind(&source_app)
+                .bind(&source_app)
+                .bind(count)
+                .fetch_all(&mut *conn)
+                .await?,
+            );
+        }
+        // Newest first: everything past the budget goes
+        if let Some(size_mb) = max_size_mb.filter(|s| *s > 0) {
+            rows.extend(
+                sqlx::query_as::<_, (String,)>(&format!(
+                    "SELECT id FROM (
+                        SELECT id, SUM(stored) OVER (ORDER BY updated_at DESC, id DESC) AS kept
+                        FROM (SELECT id, updated_at, {} AS stored FROM clipboard_items WHERE {})
+                     ) WHERE kept > ?",
+                    STORED_BYTES, RULE_SCOPE
+                ))
+                .bind(&content_type)
+                .bind(&source_app)
+                .bind(&source_app)
+                .bind(size_mb * 1024 * 1024)
+                .fetch_all(&mut *conn)
+                .await?,
+            );
+        }
+    }
+
+    let mut ids: Vec<String> = rows.into_iter().map(|(id,)| id).collect();
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Return image_paths for items that would be removed by the given retention policy
+/// and the per-type rules. `size_budget_bytes` is the `size` policy's budget.
+pub async fn get_retention_overflow_image_paths(
+    pool: &SqlitePool,
+    policy: &str,
+    days: i64,
+    count: i64,
+    size_budget_bytes: i64,
+) -> Result<Vec<String>, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    let ids = retention_overflow_ids(&mut conn, policy, days, count, size_budget_bytes).await?;
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let rows: Vec<(String,)> = sqlx::query_as(
+        "SELECT image_path FROM clipboard_items
+         WHERE id IN (SELECT value FROM json_each(?)) AND image_path IS NOT NULL",
+    )
+    .bind(serde_json::to_string(&ids).unwrap_or_default())
+    .fetch_all(&mut *conn)
+    .await?;
+
     Ok(rows.into_iter().map(|(p,)| p).collect())
 }
 
@@ -250,249 +451,558 @@ pub async fn get_all_image_paths(pool: &SqlitePool) -> Result<Vec<String>, sqlx:
     Ok(rows.into_iter().map(|(p,)| p).collect())
 }
 
-/// Delete a clipboard item and its FTS entry (transactional).
-pub async fn delete_item(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+/// Release image files after their items were deleted.
+/// Returns the subset of `paths` that no item references anymore (reference count
+/// dropped to zero); their `image_blobs` rows are removed and the caller deletes the files.
+pub async fn release_image_paths(
+    pool: &SqlitePool,
+    paths: Vec<String>,
+) -> Result<Vec<String>, sqlx::Error> {
+    let mut released = Vec::new();
     let mut tx = pool.begin().await?;
 
-    sqlx::query("DELETE FROM clipboard_fts WHERE item_id = ?")
-        .bind(id)
-        .execute(&mut *tx)
-        .await?;
+    let unique: std::collections::BTreeSet<String> = paths.into_iter().collect();
+    for path in unique {
+        let ref_count: Option<(i64,)> =
+            sqlx::query_as("SELECT ref_count FROM image_blobs WHERE path = ?")
+                .bind(&path)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let unreferenced = match ref_count {
+            Some((n,)) => n <= 0,
+            // Not (yet) content-addressed: fall back to checking item references directly
+            None => {
+                let (n,): (i64,) =
+                    sqlx::query_as("SELECT COUNT(*) FROM clipboard_items WHERE image_path = ?")
+                        .bind(&path)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                n == 0
+            }
+        };
 
-    sqlx::query("DELETE FROM clipboard_items WHERE id = ?")
-        .bind(id)
-        .execute(&mut *tx)
-        .await?;
+        if unreferenced {
+            sqlx::query("DELETE FROM image_blobs WHERE path = ?")
+                .bind(&path)
+                .execute(&mut *tx)
+                .await?;
+            released.push(path);
+        }
+    }
 
     tx.commit().await?;
 
-    Ok(())
+    Ok(released)
 }
 
-/// Search clipboard items using FTS5 trigram.
-/// Excludes thumbnail blobs for fast IPC transfer.
-pub async fn search_items(
-    pool: &SqlitePool,
-    query: &str,
-    content_type: Option<&str>,
-    limit: i64,
-    favorites_only: bool,
-) -> Result<Vec<ClipboardItem>, sqlx::Error> {
-    // Multi-token query: always use LIKE with AND matching
-    let tokens: Vec<&str> = query.split_whitespace().collect();
-    if tokens.len() > 1 {
-        return search_items_like(pool, query, content_type, limit, favorites_only).await;
-    }
-
-    // Single token: FTS5 for >= 3 chars, LIKE for < 3
-    if query.chars().count() < 3 {
-        return search_items_like(pool, query, content_type, limit, favorites_only).await;
-    }
-
-    let fts_query = format!("\"{}\"", query.replace('"', "\"\""));
-
-    let item_ids: Vec<(String,)> =
-        sqlx::query_as("SELECT item_id FROM clipboard_fts WHERE clipboard_fts MATCH ? LIMIT ?")
-            .bind(&fts_query)
-            .bind(limit)
-            .fetch_all(pool)
-            .await?;
+/// Drop `image_blobs` rows no item references anymore (used by orphan GC).
+pub async fn purge_unreferenced_image_blobs(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM image_blobs WHERE ref_count <= 0")
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
 
-    if item_ids.is_empty() {
-        return Ok(vec![]);
-    }
+/// Return (id, image_path) of items whose original predates content-addressed storage.
+pub async fn get_legacy_image_items(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, image_path FROM clipboard_items WHERE image_path IS NOT NULL AND image_hash IS NULL",
+    )
+    .fetch_all(pool)
+    .await
+}
 
-    let ids: Vec<String> = item_ids.into_iter().map(|r| r.0).collect();
-    let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+/// Point an item at a content-addressed original, registering the blob if needed.
+pub async fn set_item_image(
+    pool: &SqlitePool,
+    id: &str,
+    path: &str,
+    hash: &str,
+    size: i64,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
 
-    let fav_filter = if favorites_only {
-        " AND is_favorited = 1"
-    } else {
-        ""
-    };
-    let sql = if content_type.is_some() {
-        format!(
-            "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-             FROM clipboard_items WHERE id IN ({}) AND content_type = ?{} ORDER BY updated_at DESC, id DESC",
-            placeholders, fav_filter
-        )
-    } else {
-        format!(
-            "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-             FROM clipboard_items WHERE id IN ({}){} ORDER BY updated_at DESC, id DESC",
-            placeholders, fav_filter
-        )
-    };
+    sqlx::query(
+        "INSERT INTO image_blobs (hash, path, size) VALUES (?, ?, ?) ON CONFLICT(hash) DO NOTHING",
+    )
+    .bind(hash)
+    .bind(path)
+    .bind(size)
+    .execute(&mut *tx)
+    .await?;
 
-    let mut q = sqlx::query_as::<
-        _,
-        (
-            String,
-            String,
-            String,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            String,
-            String,
-            i64,
-            String,
-            bool,
-            String,
-            String,
-        ),
-    >(&sql);
-    for id in &ids {
-        q = q.bind(id);
-    }
-    if let Some(ct) = content_type {
-        q = q.bind(ct);
-    }
+    sqlx::query("UPDATE clipboard_items SET image_path = ?, image_hash = ? WHERE id = ?")
+        .bind(path)
+        .bind(hash)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
 
-    let items = q.fetch_all(pool).await?;
+    tx.commit().await?;
 
-    Ok(items
-        .into_iter()
-        .map(|r| ClipboardItem {
-            id: r.0,
-            content_type: r.1,
-            plain_text: r.2,
-            thumbnail: None,
-            image_path: r.3,
-            file_path: r.4,
-            file_name: r.5,
-            source_app: r.6,
-            source_app_name: r.7,
-            content_size: r.8,
-            content_hash: r.9,
-            is_favorited: r.10,
-            created_at: r.11,
-            updated_at: r.12,
-        })
-        .collect())
+    Ok(())
 }
 
-/// Fallback search using LIKE with multi-token AND matching.
-/// Excludes thumbnail blobs for fast IPC transfer.
-async fn search_items_like(
+/// Record image properties and EXIF details for an item.
+pub async fn set_image_metadata(
     pool: &SqlitePool,
-    query: &str,
-    content_type: Option<&str>,
-    limit: i64,
-    favorites_only: bool,
-) -> Result<Vec<ClipboardItem>, sqlx::Error> {
-    let tokens: Vec<&str> = query.split_whitespace().filter(|t| !t.is_empty()).collect();
-    if tokens.is_empty() {
-        return Ok(vec![]);
-    }
-
-    // Build per-token conditions: each token must match at least one searchable field
-    let mut conditions = Vec::new();
-    let mut binds = Vec::new();
-    for token in &tokens {
-        let pattern = format!("%{}%", token);
-        conditions.push("(plain_text LIKE ? OR file_name LIKE ? OR source_app_name LIKE ?)");
-        binds.push(pattern);
-    }
-    let where_clause = conditions.join(" AND ");
-    let fav_filter = if favorites_only {
-        " AND is_favorited = 1"
-    } else {
-        ""
-    };
-
-    let sql = if let Some(_ct) = content_type {
-        format!(
-            "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-             FROM clipboard_items WHERE {} AND content_type = ?{} ORDER BY updated_at DESC, id DESC LIMIT ?",
-            where_clause, fav_filter
-        )
-    } else {
-        format!(
-            "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-             FROM clipboard_items WHERE {}{} ORDER BY updated_at DESC, id DESC LIMIT ?",
-            where_clause, fav_filter
-        )
-    };
-
-    let mut q = sqlx::query_as::<
-        _,
-        (
-            String,
-            String,
-            String,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            String,
-            String,
-            i64,
-            String,
-            bool,
-            String,
-            String,
-        ),
-    >(&sql);
-
-    // Bind each token's pattern 3 times (for plain_text, file_name, source_app_name)
-    for pattern in &binds {
-        q = q.bind(pattern);
-        q = q.bind(pattern);
-        q = q.bind(pattern);
-    }
-    if let Some(ct) = content_type {
-        q = q.bind(ct);
-    }
-    q = q.bind(limit);
+    id: &str,
+    meta: &ImageMetadata,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE clipboard_items SET image_width = ?, image_height = ?, image_format = ?, image_color_type = ?,
+         exif_camera = ?, exif_taken_at = ?, exif_gps_latitude = ?, exif_gps_longitude = ?
+         WHERE id = ?",
+    )
+    .bind(meta.width as i64)
+    .bind(meta.height as i64)
+    .bind(&meta.format)
+    .bind(&meta.color_type)
+    .bind(&meta.camera)
+    .bind(&meta.taken_at)
+    .bind(meta.gps_latitude)
+    .bind(meta.gps_longitude)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
 
-    let items = q.fetch_all(pool).await?;
+/// Get recorded image metadata for an item (None if never extracted).
+pub async fn get_image_metadata(
+    pool: &SqlitePool,
+    id: &str,
+) -> Result<Option<ImageMetadata>, sqlx::Error> {
+    #[allow(clippy::type_complexity)]
+    let row: Option<(
+        Option<i64>,
+        Option<i64>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<f64>,
+        Option<f64>,
+    )> = sqlx::query_as(
+        "SELECT image_width, image_height, image_format, image_color_type,
+                exif_camera, exif_taken_at, exif_gps_latitude, exif_gps_longitude
+         FROM clipboard_items WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
 
-    Ok(items
-        .into_iter()
-        .map(|r| ClipboardItem {
-            id: r.0,
-            content_type: r.1,
-            plain_text: r.2,
-            thumbnail: None,
-            image_path: r.3,
-            file_path: r.4,
-            file_name: r.5,
-            source_app: r.6,
-            source_app_name: r.7,
-            content_size: r.8,
-            content_hash: r.9,
-            is_favorited: r.10,
-            created_at: r.11,
-            updated_at: r.12,
-        })
-        .collect())
+    Ok(
+        row.and_then(|(w, h, format, color, camera, taken_at, lat, lon)| {
+            Some(ImageMetadata {
+                width: w? as u32,
+                height: h? as u32,
+                format: format.unwrap_or_default(),
+                color_type: color.unwrap_or_default(),
+                camera,
+                taken_at,
+                gps_latitude: lat,
+                gps_longitude: lon,
+            })
+        }),
+    )
 }
 
-/// Get the thumbnail blob for a single item.
-pub async fn get_thumbnail(pool: &SqlitePool, id: &str) -> Result<Option<Vec<u8>>, sqlx::Error> {
-    let row: Option<(Option<Vec<u8>>,)> =
-        sqlx::query_as("SELECT thumbnail FROM clipboard_items WHERE id = ?")
-            .bind(id)
-            .fetch_optional(pool)
-            .await?;
-    Ok(row.and_then(|r| r.0))
+/// Image items whose metadata hasn't been extracted yet, as (id, image_path).
+pub async fn get_items_missing_image_metadata(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, image_path FROM clipboard_items
+         WHERE content_type = 'image' AND image_path IS NOT NULL AND image_width IS NULL",
+    )
+    .fetch_all(pool)
+    .await
 }
 
-/// Update the thumbnail for an existing clipboard item.
-pub async fn update_thumbnail(
+/// Store an image item's perceptual hash, optionally grouping it under a primary item.
+/// Grouping also bumps the primary in the same transaction, so the copy surfaces
+/// once: as its own row before grouping, through its primary after. Transactional.
+pub async fn set_perceptual_hash(
     pool: &SqlitePool,
     id: &str,
-    thumbnail: &[u8],
+    hash: u64,
+    variant_of: Option<&str>,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query("UPDATE clipboard_items SET thumbnail = ? WHERE id = ?")
-        .bind(thumbnail)
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("UPDATE clipboard_items SET perceptual_hash = ?, variant_of = ? WHERE id = ?")
+        .bind(hash as i64)
+        .bind(variant_of)
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
-    Ok(())
-}
-
-// ---- Favorites ----
+
+    if let Some(primary) = variant_of {
+        sqlx::query("UPDATE clipboard_items SET updated_at = datetime('now') WHERE id = ?")
+            .bind(primary)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Record which selection an item was captured from.
+pub async fn set_selection_origin(
+    pool: &SqlitePool,
+    id: &str,
+    origin: SelectionOrigin,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE clipboard_items SET selection = ? WHERE id = ?")
+        .bind(origin.as_str())
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Mark an item sensitive: masked in lists, dropped from the search index and
+/// deleted after `ttl_secs`. Transactional.
+pub async fn mark_sensitive(pool: &SqlitePool, id: &str, ttl_secs: i64) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE clipboard_items SET is_sensitive = 1, expires_at = datetime('now', ? || ' seconds')
+         WHERE id = ?",
+    )
+    .bind(format!("+{}", ttl_secs.max(0)))
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM clipboard_fts WHERE item_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Delete non-favorited items whose `expires_at` has passed. Transactional.
+pub async fn delete_expired_items(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    const EXPIRED: &str =
+        "SELECT id FROM clipboard_items WHERE is_favorited = 0 AND expires_at <= datetime('now')";
+    sqlx::query(&format!(
+        "DELETE FROM clipboard_fts WHERE item_id IN ({})",
+        EXPIRED
+    ))
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(&format!(
+        "DELETE FROM item_groups WHERE item_id IN ({})",
+        EXPIRED
+    ))
+    .execute(&mut *tx)
+    .await?;
+    let result = sqlx::query(
+        "DELETE FROM clipboard_items WHERE is_favorited = 0 AND expires_at <= datetime('now')",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected() as i64)
+}
+
+/// Seconds until the next non-favorited item expires (0 if one is already due).
+pub async fn next_expiry_secs(pool: &SqlitePool) -> Result<Option<i64>, sqlx::Error> {
+    let row: (Option<i64>,) = sqlx::query_as(
+        "SELECT MAX(0, CAST(ROUND((julianday(MIN(expires_at)) - julianday('now')) * 86400) AS INTEGER))
+         FROM clipboard_items WHERE is_favorited = 0 AND expires_at IS NOT NULL",
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(row.0)
+}
+
+/// Recent primary image items to compare a new image against, as (id, content_hash,
+/// perceptual_hash), most recent first. With `aspect_ratio` set, items whose recorded
+/// dimensions differ in shape by more than 2% are skipped.
+pub async fn get_similarity_candidates(
+    pool: &SqlitePool,
+    aspect_ratio: Option<f64>,
+    limit: i64,
+) -> Result<Vec<(String, String, u64)>, sqlx::Error> {
+    let rows: Vec<(String, String, i64)> = sqlx::query_as(
+        "SELECT id, content_hash, perceptual_hash FROM clipboard_items
+         WHERE content_type = 'image' AND perceptual_hash IS NOT NULL AND variant_of IS NULL
+           AND (?1 IS NULL OR image_width IS NULL
+                OR ABS(CAST(image_width AS REAL) / image_height - ?1) <= 0.02 * ?1)
+         ORDER BY updated_at DESC LIMIT ?2",
+    )
+    .bind(aspect_ratio)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, hash, phash)| (id, hash, phash as u64))
+        .collect())
+}
+
+/// Image items without a perceptual hash yet, as (id, image_path).
+pub async fn get_items_missing_perceptual_hash(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, image_path FROM clipboard_items
+         WHERE content_type = 'image' AND image_path IS NOT NULL AND perceptual_hash IS NULL",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Near-duplicate variants grouped under an item, most recent first.
+pub async fn get_item_variants(
+    pool: &SqlitePool,
+    id: &str,
+) -> Result<Vec<ClipboardItem>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM clipboard_items WHERE variant_of = ? ORDER BY updated_at DESC, id DESC",
+        ITEM_COLUMNS
+    ))
+    .bind(id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Delete a clipboard item and its FTS entry (transactional).
+pub async fn delete_item(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM clipboard_fts WHERE item_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM clipboard_items WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// SQL conditions (to be ANDed) and their string binds for an `ItemFilter`.
+fn filter_conditions(filter: &ItemFilter) -> (Vec<&'static str>, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut binds = Vec::new();
+    if let Some(ct) = &filter.content_type {
+        conditions.push("content_type = ?");
+        binds.push(ct.clone());
+    }
+    if filter.favorites_only {
+        conditions.push("is_favorited = 1");
+    }
+    if let Some(app) = &filter.source_app {
+        conditions.push("source_app = ?");
+        binds.push(app.clone());
+    }
+    if let Some(group_id) = &filter.group_id {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM item_groups g WHERE g.item_id = clipboard_items.id AND g.group_id = ?)",
+        );
+        binds.push(group_id.clone());
+    }
+    if filter.ungrouped {
+        conditions
+            .push("NOT EXISTS (SELECT 1 FROM item_groups g WHERE g.item_id = clipboard_items.id)");
+    }
+    if let Some(selection) = &filter.selection {
+        conditions.push("selection = ?");
+        binds.push(selection.clone());
+    }
+    if let Some(days) = filter.older_than_days {
+        conditions.push("created_at < datetime('now', ? || ' days')");
+        binds.push(format!("-{}", days.max(0)));
+    }
+    (conditions, binds)
+}
+
+/// `filter_conditions` as a ` AND ...` suffix for an existing WHERE clause.
+fn filter_sql(filter: &ItemFilter) -> (String, Vec<String>) {
+    let (conditions, binds) = filter_conditions(filter);
+    let sql = conditions
+        .iter()
+        .map(|c| format!(" AND {}", c))
+        .collect::<String>();
+    (sql, binds)
+}
+
+/// Search clipboard items using FTS5 trigram.
+/// Excludes thumbnail blobs for fast IPC transfer.
+pub async fn search_items(
+    pool: &SqlitePool,
+    query: &str,
+    filter: &ItemFilter,
+    limit: i64,
+) -> Result<Vec<ClipboardItem>, sqlx::Error> {
+    // Multi-token query: always use LIKE with AND matching
+    let tokens: Vec<&str> = query.split_whitespace().collect();
+    if tokens.len() > 1 {
+        return search_items_like(pool, query, filter, limit).await;
+    }
+
+    // Single token: FTS5 for >= 3 chars, LIKE for < 3
+    if query.chars().count() < 3 {
+        return search_items_like(pool, query, filter, limit).await;
+    }
+
+    let fts_query = format!("\"{}\"", query.replace('"', "\"\""));
+
+    let item_ids: Vec<(String,)> =
+        sqlx::query_as("SELECT item_id FROM clipboard_fts WHERE clipboard_fts MATCH ? LIMIT ?")
+            .bind(&fts_query)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+
+    if item_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let ids: Vec<String> = item_ids.into_iter().map(|r| r.0).collect();
+    let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+
+    let (filter_clause, filter_binds) = filter_sql(filter);
+    let sql = format!(
+        "SELECT {} FROM clipboard_items WHERE variant_of IS NULL AND id IN ({}){} ORDER BY updated_at DESC, id DESC",
+        ITEM_COLUMNS, placeholders, filter_clause
+    );
+
+    let mut q = sqlx::query_as::<_, ClipboardItem>(&sql);
+    for id in &ids {
+        q = q.bind(id);
+    }
+    for value in &filter_binds {
+        q = q.bind(value);
+    }
+
+    q.fetch_all(pool).await
+}
+
+/// Fallback search using LIKE with multi-token AND matching.
+/// Excludes thumbnail blobs for fast IPC transfer.
+async fn search_items_like(
+    pool: &SqlitePool,
+    query: &str,
+    filter: &ItemFilter,
+    limit: i64,
+) -> Result<Vec<ClipboardItem>, sqlx::Error> {
+    let tokens: Vec<&str> = query.split_whitespace().filter(|t| !t.is_empty()).collect();
+    if tokens.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Build per-token conditions: each token must match at least one searchable field
+    let mut conditions = Vec::new();
+    let mut binds = Vec::new();
+    for token in &tokens {
+        let pattern = format!("%{}%", token);
+        conditions.push(
+            "((is_sensitive = 0 AND plain_text LIKE ?) OR file_name LIKE ? OR source_app_name LIKE ?)",
+        );
+        binds.push(pattern);
+    }
+    let where_clause = conditions.join(" AND ");
+    let (filter_clause, filter_binds) = filter_sql(filter);
+
+    let sql = format!(
+        "SELECT {} FROM clipboard_items WHERE variant_of IS NULL AND {}{} ORDER BY updated_at DESC, id DESC LIMIT ?",
+        ITEM_COLUMNS, where_clause, filter_clause
+    );
+
+    let mut q = sqlx::query_as::<_, ClipboardItem>(&sql);
+
+    // Bind each token's pattern 3 times (for plain_text, file_name, source_app_name)
+    for pattern in &binds {
+        q = q.bind(pattern);
+        q = q.bind(pattern);
+        q = q.bind(pattern);
+    }
+    for value in &filter_binds {
+        q = q.bind(value);
+    }
+    q = q.bind(limit);
+
+    q.fetch_all(pool).await
+}
+
+/// Get the content hash of a single item (keys its on-disk thumbnails).
+pub async fn get_content_hash(pool: &SqlitePool, id: &str) -> Result<Option<String>, sqlx::Error> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT content_hash FROM clipboard_items WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.map(|r| r.0))
+}
+
+/// Return (content_type, content_hash, source_path) for every item that can have a thumbnail:
+/// the stored original for images, the file on disk for file items.
+pub async fn get_thumbnail_sources(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT content_type, content_hash, COALESCE(image_path, file_path) FROM clipboard_items
+         WHERE (content_type = 'image' AND image_path IS NOT NULL)
+            OR (content_type = 'file' AND file_path IS NOT NULL)",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Return the content hashes of all image and file items (used by thumbnail GC).
+pub async fn get_thumbnail_hashes(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        "SELECT content_hash FROM clipboard_items WHERE content_type IN ('image', 'file')",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|r| r.0).collect())
+}
+
+/// Return up to `limit` items that still carry a legacy thumbnail blob in the DB,
+/// as (id, content_hash, image_path, thumbnail).
+#[allow(clippy::type_complexity)]
+pub async fn get_legacy_thumbnails(
+    pool: &SqlitePool,
+    limit: i64,
+) -> Result<Vec<(String, String, Option<String>, Vec<u8>)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, content_hash, image_path, thumbnail FROM clipboard_items
+         WHERE thumbnail IS NOT NULL LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Drop the legacy thumbnail blob of an item once it lives in the disk cache.
+pub async fn clear_thumbnail_blob(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE clipboard_items SET thumbnail = NULL WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// ---- Favorites ----
 
 /// Get all favorited items, optionally filtered by content type.
 /// Excludes thumbnail blobs for fast IPC transfer.
@@ -501,83 +1011,27 @@ pub async fn get_favorited_items(
     content_type: Option<&str>,
     limit: i64,
     offset: i64,
-) -> Result<Vec<super::models::ClipboardItem>, sqlx::Error> {
-    let sql = if content_type.is_some() {
-        "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-         FROM clipboard_items WHERE is_favorited = 1 AND content_type = ? ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?"
-    } else {
-        "SELECT id, content_type, plain_text, image_path, file_path, file_name, source_app, source_app_name, content_size, content_hash, is_favorited, created_at, updated_at
-         FROM clipboard_items WHERE is_favorited = 1 ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?"
-    };
-
-    let items = if let Some(ct) = content_type {
-        sqlx::query_as::<
-            _,
-            (
-                String,
-                String,
-                String,
-                Option<String>,
-                Option<String>,
-                Option<String>,
-                String,
-                String,
-                i64,
-                String,
-                bool,
-                String,
-                String,
-            ),
-        >(sql)
+) -> Result<Vec<ClipboardItem>, sqlx::Error> {
+    if let Some(ct) = content_type {
+        sqlx::query_as(&format!(
+            "SELECT {} FROM clipboard_items WHERE variant_of IS NULL AND is_favorited = 1 AND content_type = ? ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?",
+            ITEM_COLUMNS
+        ))
         .bind(ct)
         .bind(limit)
         .bind(offset)
         .fetch_all(pool)
-        .await?
+        .await
     } else {
-        sqlx::query_as::<
-            _,
-            (
-                String,
-                String,
-                String,
-                Option<String>,
-                Option<String>,
-                Option<String>,
-                String,
-                String,
-                i64,
-                String,
-                bool,
-                String,
-                String,
-            ),
-        >(sql)
+        sqlx::query_as(&format!(
+            "SELECT {} FROM clipboard_items WHERE variant_of IS NULL AND is_favorited = 1 ORDER BY updated_at DESC, id DESC LIMIT ? OFFSET ?",
+            ITEM_COLUMNS
+        ))
         .bind(limit)
         .bind(offset)
         .fetch_all(pool)
-        .await?
-    };
-
-    Ok(items
-        .into_iter()
-        .map(|r| super::models::ClipboardItem {
-            id: r.0,
-            content_type: r.1,
-            plain_text: r.2,
-            thumbnail: None,
-            image_path: r.3,
-            file_path: r.4,
-            file_name: r.5,
-            source_app: r.6,
-            source_app_name: r.7,
-            content_size: r.8,
-            content_hash: r.9,
-            is_favorited: r.10,
-            created_at: r.11,
-            updated_at: r.12,
-        })
-        .collect())
+        .await
+    }
 }
 
 // ---- Settings ----
@@ -608,6 +1062,15 @@ pub async fn set_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<()
     Ok(())
 }
 
+/// Delete a setting by key (no-op if missing).
+pub async fn delete_setting(pool: &SqlitePool, key: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM settings WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 /// Clear all non-favorited clipboard items and their FTS entries (transactional).
 pub async fn clear_history(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
     let mut tx = pool.begin().await?;
@@ -633,96 +1096,478 @@ pub async fn clear_history(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
     Ok(result.rows_affected() as i64)
 }
 
-/// Cleanup old items by retention policy (days or count). Preserves favorites. Transactional.
-pub async fn cleanup_by_retention(
+/// Image paths of the non-favorited items matched by `filter`.
+pub async fn get_filtered_image_paths(
     pool: &SqlitePool,
-    policy: &str,
-    days: i64,
-    count: i64,
-) -> Result<i64, sqlx::Error> {
-    match policy {
-        "days" if days > 0 => {
-            let mut tx = pool.begin().await?;
-            let days_str = format!("-{}", days);
-
-            sqlx::query(
-                "DELETE FROM clipboard_fts WHERE item_id IN (
-                    SELECT id FROM clipboard_items
-                    WHERE is_favorited = 0
-                    AND created_at < datetime('now', ? || ' days')
-                )",
-            )
-            .bind(&days_str)
-            .execute(&mut *tx)
-            .await?;
+    filter: &ItemFilter,
+) -> Result<Vec<String>, sqlx::Error> {
+    let (filter_clause, filter_binds) = filter_sql(filter);
+    let sql = format!(
+        "SELECT image_path FROM clipboard_items WHERE is_favorited = 0 AND image_path IS NOT NULL{}",
+        filter_clause
+    );
+    let mut q = sqlx::query_as::<_, (String,)>(&sql);
+    for value in &filter_binds {
+        q = q.bind(value);
+    }
+    let rows = q.fetch_all(pool).await?;
 
-            sqlx::query(
-                "DELETE FROM item_groups WHERE item_id IN (
-                    SELECT id FROM clipboard_items
-                    WHERE is_favorited = 0
-                    AND created_at < datetime('now', ? || ' days')
-                )",
-            )
-            .bind(&days_str)
+    Ok(rows.into_iter().map(|(p,)| p).collect())
+}
+
+/// Delete the non-favorited items matched by `filter`. Returns the deleted count.
+pub async fn clear_filtered(pool: &SqlitePool, filter: &ItemFilter) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Resolve ids first: the group conditions read item_groups, which is cleaned below
+    let (filter_clause, filter_binds) = filter_sql(filter);
+    let sql = format!(
+        "SELECT id FROM clipboard_items WHERE is_favorited = 0{}",
+        filter_clause
+    );
+    let mut q = sqlx::query_as::<_, (String,)>(&sql);
+    for value in &filter_binds {
+        q = q.bind(value);
+    }
+    let ids: Vec<String> = q
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|(id,)| id)
+        .collect();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    let ids_json = serde_json::to_string(&ids).unwrap_or_default();
+
+    sqlx::query("DELETE FROM clipboard_fts WHERE item_id IN (SELECT value FROM json_each(?))")
+        .bind(&ids_json)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM item_groups WHERE item_id IN (SELECT value FROM json_each(?))")
+        .bind(&ids_json)
+        .execute(&mut *tx)
+        .await?;
+
+    let result =
+        sqlx::query("DELETE FROM clipboard_items WHERE id IN (SELECT value FROM json_each(?))")
+            .bind(&ids_json)
             .execute(&mut *tx)
             .await?;
 
-            let result = sqlx::query(
-                "DELETE FROM clipboard_items WHERE is_favorited = 0 AND created_at < datetime('now', ? || ' days')",
-            )
-            .bind(&days_str)
+    tx.commit().await?;
+
+    Ok(result.rows_affected() as i64)
+}
+
+/// Dry run of `cleanup_by_retention`: what the same policy and rules would delete,
+/// grouped by content type, plus up to `sample_limit` of the affected items. Deletes nothing.
+pub async fn preview_retention(
+    pool: &SqlitePool,
+    policy: &str,
+    days: i64,
+    count: i64,
+    size_budget_bytes: i64,
+    sample_limit: i64,
+) -> Result<RetentionPreview, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    let ids = retention_overflow_ids(&mut conn, policy, days, count, size_budget_bytes).await?;
+    if ids.is_empty() {
+        return Ok(RetentionPreview::default());
+    }
+    let ids_json = serde_json::to_string(&ids).unwrap_or_default();
+
+    let by_type: Vec<(String, i64, i64)> = sqlx::query_as(&format!(
+        "SELECT content_type, COUNT(*), COALESCE(SUM({}), 0) FROM clipboard_items
+         WHERE id IN (SELECT value FROM json_each(?))
+         GROUP BY content_type ORDER BY COUNT(*) DESC, content_type",
+        STORED_BYTES
+    ))
+    .bind(&ids_json)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let sample: Vec<ClipboardItem> = sqlx::query_as(&format!(
+        "SELECT {} FROM clipboard_items WHERE id IN (SELECT value FROM json_each(?))
+         ORDER BY updated_at DESC, id DESC LIMIT ?",
+        ITEM_COLUMNS
+    ))
+    .bind(&ids_json)
+    .bind(sample_limit)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(RetentionPreview {
+        total_items: by_type.iter().map(|t| t.1).sum(),
+        total_bytes: by_type.iter().map(|t| t.2).sum(),
+        by_type: by_type
+            .into_iter()
+            .map(|(content_type, items, bytes)| RetentionTypeSummary {
+                content_type,
+                items,
+                bytes,
+            })
+            .collect(),
+        sample,
+    })
+}
+
+/// Cleanup old items by retention policy (days, count or size) and the per-type
+/// `retention_rules`. Preserves favorites. Transactional.
+/// For `size`, the oldest items are removed until `logical_storage_bytes` fits
+/// `size_budget_bytes`.
+pub async fn cleanup_by_retention(
+    pool: &SqlitePool,
+    policy: &str,
+    days: i64,
+    count: i64,
+    size_budget_bytes: i64,
+) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let ids = retention_overflow_ids(&mut tx, policy, days, count, size_budget_bytes).await?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    let ids_json = serde_json::to_string(&ids).unwrap_or_default();
+
+    sqlx::query("DELETE FROM clipboard_fts WHERE item_id IN (SELECT value FROM json_each(?))")
+        .bind(&ids_json)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM item_groups WHERE item_id IN (SELECT value FROM json_each(?))")
+        .bind(&ids_json)
+        .execute(&mut *tx)
+        .await?;
+
+    let result =
+        sqlx::query("DELETE FROM clipboard_items WHERE id IN (SELECT value FROM json_each(?))")
+            .bind(&ids_json)
             .execute(&mut *tx)
             .await?;
 
-            tx.commit().await?;
+    tx.commit().await?;
 
-            Ok(result.rows_affected() as i64)
-        }
-        "count" if count > 0 => {
-            let mut tx = pool.begin().await?;
+    Ok(result.rows_affected() as i64)
+}
 
-            sqlx::query(
-                "DELETE FROM clipboard_fts WHERE item_id IN (
-                    SELECT id FROM clipboard_items
-                    WHERE is_favorited = 0
-                    ORDER BY updated_at DESC, id DESC
-                    LIMIT -1 OFFSET ?
-                )",
-            )
-            .bind(count)
-            .execute(&mut *tx)
+/// List all retention rules, ordered by content type then source app.
+pub async fn get_retention_rules(pool: &SqlitePool) -> Result<Vec<RetentionRule>, sqlx::Error> {
+    let rows: Vec<RetentionRuleRow> = sqlx::query_as(RETENTION_RULES_SELECT)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| RetentionRule {
+            id: r.0,
+            content_type: r.1,
+            source_app: r.2,
+            max_days: r.3,
+            max_count: r.4,
+            max_size_mb: r.5,
+        })
+        .collect())
+}
+
+/// Insert a rule, or replace the limits of the existing rule with the same
+/// content type and source app. Returns the rule id.
+pub async fn upsert_retention_rule(
+    pool: &SqlitePool,
+    rule: &RetentionRule,
+) -> Result<i64, sqlx::Error> {
+    let row: (i64,) = sqlx::query_as(
+        "INSERT INTO retention_rules (content_type, source_app, max_days, max_count, max_size_mb)
+         VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(content_type, source_app) DO UPDATE SET
+            max_days = excluded.max_days,
+            max_count = excluded.max_count,
+            max_size_mb = excluded.max_size_mb
+         RETURNING id",
+    )
+    .bind(&rule.content_type)
+    .bind(&rule.source_app)
+    .bind(rule.max_days)
+    .bind(rule.max_count)
+    .bind(rule.max_size_mb)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.0)
+}
+
+/// Delete a retention rule by id.
+pub async fn delete_retention_rule(pool: &SqlitePool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM retention_rules WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// List all app capture rules, ordered by app.
+pub async fn get_app_capture_rules(pool: &SqlitePool) -> Result<Vec<AppCaptureRule>, sqlx::Error> {
+    let rows: Vec<(i64, String, String)> =
+        sqlx::query_as("SELECT id, app, action FROM app_capture_rules ORDER BY app")
+            .fetch_all(pool)
             .await?;
 
+    Ok(rows
+        .into_iter()
+        .map(|r| AppCaptureRule {
+            id: r.0,
+            app: r.1,
+            action: r.2,
+        })
+        .collect())
+}
+
+/// Whether any app capture rule is configured.
+pub async fn has_app_capture_rules(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+    let (exists,): (bool,) = sqlx::query_as("SELECT EXISTS(SELECT 1 FROM app_capture_rules)")
+        .fetch_one(pool)
+        .await?;
+    Ok(exists)
+}
+
+/// Insert a rule, or change the action of the existing rule for the same app.
+/// Returns the rule id.
+pub async fn upsert_app_capture_rule(
+    pool: &SqlitePool,
+    rule: &AppCaptureRule,
+) -> Result<i64, sqlx::Error> {
+    let row: (i64,) = sqlx::query_as(
+        "INSERT INTO app_capture_rules (app, action) VALUES (?, ?)
+         ON CONFLICT(app) DO UPDATE SET action = excluded.action
+         RETURNING id",
+    )
+    .bind(&rule.app)
+    .bind(&rule.action)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.0)
+}
+
+/// Delete an app capture rule by id.
+pub async fn delete_app_capture_rule(pool: &SqlitePool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM app_capture_rules WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// List all capture ignore rules, oldest first (the order they're checked in).
+pub async fn get_capture_ignore_rules(
+    pool: &SqlitePool,
+) -> Result<Vec<CaptureIgnoreRule>, sqlx::Error> {
+    let rows: Vec<(i64, String, String, bool)> =
+        sqlx::query_as("SELECT id, kind, value, enabled FROM capture_ignore_rules ORDER BY id")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| CaptureIgnoreRule {
+            id: r.0,
+            kind: r.1,
+            value: r.2,
+            enabled: r.3,
+        })
+        .collect())
+}
+
+/// Insert a rule (id 0) or update the rule with the given id. Returns the rule id.
+pub async fn save_capture_ignore_rule(
+    pool: &SqlitePool,
+    rule: &CaptureIgnoreRule,
+) -> Result<i64, sqlx::Error> {
+    if rule.id == 0 {
+        let row: (i64,) = sqlx::query_as(
+            "INSERT INTO capture_ignore_rules (kind, value, enabled) VALUES (?, ?, ?)
+             RETURNING id",
+        )
+        .bind(&rule.kind)
+        .bind(&rule.value)
+        .bind(rule.enabled)
+        .fetch_one(pool)
+        .await?;
+        return Ok(row.0);
+    }
+    let result = sqlx::query(
+        "UPDATE capture_ignore_rules SET kind = ?, value = ?, enabled = ? WHERE id = ?",
+    )
+    .bind(&rule.kind)
+    .bind(&rule.value)
+    .bind(rule.enabled)
+    .bind(rule.id)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    Ok(rule.id)
+}
+
+/// Delete a capture ignore rule by id.
+pub async fn delete_capture_ignore_rule(pool: &SqlitePool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM capture_ignore_rules WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Fold the WAL back into the main database file and truncate it.
+pub async fn checkpoint_wal(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Rebuild the database file to return free pages to the filesystem.
+pub async fn vacuum(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("VACUUM").execute(pool).await?;
+    Ok(())
+}
+
+// ---- Jobs ----
+
+/// Queue a background job. Idempotent per (kind, payload): an identical pending or
+/// running job is left alone, a failed one is reset for another round of attempts.
+pub async fn enqueue_job(pool: &SqlitePool, kind: &str, payload: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO jobs (kind, payload) VALUES (?, ?)
+         ON CONFLICT(kind, payload) DO UPDATE SET
+            status = 'pending', attempts = 0, last_error = NULL,
+            run_after = datetime('now'), updated_at = datetime('now')
+         WHERE jobs.status = 'failed'",
+    )
+    .bind(kind)
+    .bind(payload)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Claim up to `limit` due pending jobs, marking them running and counting the attempt (transactional).
+pub async fn claim_due_jobs(pool: &SqlitePool, limit: i64) -> Result<Vec<Job>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let rows: Vec<(i64, String, String, i64, i64)> = sqlx::query_as(
+        "SELECT id, kind, payload, attempts, max_attempts FROM jobs
+         WHERE status = 'pending' AND run_after <= datetime('now')
+         ORDER BY run_after, id LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for row in &rows {
+        sqlx::query(
+            "UPDATE jobs SET status = 'running', attempts = attempts + 1, updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(row.0)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| Job {
+            id: r.0,
+            kind: r.1,
+            payload: r.2,
+            attempts: r.3 + 1,
+            max_attempts: r.4,
+        })
+        .collect())
+}
+
+/// Remove a job that finished successfully.
+pub async fn complete_job(pool: &SqlitePool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM jobs WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Record a job failure: re-queue it after `retry_in_secs`, or mark it failed when `None`.
+pub async fn fail_job(
+    pool: &SqlitePool,
+    id: i64,
+    error: &str,
+    retry_in_secs: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    match retry_in_secs {
+        Some(secs) => {
             sqlx::query(
-                "DELETE FROM item_groups WHERE item_id IN (
-                    SELECT id FROM clipboard_items
-                    WHERE is_favorited = 0
-                    ORDER BY updated_at DESC, id DESC
-                    LIMIT -1 OFFSET ?
-                )",
+                "UPDATE jobs SET status = 'pending', last_error = ?,
+                    run_after = datetime('now', ? || ' seconds'), updated_at = datetime('now')
+                 WHERE id = ?",
             )
-            .bind(count)
-            .execute(&mut *tx)
+            .bind(error)
+            .bind(format!("+{}", secs))
+            .bind(id)
+            .execute(pool)
             .await?;
-
-            let result = sqlx::query(
-                "DELETE FROM clipboard_items WHERE is_favorited = 0 AND id NOT IN (
-                    SELECT id FROM clipboard_items
-                    WHERE is_favorited = 0
-                    ORDER BY updated_at DESC, id DESC
-                    LIMIT ?
-                )",
+        }
+        None => {
+            sqlx::query(
+                "UPDATE jobs SET status = 'failed', last_error = ?, updated_at = datetime('now') WHERE id = ?",
             )
-            .bind(count)
-            .execute(&mut *tx)
+            .bind(error)
+            .bind(id)
+            .execute(pool)
             .await?;
-
-            tx.commit().await?;
-
-            Ok(result.rows_affected() as i64)
         }
-        _ => Ok(0), // "unlimited" or invalid - no cleanup
     }
+    Ok(())
+}
+
+/// Put jobs left `running` by a previous session back in the queue. Returns how many.
+pub async fn requeue_running_jobs(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE jobs SET status = 'pending', run_after = datetime('now'), updated_at = datetime('now')
+         WHERE status = 'running'",
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Seconds until the earliest pending job becomes due (0 if one is due now, None if none pending).
+pub async fn next_job_due_in(pool: &SqlitePool) -> Result<Option<i64>, sqlx::Error> {
+    let row: (Option<i64>,) = sqlx::query_as(
+        "SELECT MAX(0, MIN(CAST(strftime('%s', run_after) AS INTEGER)) - CAST(strftime('%s', 'now') AS INTEGER))
+         FROM jobs WHERE status = 'pending'",
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(row.0)
+}
+
+/// Count jobs by status.
+pub async fn get_job_counts(pool: &SqlitePool) -> Result<JobCounts, sqlx::Error> {
+    let row: (i64, i64, i64) = sqlx::query_as(
+        "SELECT
+            COALESCE(SUM(status = 'pending'), 0),
+            COALESCE(SUM(status = 'running'), 0),
+            COALESCE(SUM(status = 'failed'), 0)
+         FROM jobs",
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(JobCounts {
+        pending: row.0,
+        running: row.1,
+        failed: row.2,
+    })
 }
 
 #[cfg(test)]
@@ -738,14 +1583,16 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "Hello from test".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "com.test.app".into(),
             source_app_name: "TestApp".into(),
             content_size: 15,
             content_hash: "hash-test-1".into(),
+            sensitive_ttl_secs: None,
         };
 
         let id = insert_item(&pool, &item).await.unwrap();
@@ -765,14 +1612,16 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "Duplicate content".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 17,
             content_hash: "same-hash".into(),
+            sensitive_ttl_secs: None,
         };
 
         let id1 = insert_item(&pool, &item).await.unwrap();
@@ -790,14 +1639,16 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "Bump test".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 9,
             content_hash: "bump-hash".into(),
+            sensitive_ttl_secs: None,
         };
 
         let id = insert_item(&pool, &item).await.unwrap();
@@ -842,14 +1693,16 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "To be deleted".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 13,
             content_hash: "delete-hash".into(),
+            sensitive_ttl_secs: None,
         };
 
         let id = insert_item(&pool, &item).await.unwrap();
@@ -875,39 +1728,47 @@ mod tests {
                 content_type: ContentType::PlainText,
                 plain_text: text.to_string(),
                 rich_content: None,
-                thumbnail: None,
                 image_path: None,
+                image_hash: None,
+                image_mime: None,
                 file_path: None,
                 file_name: None,
                 source_app: "".into(),
                 source_app_name: "".into(),
                 content_size: text.len() as i64,
                 content_hash: format!("hash-{}", i),
+                sensitive_ttl_secs: None,
             };
             insert_item(&pool, &item).await.unwrap();
         }
 
         // FTS search (>= 3 chars)
-        let results = search_items(&pool, "World", None, 10, false).await.unwrap();
+        let results = search_items(&pool, "World", &ItemFilter::default(), 10)
+            .await
+            .unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].plain_text.contains("World"));
 
         // Chinese search (>= 3 chars for trigram)
-        let results = search_items(&pool, "中文搜", None, 10, false)
+        let results = search_items(&pool, "中文搜", &ItemFilter::default(), 10)
             .await
             .unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].plain_text.contains("中文"));
 
         // Short query fallback to LIKE
-        let results = search_items(&pool, "Ru", None, 10, false).await.unwrap();
+        let results = search_items(&pool, "Ru", &ItemFilter::default(), 10)
+            .await
+            .unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].plain_text.contains("Rust"));
 
         // Type filter
-        let results = search_items(&pool, "World", Some("image"), 10, false)
-            .await
-            .unwrap();
+        let image_only = ItemFilter {
+            content_type: Some("image".to_string()),
+            ..Default::default()
+        };
+        let results = search_items(&pool, "World", &image_only, 10).await.unwrap();
         assert_eq!(results.len(), 0);
     }
 
@@ -919,28 +1780,32 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "text content".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 12,
             content_hash: "text-hash".into(),
+            sensitive_ttl_secs: None,
         };
 
         let image_item = NewClipboardItem {
             content_type: ContentType::Image,
             plain_text: "".into(),
             rich_content: None,
-            thumbnail: Some(vec![1, 2, 3]),
             image_path: Some("/tmp/test.png".into()),
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 1024,
             content_hash: "image-hash".into(),
+            sensitive_ttl_secs: None,
         };
 
         insert_item(&pool, &text_item).await.unwrap();
@@ -966,14 +1831,16 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "Favorite me".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 11,
             content_hash: "fav-hash".into(),
+            sensitive_ttl_secs: None,
         };
         let id = insert_item(&pool, &item).await.unwrap();
 
@@ -1023,6 +1890,11 @@ mod tests {
             .unwrap();
         let val = get_setting(&pool, "custom_key").await.unwrap();
         assert_eq!(val, Some("custom_value".to_string()));
+
+        // Delete key
+        delete_setting(&pool, "custom_key").await.unwrap();
+        let val = get_setting(&pool, "custom_key").await.unwrap();
+        assert_eq!(val, None);
     }
 
     #[tokio::test]
@@ -1035,14 +1907,16 @@ mod tests {
                 content_type: ContentType::PlainText,
                 plain_text: format!("Item {}", i),
                 rich_content: None,
-                thumbnail: None,
                 image_path: None,
+                image_hash: None,
+                image_mime: None,
                 file_path: None,
                 file_name: None,
                 source_app: "".into(),
                 source_app_name: "".into(),
                 content_size: 6,
                 content_hash: format!("clear-hash-{}", i),
+                sensitive_ttl_secs: None,
             };
             insert_item(&pool, &item).await.unwrap();
         }
@@ -1065,6 +1939,249 @@ mod tests {
         assert!(remaining[0].is_favorited);
     }
 
+    #[tokio::test]
+    async fn test_selection_origin() {
+        let pool = test_pool().await;
+
+        let mut ids = Vec::new();
+        for i in 0..2 {
+            let item = NewClipboardItem {
+                content_type: ContentType::PlainText,
+                plain_text: format!("Selected {}", i),
+                rich_content: None,
+                image_path: None,
+                image_hash: None,
+                image_mime: None,
+                file_path: None,
+                file_name: None,
+                source_app: "".into(),
+                source_app_name: "".into(),
+                content_size: 10,
+                content_hash: format!("selected-{}", i),
+                sensitive_ttl_secs: None,
+            };
+            ids.push(insert_item(&pool, &item).await.unwrap());
+        }
+        set_selection_origin(&pool, &ids[1], SelectionOrigin::Primary)
+            .await
+            .unwrap();
+
+        let items = get_items(&pool, None, 10, 0).await.unwrap();
+        let origin = |id: &str| items.iter().find(|i| i.id == id).unwrap().selection.clone();
+        assert_eq!(origin(&ids[0]), "clipboard");
+        assert_eq!(origin(&ids[1]), "primary");
+
+        let primary = ItemFilter {
+            selection: Some("primary".into()),
+            ..Default::default()
+        };
+        let results = search_items(&pool, "Selected", &primary, 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, ids[1]);
+        assert_eq!(clear_filtered(&pool, &primary).await.unwrap(), 1);
+        assert_eq!(get_items(&pool, None, 10, 0).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_items() {
+        let pool = test_pool().await;
+
+        let mut ids = Vec::new();
+        for (i, text) in ["hunter2-secret", "public-note"].iter().enumerate() {
+            let item = NewClipboardItem {
+                content_type: ContentType::PlainText,
+                plain_text: text.to_string(),
+                rich_content: None,
+                image_path: None,
+                image_hash: None,
+                image_mime: None,
+                file_path: None,
+                file_name: None,
+                source_app: "".into(),
+                source_app_name: "".into(),
+                content_size: 14,
+                content_hash: format!("sensitive-{}", i),
+                sensitive_ttl_secs: None,
+            };
+            ids.push(insert_item(&pool, &item).await.unwrap());
+        }
+        assert_eq!(next_expiry_secs(&pool).await.unwrap(), None);
+        mark_sensitive(&pool, &ids[0], 60).await.unwrap();
+
+        // Masked in lists and previews, but pasted from the stored text
+        let items = get_items(&pool, None, 10, 0).await.unwrap();
+        let secret = items.iter().find(|i| i.id == ids[0]).unwrap();
+        assert!(secret.is_sensitive);
+        assert_eq!(secret.plain_text, "");
+        let (_, pt, ..) = get_item_detail(&pool, &ids[0]).await.unwrap().unwrap();
+        assert_eq!(pt, "");
+        let (_, pt, ..) = get_item_by_id(&pool, &ids[0]).await.unwrap().unwrap();
+        assert_eq!(pt, "hunter2-secret");
+
+        // Neither FTS nor LIKE search finds it
+        let none = ItemFilter::default();
+        assert!(search_items(&pool, "hunter2", &none, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(search_items(&pool, "hunter2 secret", &none, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let secs = next_expiry_secs(&pool).await.unwrap().unwrap();
+        assert!((58..=60).contains(&secs));
+        assert_eq!(delete_expired_items(&pool).await.unwrap(), 0);
+
+        mark_sensitive(&pool, &ids[0], 0).await.unwrap();
+        assert_eq!(next_expiry_secs(&pool).await.unwrap(), Some(0));
+        assert_eq!(delete_expired_items(&pool).await.unwrap(), 1);
+        let remaining = get_items(&pool, None, 10, 0).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, ids[1]);
+        assert!(!remaining[0].is_sensitive);
+        assert_eq!(next_expiry_secs(&pool).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_insert_sensitive_item() {
+        let pool = test_pool().await;
+
+        let item = NewClipboardItem {
+            content_type: ContentType::PlainText,
+            plain_text: "hunter2-secret".into(),
+            rich_content: None,
+            image_path: None,
+            image_hash: None,
+            image_mime: None,
+            file_path: None,
+            file_name: None,
+            source_app: "".into(),
+            source_app_name: "".into(),
+            content_size: 14,
+            content_hash: "sensitive-insert".into(),
+            sensitive_ttl_secs: Some(60),
+        };
+        let id = insert_item(&pool, &item).await.unwrap();
+
+        // Masked and unsearchable from the moment it's stored
+        let fts_rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_fts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(fts_rows, 0);
+        let items = get_items(&pool, None, 10, 0).await.unwrap();
+        assert_eq!(items[0].id, id);
+        assert!(items[0].is_sensitive);
+        assert_eq!(items[0].plain_text, "");
+        let secs = next_expiry_secs(&pool).await.unwrap().unwrap();
+        assert!((58..=60).contains(&secs));
+    }
+
+    #[tokio::test]
+    async fn test_clear_filtered() {
+        let pool = test_pool().await;
+
+        let mut ids = Vec::new();
+        for (i, (ct, app)) in [
+            (ContentType::Image, "com.app.a"),
+            (ContentType::Image, "com.app.b"),
+            (ContentType::PlainText, "com.app.a"),
+            (ContentType::PlainText, "com.app.b"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let item = NewClipboardItem {
+                content_type: ct,
+                plain_text: format!("Filtered {}", i),
+                rich_content: None,
+                image_path: None,
+                image_hash: None,
+                image_mime: None,
+                file_path: None,
+                file_name: None,
+                source_app: app.into(),
+                source_app_name: "".into(),
+                content_size: 10,
+                content_hash: format!("filtered-{}", i),
+                sensitive_ttl_secs: None,
+            };
+            ids.push(insert_item(&pool, &item).await.unwrap());
+        }
+        // Items 0 and 2 are old; item 3 is in a group
+        sqlx::query(
+            "UPDATE clipboard_items SET created_at = datetime('now', '-5 days') WHERE id IN (?, ?)",
+        )
+        .bind(&ids[0])
+        .bind(&ids[2])
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO groups (id, name) VALUES ('g1', 'Work')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO item_groups (item_id, group_id) VALUES (?, 'g1')")
+            .bind(&ids[3])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Images older than 3 days
+        let old_images = ItemFilter {
+            content_type: Some("image".into()),
+            older_than_days: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(clear_filtered(&pool, &old_images).await.unwrap(), 1);
+
+        // The same filter narrows search
+        let in_group = ItemFilter {
+            group_id: Some("g1".into()),
+            ..Default::default()
+        };
+        let results = search_items(&pool, "Filtered", &in_group, 10)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, ids[3]);
+
+        // Everything not in a group, except favorites
+        sqlx::query("UPDATE clipboard_items SET is_favorited = 1 WHERE id = ?")
+            .bind(&ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+        let ungrouped = ItemFilter {
+            ungrouped: true,
+            ..Default::default()
+        };
+        assert_eq!(clear_filtered(&pool, &ungrouped).await.unwrap(), 1);
+
+        let remaining: Vec<String> = get_items(&pool, None, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.contains(&ids[1]) && remaining.contains(&ids[3]));
+
+        // Everything from one app
+        let from_b = ItemFilter {
+            source_app: Some("com.app.b".into()),
+            ..Default::default()
+        };
+        assert_eq!(clear_filtered(&pool, &from_b).await.unwrap(), 1);
+        let fts: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM clipboard_fts WHERE item_id = ?")
+            .bind(&ids[3])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(fts.0, 0);
+    }
+
     #[tokio::test]
     async fn test_cleanup_by_retention_count() {
         let pool = test_pool().await;
@@ -1075,20 +2192,22 @@ mod tests {
                 content_type: ContentType::PlainText,
                 plain_text: format!("Retention item {}", i),
                 rich_content: None,
-                thumbnail: None,
                 image_path: None,
+                image_hash: None,
+                image_mime: None,
                 file_path: None,
                 file_name: None,
                 source_app: "".into(),
                 source_app_name: "".into(),
                 content_size: 16,
                 content_hash: format!("ret-hash-{}", i),
+                sensitive_ttl_secs: None,
             };
             insert_item(&pool, &item).await.unwrap();
         }
 
         // Keep only 2 most recent
-        let deleted = cleanup_by_retention(&pool, "count", 0, 2).await.unwrap();
+        let deleted = cleanup_by_retention(&pool, "count", 0, 2, 0).await.unwrap();
         assert_eq!(deleted, 3);
 
         let remaining = get_items(&pool, None, 10, 0).await.unwrap();
@@ -1096,84 +2215,569 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_cleanup_unlimited_noop() {
+    async fn test_cleanup_unlimited_noop() {
+        let pool = test_pool().await;
+
+        let item = NewClipboardItem {
+            content_type: ContentType::PlainText,
+            plain_text: "Keep me".into(),
+            rich_content: None,
+            image_path: None,
+            image_hash: None,
+            image_mime: None,
+            file_path: None,
+            file_name: None,
+            source_app: "".into(),
+            source_app_name: "".into(),
+            content_size: 7,
+            content_hash: "noop-hash".into(),
+            sensitive_ttl_secs: None,
+        };
+        insert_item(&pool, &item).await.unwrap();
+
+        let deleted = cleanup_by_retention(&pool, "unlimited", 0, 0, 0)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 0);
+
+        let remaining = get_items(&pool, None, 10, 0).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_by_retention_days() {
+        let pool = test_pool().await;
+
+        // Insert 3 items
+        for i in 0..3 {
+            let item = NewClipboardItem {
+                content_type: ContentType::PlainText,
+                plain_text: format!("Old item {}", i),
+                rich_content: None,
+                image_path: None,
+                image_hash: None,
+                image_mime: None,
+                file_path: None,
+                file_name: None,
+                source_app: "".into(),
+                source_app_name: "".into(),
+                content_size: 10,
+                content_hash: format!("days-hash-{}", i),
+                sensitive_ttl_secs: None,
+            };
+            insert_item(&pool, &item).await.unwrap();
+        }
+
+        // Backdate created_at of all items to 60 days ago
+        sqlx::query("UPDATE clipboard_items SET created_at = datetime('now', '-60 days')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Insert 1 recent item (created_at defaults to now)
+        let recent = NewClipboardItem {
+            content_type: ContentType::PlainText,
+            plain_text: "Recent item".into(),
+            rich_content: None,
+            image_path: None,
+            image_hash: None,
+            image_mime: None,
+            file_path: None,
+            file_name: None,
+            source_app: "".into(),
+            source_app_name: "".into(),
+            content_size: 11,
+            content_hash: "days-hash-recent".into(),
+            sensitive_ttl_secs: None,
+        };
+        insert_item(&pool, &recent).await.unwrap();
+
+        // Retain items from the last 30 days — the 3 old items should be deleted
+        let deleted = cleanup_by_retention(&pool, "days", 30, 0, 0).await.unwrap();
+        assert_eq!(deleted, 3);
+
+        let remaining = get_items(&pool, None, 10, 0).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].plain_text, "Recent item");
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_by_retention_size() {
+        let pool = test_pool().await;
+
+        // Oldest first: a 600-byte image, 300 bytes of text, a favorited 5000-byte
+        // image and a recent 100-byte image
+        let old_image = insert_item(&pool, &image_item("size-old", "/tmp/size-old.png"))
+            .await
+            .unwrap();
+        let text = insert_item(
+            &pool,
+            &NewClipboardItem {
+                content_type: ContentType::PlainText,
+                plain_text: "x".repeat(300),
+                rich_content: None,
+                image_path: None,
+                image_hash: None,
+                image_mime: None,
+                file_path: None,
+                file_name: None,
+                source_app: "".into(),
+                source_app_name: "".into(),
+                content_size: 300,
+                content_hash: "size-text".into(),
+                sensitive_ttl_secs: None,
+            },
+        )
+        .await
+        .unwrap();
+        let fav = insert_item(&pool, &image_item("size-fav", "/tmp/size-fav.png"))
+            .await
+            .unwrap();
+        let recent = insert_item(&pool, &image_item("size-new", "/tmp/size-new.png"))
+            .await
+            .unwrap();
+        let age = |id: String, minutes: i64| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query(
+                    "UPDATE clipboard_items SET updated_at = datetime('now', ? || ' minutes')
+                     WHERE id = ?",
+                )
+                .bind(format!("-{}", minutes))
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+        };
+        for (id, minutes) in [(&old_image, 4), (&text, 3), (&fav, 2), (&recent, 1)] {
+            age(id.clone(), minutes).await;
+        }
+        for (hash, size) in [("size-old", 600), ("size-fav", 5000), ("size-new", 100)] {
+            sqlx::query("UPDATE image_blobs SET size = ? WHERE hash = ?")
+                .bind(size)
+                .bind(hash)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("UPDATE clipboard_items SET is_favorited = 1 WHERE id = ?")
+            .bind(&fav)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(logical_storage_bytes(&mut conn).await.unwrap(), 6000);
+        drop(conn);
+
+        // 700 bytes over a 5300-byte budget: the old image frees 600, so the text goes too
+        let paths = get_retention_overflow_image_paths(&pool, "size", 0, 0, 5300)
+            .await
+            .unwrap();
+        assert_eq!(paths, vec!["/tmp/size-old.png".to_string()]);
+        let deleted = cleanup_by_retention(&pool, "size", 0, 0, 5300)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 2);
+
+        let remaining: Vec<String> = get_items(&pool, None, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(remaining, vec![recent.clone(), fav.clone()]);
+
+        // The deletions count right away, so running again with unchanged data is a no-op
+        assert_eq!(
+            cleanup_by_retention(&pool, "size", 0, 0, 5300)
+                .await
+                .unwrap(),
+            0
+        );
+
+        // A shared image is only freed once its last item goes: deleting the oldest
+        // reference alone frees nothing, so the next items are taken as well
+        let shared_old = insert_item(&pool, &image_item("size-shared", "/tmp/size-shared.png"))
+            .await
+            .unwrap();
+        let shared_new = insert_item(
+            &pool,
+            &NewClipboardItem {
+                content_hash: "size-shared-copy".into(),
+                ..image_item("size-shared", "/tmp/size-shared.png")
+            },
+        )
+        .await
+        .unwrap();
+        age(shared_old.clone(), 10).await;
+        sqlx::query("UPDATE image_blobs SET size = 1000 WHERE hash = 'size-shared'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let deleted = cleanup_by_retention(&pool, "size", 0, 0, 5300)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 3);
+        let remaining: Vec<String> = get_items(&pool, None, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(remaining, vec![fav.clone()]);
+        assert!(!remaining.contains(&shared_new));
+        assert_eq!(
+            cleanup_by_retention(&pool, "size", 0, 0, 5300)
+                .await
+                .unwrap(),
+            0
+        );
+
+        // No budget: nothing to do
+        assert_eq!(
+            cleanup_by_retention(&pool, "size", 0, 0, 0).await.unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_size_retention_stable_across_maintenance_passes() {
+        let pool = test_pool().await;
+        for n in 0..20 {
+            insert_item(
+                &pool,
+                &NewClipboardItem {
+                    content_type: ContentType::PlainText,
+                    plain_text: format!("{:04}{}", n, "x".repeat(996)),
+                    rich_content: None,
+                    image_path: None,
+                    image_hash: None,
+                    image_mime: None,
+                    file_path: None,
+                    file_name: None,
+                    source_app: "".into(),
+                    source_app_name: "".into(),
+                    content_size: 1000,
+                    content_hash: format!("pass-{}", n),
+                    sensitive_ttl_secs: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        // What the maintenance scheduler does every interval, with VACUUM off
+        let mut deleted = Vec::new();
+        for _ in 0..3 {
+            deleted.push(
+                cleanup_by_retention(&pool, "size", 0, 0, 12_000)
+                    .await
+                    .unwrap(),
+            );
+            checkpoint_wal(&pool).await.unwrap();
+        }
+        assert_eq!(deleted, vec![8, 0, 0]);
+        assert_eq!(get_items(&pool, None, 50, 0).await.unwrap().len(), 12);
+    }
+
+    #[tokio::test]
+    async fn test_retention_rules_per_type() {
+        let pool = test_pool().await;
+
+        let text = |n: usize, app: &str| NewClipboardItem {
+            content_type: ContentType::PlainText,
+            plain_text: format!("text {}", n),
+            rich_content: None,
+            image_path: None,
+            image_hash: None,
+            image_mime: None,
+            file_path: None,
+            file_name: None,
+            source_app: app.into(),
+            source_app_name: "".into(),
+            content_size: 100,
+            content_hash: format!("rule-text-{}", n),
+            sensitive_ttl_secs: None,
+        };
+        for n in 0..3 {
+            insert_item(&pool, &text(n, "com.editor")).await.unwrap();
+        }
+        let term_a = insert_item(&pool, &text(3, "com.terminal")).await.unwrap();
+        let term_b = insert_item(&pool, &text(4, "com.terminal")).await.unwrap();
+        let old_image = insert_item(&pool, &image_item("rule-img-old", "/tmp/rule-old.png"))
+            .await
+            .unwrap();
+        let fav_image = insert_item(&pool, &image_item("rule-img-fav", "/tmp/rule-fav.png"))
+            .await
+            .unwrap();
+        insert_item(&pool, &image_item("rule-img-new", "/tmp/rule-new.png"))
+            .await
+            .unwrap();
+
+        // Everything but the newest image is two weeks old; term_b is the most recent text
+        sqlx::query("UPDATE clipboard_items SET created_at = datetime('now', '-14 days'), is_favorited = (id = ?) WHERE content_hash != 'rule-img-new'")
+            .bind(&fav_image)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE clipboard_items SET updated_at = datetime('now', '+1 minute') WHERE id = ?",
+        )
+        .bind(&term_b)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Images: one week. Terminal text: keep only the latest. Other text: a year.
+        for rule in [
+            RetentionRule {
+                content_type: "image".into(),
+                max_days: Some(7),
+                ..Default::default()
+            },
+            RetentionRule {
+                content_type: "plain_text".into(),
+                source_app: "com.terminal".into(),
+                max_count: Some(1),
+                ..Default::default()
+            },
+            RetentionRule {
+                content_type: "plain_text".into(),
+                max_days: Some(365),
+                ..Default::default()
+            },
+        ] {
+            upsert_retention_rule(&pool, &rule).await.unwrap();
+        }
+        assert_eq!(get_retention_rules(&pool).await.unwrap().len(), 3);
+
+        let paths = get_retention_overflow_image_paths(&pool, "unlimited", 0, 0, 0)
+            .await
+            .unwrap();
+        assert_eq!(paths, vec!["/tmp/rule-old.png".to_string()]);
+
+        let deleted = cleanup_by_retention(&pool, "unlimited", 0, 0, 0)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 2);
+        let remaining: Vec<String> = get_items(&pool, None, 20, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(remaining.len(), 6);
+        assert!(!remaining.contains(&old_image));
+        assert!(!remaining.contains(&term_a));
+        assert!(remaining.contains(&term_b));
+        assert!(remaining.contains(&fav_image));
+
+        // Upserting the same scope replaces its limits instead of adding a rule
+        let id = upsert_retention_rule(
+            &pool,
+            &RetentionRule {
+                content_type: "plain_text".into(),
+                max_size_mb: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let rules = get_retention_rules(&pool).await.unwrap();
+        assert_eq!(rules.len(), 3);
+        let rule = rules.iter().find(|r| r.id == id).unwrap();
+        assert_eq!((rule.max_days, rule.max_size_mb), (None, Some(1)));
+
+        delete_retention_rule(&pool, id).await.unwrap();
+        assert_eq!(get_retention_rules(&pool).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_app_capture_rules() {
+        let pool = test_pool().await;
+
+        // The migration seeds password managers and remote desktop clients
+        let defaults = get_app_capture_rules(&pool).await.unwrap();
+        assert!(defaults
+            .iter()
+            .any(|r| r.app == "KeePassXC" && r.action == "exclude"));
+
+        // The app name is unique regardless of case, so this updates the seeded rule
+        let id = upsert_app_capture_rule(
+            &pool,
+            &AppCaptureRule {
+                app: "keepassxc".into(),
+                action: "include".into(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let rules = get_app_capture_rules(&pool).await.unwrap();
+        assert_eq!(rules.len(), defaults.len());
+        let rule = rules.iter().find(|r| r.id == id).unwrap();
+        assert_eq!(
+            (rule.app.as_str(), rule.action.as_str()),
+            ("KeePassXC", "include")
+        );
+
+        delete_app_capture_rule(&pool, id).await.unwrap();
+        assert_eq!(
+            get_app_capture_rules(&pool).await.unwrap().len(),
+            defaults.len() - 1
+        );
+
+        assert!(has_app_capture_rules(&pool).await.unwrap());
+        for rule in get_app_capture_rules(&pool).await.unwrap() {
+            delete_app_capture_rule(&pool, rule.id).await.unwrap();
+        }
+        assert!(!has_app_capture_rules(&pool).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_capture_ignore_rules() {
         let pool = test_pool().await;
+        assert!(get_capture_ignore_rules(&pool).await.unwrap().is_empty());
+
+        let otp = save_capture_ignore_rule(
+            &pool,
+            &CaptureIgnoreRule {
+                kind: "regex".into(),
+                value: r"^\d{6}$".into(),
+                enabled: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let blank = save_capture_ignore_rule(
+            &pool,
+            &CaptureIgnoreRule {
+                kind: "whitespace".into(),
+                enabled: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Saving with an id updates in place
+        save_capture_ignore_rule(
+            &pool,
+            &CaptureIgnoreRule {
+                id: otp,
+                kind: "regex".into(),
+                value: r"^\d{8}$".into(),
+                enabled: false,
+            },
+        )
+        .await
+        .unwrap();
+        let rules = get_capture_ignore_rules(&pool).await.unwrap();
+        assert_eq!(
+            rules.iter().map(|r| r.id).collect::<Vec<_>>(),
+            vec![otp, blank]
+        );
+        assert_eq!(rules[0].value, r"^\d{8}$");
+        assert!(!rules[0].enabled);
 
-        let item = NewClipboardItem {
-            content_type: ContentType::PlainText,
-            plain_text: "Keep me".into(),
-            rich_content: None,
-            thumbnail: None,
-            image_path: None,
-            file_path: None,
-            file_name: None,
-            source_app: "".into(),
-            source_app_name: "".into(),
-            content_size: 7,
-            content_hash: "noop-hash".into(),
+        // The check constraint rejects unknown kinds; unknown ids aren't created
+        let bad = CaptureIgnoreRule {
+            kind: "color".into(),
+            ..Default::default()
         };
-        insert_item(&pool, &item).await.unwrap();
-
-        let deleted = cleanup_by_retention(&pool, "unlimited", 0, 0)
-            .await
-            .unwrap();
-        assert_eq!(deleted, 0);
+        assert!(save_capture_ignore_rule(&pool, &bad).await.is_err());
+        let missing = CaptureIgnoreRule {
+            id: 999,
+            kind: "whitespace".into(),
+            ..Default::default()
+        };
+        assert!(save_capture_ignore_rule(&pool, &missing).await.is_err());
 
-        let remaining = get_items(&pool, None, 10, 0).await.unwrap();
-        assert_eq!(remaining.len(), 1);
+        delete_capture_ignore_rule(&pool, otp).await.unwrap();
+        assert_eq!(get_capture_ignore_rules(&pool).await.unwrap().len(), 1);
     }
 
     #[tokio::test]
-    async fn test_cleanup_by_retention_days() {
+    async fn test_preview_retention_matches_cleanup() {
         let pool = test_pool().await;
 
-        // Insert 3 items
-        for i in 0..3 {
+        for i in 0..4 {
             let item = NewClipboardItem {
                 content_type: ContentType::PlainText,
-                plain_text: format!("Old item {}", i),
+                plain_text: format!("preview text {}", i),
                 rich_content: None,
-                thumbnail: None,
                 image_path: None,
+                image_hash: None,
+                image_mime: None,
                 file_path: None,
                 file_name: None,
                 source_app: "".into(),
                 source_app_name: "".into(),
                 content_size: 10,
-                content_hash: format!("days-hash-{}", i),
+                content_hash: format!("preview-text-{}", i),
+                sensitive_ttl_secs: None,
             };
             insert_item(&pool, &item).await.unwrap();
         }
-
-        // Backdate created_at of all items to 60 days ago
-        sqlx::query("UPDATE clipboard_items SET created_at = datetime('now', '-60 days')")
-            .execute(&pool)
+        for hash in ["preview-img-1", "preview-img-2"] {
+            insert_item(&pool, &image_item(hash, &format!("/tmp/{}.png", hash)))
+                .await
+                .unwrap();
+        }
+        // Newer in insertion order; the first text item is a favorite
+        sqlx::query(
+            "UPDATE clipboard_items SET updated_at = datetime('now', '+' || rowid || ' minutes'),
+                    is_favorited = (content_hash = 'preview-text-0')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Keep only the newest image: the three other texts and the older image go
+        let preview = preview_retention(&pool, "count", 0, 1, 0, 2).await.unwrap();
+        assert_eq!(preview.total_items, 4);
+        assert_eq!(preview.total_bytes, 3 * 10 + 2048);
+        assert_eq!(
+            preview.by_type,
+            vec![
+                RetentionTypeSummary {
+                    content_type: "plain_text".into(),
+                    items: 3,
+                    bytes: 30,
+                },
+                RetentionTypeSummary {
+                    content_type: "image".into(),
+                    items: 1,
+                    bytes: 2048,
+                },
+            ]
+        );
+        let sample: Vec<&str> = preview
+            .sample
+            .iter()
+            .map(|i| i.content_hash.as_str())
+            .collect();
+        assert_eq!(sample, vec!["preview-img-1", "preview-text-3"]);
+
+        // Nothing was deleted, and the real run removes exactly the previewed items
+        assert_eq!(get_items(&pool, None, 20, 0).await.unwrap().len(), 6);
+        let deleted = cleanup_by_retention(&pool, "count", 0, 1, 0).await.unwrap();
+        assert_eq!(deleted, preview.total_items);
+
+        let empty = preview_retention(&pool, "unlimited", 0, 0, 0, 5)
             .await
             .unwrap();
+        assert_eq!(empty.total_items, 0);
+        assert!(empty.by_type.is_empty());
+    }
 
-        // Insert 1 recent item (created_at defaults to now)
-        let recent = NewClipboardItem {
-            content_type: ContentType::PlainText,
-            plain_text: "Recent item".into(),
-            rich_content: None,
-            thumbnail: None,
-            image_path: None,
-            file_path: None,
-            file_name: None,
-            source_app: "".into(),
-            source_app_name: "".into(),
-            content_size: 11,
-            content_hash: "days-hash-recent".into(),
-        };
-        insert_item(&pool, &recent).await.unwrap();
-
-        // Retain items from the last 30 days — the 3 old items should be deleted
-        let deleted = cleanup_by_retention(&pool, "days", 30, 0).await.unwrap();
-        assert_eq!(deleted, 3);
-
-        let remaining = get_items(&pool, None, 10, 0).await.unwrap();
-        assert_eq!(remaining.len(), 1);
-        assert_eq!(remaining[0].plain_text, "Recent item");
+    #[tokio::test]
+    async fn test_checkpoint_and_vacuum() {
+        let pool = test_pool().await;
+        checkpoint_wal(&pool).await.unwrap();
+        vacuum(&pool).await.unwrap();
     }
 
     #[tokio::test]
@@ -1185,23 +2789,29 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "Some content".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 12,
             content_hash: "empty-q-hash".into(),
+            sensitive_ttl_secs: None,
         };
         insert_item(&pool, &item).await.unwrap();
 
         // Empty string query should return empty results (search_items_like returns vec![] for empty tokens)
-        let results = search_items(&pool, "", None, 10, false).await.unwrap();
+        let results = search_items(&pool, "", &ItemFilter::default(), 10)
+            .await
+            .unwrap();
         assert!(results.is_empty());
 
         // Whitespace-only query should also return empty results
-        let results = search_items(&pool, "   ", None, 10, false).await.unwrap();
+        let results = search_items(&pool, "   ", &ItemFilter::default(), 10)
+            .await
+            .unwrap();
         assert!(results.is_empty());
     }
 
@@ -1213,32 +2823,34 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "Normal text content".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 19,
             content_hash: "special-hash".into(),
+            sensitive_ttl_secs: None,
         };
         insert_item(&pool, &item).await.unwrap();
 
         // FTS5 special characters should not cause a crash
         // Quotes are escaped by the search_items function (double-quoting)
-        let results = search_items(&pool, "\"quoted\"", None, 10, false).await;
+        let results = search_items(&pool, "\"quoted\"", &ItemFilter::default(), 10).await;
         assert!(results.is_ok());
 
         // Asterisks (FTS5 prefix operator) should not crash
-        let results = search_items(&pool, "text*", None, 10, false).await;
+        let results = search_items(&pool, "text*", &ItemFilter::default(), 10).await;
         assert!(results.is_ok());
 
         // Parentheses and boolean operators
-        let results = search_items(&pool, "OR AND NOT", None, 10, false).await;
+        let results = search_items(&pool, "OR AND NOT", &ItemFilter::default(), 10).await;
         assert!(results.is_ok());
 
         // Braces and brackets
-        let results = search_items(&pool, "{test}[0]", None, 10, false).await;
+        let results = search_items(&pool, "{test}[0]", &ItemFilter::default(), 10).await;
         assert!(results.is_ok());
     }
 
@@ -1250,29 +2862,37 @@ mod tests {
             content_type: ContentType::PlainText,
             plain_text: "X marks the spot".into(),
             rich_content: None,
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: 16,
             content_hash: "single-char-hash".into(),
+            sensitive_ttl_secs: None,
         };
         insert_item(&pool, &item).await.unwrap();
 
         // Single character (< 3 chars) should fall back to LIKE search
-        let results = search_items(&pool, "X", None, 10, false).await.unwrap();
+        let results = search_items(&pool, "X", &ItemFilter::default(), 10)
+            .await
+            .unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].plain_text.contains("X"));
 
         // Two characters should also use LIKE fallback
-        let results = search_items(&pool, "ma", None, 10, false).await.unwrap();
+        let results = search_items(&pool, "ma", &ItemFilter::default(), 10)
+            .await
+            .unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].plain_text.contains("marks"));
 
         // Single char with no match
-        let results = search_items(&pool, "Z", None, 10, false).await.unwrap();
+        let results = search_items(&pool, "Z", &ItemFilter::default(), 10)
+            .await
+            .unwrap();
         assert!(results.is_empty());
     }
 
@@ -1285,14 +2905,16 @@ mod tests {
             content_type: ContentType::RichText,
             plain_text: "Hello World".into(),
             rich_content: Some(rich_html.as_bytes().to_vec()),
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "com.test.editor".into(),
             source_app_name: "TestEditor".into(),
             content_size: rich_html.len() as i64,
             content_hash: "rich-hash".into(),
+            sensitive_ttl_secs: None,
         };
 
         let id = insert_item(&pool, &item).await.unwrap();
@@ -1300,10 +2922,11 @@ mod tests {
         let detail = get_item_detail(&pool, &id).await.unwrap();
         assert!(detail.is_some());
 
-        let (ct, pt, rc, _ip, _fp, _fn, cs) = detail.unwrap();
+        let (ct, pt, rc, _ip, im, _fp, _fn, cs) = detail.unwrap();
         assert_eq!(ct, "rich_text");
         assert_eq!(pt, "Hello World");
         assert_eq!(rc, Some(rich_html.to_string()));
+        assert_eq!(im, None);
         assert_eq!(cs, rich_html.len() as i64);
 
         // Test with UTF-8 rich content containing CJK characters
@@ -1312,21 +2935,481 @@ mod tests {
             content_type: ContentType::RichText,
             plain_text: "你好世界".into(),
             rich_content: Some(cjk_html.as_bytes().to_vec()),
-            thumbnail: None,
             image_path: None,
+            image_hash: None,
+            image_mime: None,
             file_path: None,
             file_name: None,
             source_app: "".into(),
             source_app_name: "".into(),
             content_size: cjk_html.len() as i64,
             content_hash: "cjk-rich-hash".into(),
+            sensitive_ttl_secs: None,
         };
 
         let cjk_id = insert_item(&pool, &cjk_item).await.unwrap();
         let cjk_detail = get_item_detail(&pool, &cjk_id).await.unwrap();
         assert!(cjk_detail.is_some());
 
-        let (_, _, cjk_rc, _, _, _, _) = cjk_detail.unwrap();
+        let (_, _, cjk_rc, _, _, _, _, _) = cjk_detail.unwrap();
         assert_eq!(cjk_rc, Some(cjk_html.to_string()));
     }
+
+    #[tokio::test]
+    async fn test_merge_rich_and_plain_copies() {
+        let pool = test_pool().await;
+        let html = "<p>Same <b>text</b></p>";
+
+        let plain_id = insert_item(
+            &pool,
+            &NewClipboardItem {
+                content_type: ContentType::PlainText,
+                plain_text: "Same text".into(),
+                rich_content: None,
+                image_path: None,
+                image_hash: None,
+                image_mime: None,
+                file_path: None,
+                file_name: None,
+                source_app: "".into(),
+                source_app_name: "".into(),
+                content_size: 9,
+                content_hash: "same-text-hash".into(),
+                sensitive_ttl_secs: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // The rich copy finds the plain item and upgrades it in place
+        let found = find_and_bump_by_hash(&pool, "same-text-hash")
+            .await
+            .unwrap();
+        assert_eq!(found.as_deref(), Some(plain_id.as_str()));
+        assert!(merge_rich_content(&pool, &plain_id, html.as_bytes())
+            .await
+            .unwrap());
+
+        let (ct, pt, rc, ..) = get_item_detail(&pool, &plain_id).await.unwrap().unwrap();
+        assert_eq!(ct, "rich_text");
+        assert_eq!(pt, "Same text");
+        assert_eq!(rc.as_deref(), Some(html));
+
+        // Copying it again (rich or plain) leaves the rich item untouched
+        assert!(!merge_rich_content(&pool, &plain_id, b"<p>other</p>")
+            .await
+            .unwrap());
+        let (_, _, rc, ..) = get_item_detail(&pool, &plain_id).await.unwrap().unwrap();
+        assert_eq!(rc.as_deref(), Some(html));
+        assert_eq!(get_items(&pool, None, 10, 0).await.unwrap().len(), 1);
+    }
+
+    fn image_item(hash: &str, path: &str) -> NewClipboardItem {
+        NewClipboardItem {
+            content_type: ContentType::Image,
+            plain_text: "".into(),
+            rich_content: None,
+            image_path: Some(path.into()),
+            image_hash: Some(hash.into()),
+            image_mime: Some("image/png".into()),
+            file_path: None,
+            file_name: None,
+            source_app: "".into(),
+            source_app_name: "".into(),
+            content_size: 2048,
+            content_hash: hash.into(),
+            sensitive_ttl_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_image_mime_roundtrip() {
+        let pool = test_pool().await;
+
+        let mut gif = image_item("gifhash", "/data/images/gi/gifhash.gif");
+        gif.image_mime = Some("image/gif".into());
+        let id = insert_item(&pool, &gif).await.unwrap();
+
+        let (_, _, _, ip, im, _) = get_item_by_id(&pool, &id).await.unwrap().unwrap();
+        assert_eq!(ip.as_deref(), Some("/data/images/gi/gifhash.gif"));
+        assert_eq!(im.as_deref(), Some("image/gif"));
+
+        let (_, _, _, _, im, _, _, _) = get_item_detail(&pool, &id).await.unwrap().unwrap();
+        assert_eq!(im.as_deref(), Some("image/gif"));
+    }
+
+    #[tokio::test]
+    async fn test_image_metadata_roundtrip() {
+        let pool = test_pool().await;
+        let id = insert_item(
+            &pool,
+            &image_item("metahash", "/data/images/me/metahash.jpg"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_items_missing_image_metadata(&pool).await.unwrap().len(),
+            1
+        );
+        assert_eq!(get_image_metadata(&pool, &id).await.unwrap(), None);
+
+        let meta = ImageMetadata {
+            width: 4000,
+            height: 3000,
+            format: "jpeg".into(),
+            color_type: "rgb8".into(),
+            camera: Some("Canon EOS R5".into()),
+            taken_at: Some("2024-05-06T07:08:09".into()),
+            gps_latitude: Some(35.675),
+            gps_longitude: Some(-139.75),
+        };
+        set_image_metadata(&pool, &id, &meta).await.unwrap();
+
+        assert_eq!(get_image_metadata(&pool, &id).await.unwrap(), Some(meta));
+        assert!(get_items_missing_image_metadata(&pool)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(get_image_metadata(&pool, "missing").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_near_duplicate_variants() {
+        let pool = test_pool().await;
+        let primary = insert_item(&pool, &image_item("shot1", "/data/images/sh/shot1.png"))
+            .await
+            .unwrap();
+        let variant = insert_item(&pool, &image_item("shot2", "/data/images/sh/shot2.jpg"))
+            .await
+            .unwrap();
+        assert_eq!(
+            get_items_missing_perceptual_hash(&pool)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        set_perceptual_hash(&pool, &primary, u64::MAX - 1, None)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE clipboard_items SET updated_at = '2000-01-01 00:00:00' WHERE id = ?")
+            .bind(&primary)
+            .execute(&pool)
+            .await
+            .unwrap();
+        set_perceptual_hash(&pool, &variant, u64::MAX, Some(&primary))
+            .await
+            .unwrap();
+        assert!(get_items_missing_perceptual_hash(&pool)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Variants are hidden from the list and counted on their primary,
+        // which grouping brought back to the top
+        let items = get_items(&pool, None, 50, 0).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, primary);
+        assert_eq!(items[0].variant_count, 1);
+        assert_ne!(items[0].updated_at, "2000-01-01 00:00:00");
+        let variants = get_item_variants(&pool, &primary).await.unwrap();
+        assert_eq!(variants.len(), 1);
+        assert_eq!(variants[0].id, variant);
+
+        // Only primaries are candidates; u64 hashes survive the i64 column
+        let candidates = get_similarity_candidates(&pool, None, 10).await.unwrap();
+        assert_eq!(
+            candidates,
+            vec![(primary.clone(), "shot1".to_string(), u64::MAX - 1)]
+        );
+
+        // Aspect ratio filter uses recorded dimensions
+        let meta = ImageMetadata {
+            width: 1600,
+            height: 900,
+            format: "png".into(),
+            color_type: "rgba8".into(),
+            ..Default::default()
+        };
+        set_image_metadata(&pool, &primary, &meta).await.unwrap();
+        assert_eq!(
+            get_similarity_candidates(&pool, Some(16.0 / 9.0), 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(get_similarity_candidates(&pool, Some(4.0 / 3.0), 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Re-copying a variant bumps its primary
+        sqlx::query("UPDATE clipboard_items SET updated_at = '2000-01-01 00:00:00'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            find_and_bump_by_hash(&pool, "shot2").await.unwrap(),
+            Some(variant.clone())
+        );
+        let items = get_items(&pool, None, 50, 0).await.unwrap();
+        assert_ne!(items[0].updated_at, "2000-01-01 00:00:00");
+
+        // Deleting the primary promotes its variants
+        delete_item(&pool, &primary).await.unwrap();
+        let items = get_items(&pool, None, 50, 0).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, variant);
+        assert_eq!(items[0].variant_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_thumbnail_sources_and_legacy_blobs() {
+        let pool = test_pool().await;
+
+        let img_id = insert_item(&pool, &image_item("imghash", "/data/images/im/imghash.png"))
+            .await
+            .unwrap();
+        let file = NewClipboardItem {
+            content_type: ContentType::File,
+            plain_text: "/home/me/photo.jpg".into(),
+            rich_content: None,
+            image_path: None,
+            image_hash: None,
+            image_mime: None,
+            file_path: Some("/home/me/photo.jpg".into()),
+            file_name: Some("photo.jpg".into()),
+            source_app: "".into(),
+            source_app_name: "".into(),
+            content_size: 100,
+            content_hash: "filehash".into(),
+            sensitive_ttl_secs: None,
+        };
+        insert_item(&pool, &file).await.unwrap();
+        let text = NewClipboardItem {
+            content_type: ContentType::PlainText,
+            plain_text: "just text".into(),
+            file_path: None,
+            file_name: None,
+            content_hash: "texthash".into(),
+            ..file
+        };
+        insert_item(&pool, &text).await.unwrap();
+
+        let mut sources = get_thumbnail_sources(&pool).await.unwrap();
+        sources.sort();
+        assert_eq!(
+            sources,
+            vec![
+                (
+                    "file".to_string(),
+                    "filehash".to_string(),
+                    "/home/me/photo.jpg".to_string()
+                ),
+                (
+                    "image".to_string(),
+                    "imghash".to_string(),
+                    "/data/images/im/imghash.png".to_string()
+                ),
+            ]
+        );
+        let mut hashes = get_thumbnail_hashes(&pool).await.unwrap();
+        hashes.sort();
+        assert_eq!(hashes, vec!["filehash", "imghash"]);
+        assert_eq!(
+            get_content_hash(&pool, &img_id).await.unwrap().as_deref(),
+            Some("imghash")
+        );
+
+        // Simulate a row written before the disk cache existed
+        sqlx::query("UPDATE clipboard_items SET thumbnail = ? WHERE id = ?")
+            .bind(vec![1u8, 2, 3])
+            .bind(&img_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let legacy = get_legacy_thumbnails(&pool, 10).await.unwrap();
+        assert_eq!(legacy.len(), 1);
+        assert_eq!(legacy[0].0, img_id);
+        assert_eq!(legacy[0].3, vec![1, 2, 3]);
+
+        clear_thumbnail_blob(&pool, &img_id).await.unwrap();
+        assert!(get_legacy_thumbnails(&pool, 10).await.unwrap().is_empty());
+    }
+
+    async fn blob_ref_count(pool: &SqlitePool, hash: &str) -> Option<i64> {
+        sqlx::query_as::<_, (i64,)>("SELECT ref_count FROM image_blobs WHERE hash = ?")
+            .bind(hash)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+            .map(|r| r.0)
+    }
+
+    #[tokio::test]
+    async fn test_image_blob_ref_counting() {
+        let pool = test_pool().await;
+        let path = "/data/images/ab/abc.png";
+
+        let id1 = insert_item(&pool, &image_item("abc", path)).await.unwrap();
+        assert_eq!(blob_ref_count(&pool, "abc").await, Some(1));
+
+        // A second item sharing the same original bumps the count
+        let mut second = image_item("abc", path);
+        second.content_hash = "abc-variant".into();
+        let id2 = insert_item(&pool, &second).await.unwrap();
+        assert_eq!(blob_ref_count(&pool, "abc").await, Some(2));
+
+        // Deleting one item keeps the file alive
+        delete_item(&pool, &id1).await.unwrap();
+        assert_eq!(blob_ref_count(&pool, "abc").await, Some(1));
+        let released = release_image_paths(&pool, vec![path.into()]).await.unwrap();
+        assert!(released.is_empty());
+
+        // Deleting the last reference releases it
+        delete_item(&pool, &id2).await.unwrap();
+        let released = release_image_paths(&pool, vec![path.into(), path.into()])
+            .await
+            .unwrap();
+        assert_eq!(released, vec![path.to_string()]);
+        assert_eq!(blob_ref_count(&pool, "abc").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_clear_history_releases_shared_images() {
+        let pool = test_pool().await;
+        let path = "/data/images/de/def.png";
+
+        let fav_id = insert_item(&pool, &image_item("def", path)).await.unwrap();
+        let mut other = image_item("def", path);
+        other.content_hash = "def-2".into();
+        insert_item(&pool, &other).await.unwrap();
+
+        sqlx::query("UPDATE clipboard_items SET is_favorited = 1 WHERE id = ?")
+            .bind(&fav_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        clear_history(&pool).await.unwrap();
+
+        // The favorite still references the file
+        assert_eq!(blob_ref_count(&pool, "def").await, Some(1));
+        let released = release_image_paths(&pool, vec![path.into()]).await.unwrap();
+        assert!(released.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_item_image_migrates_legacy_path() {
+        let pool = test_pool().await;
+
+        let mut legacy = image_item("legacy", "/data/images/2025-01/uuid.png");
+        legacy.image_hash = None;
+        let id = insert_item(&pool, &legacy).await.unwrap();
+
+        let pending = get_legacy_image_items(&pool).await.unwrap();
+        assert_eq!(
+            pending,
+            vec![(id.clone(), "/data/images/2025-01/uuid.png".into())]
+        );
+
+        set_item_image(&pool, &id, "/data/images/12/1234.png", "1234", 2048)
+            .await
+            .unwrap();
+        assert!(get_legacy_image_items(&pool).await.unwrap().is_empty());
+        assert_eq!(blob_ref_count(&pool, "1234").await, Some(1));
+        assert_eq!(
+            get_image_path_by_id(&pool, &id).await.unwrap(),
+            Some("/data/images/12/1234.png".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_purge_unreferenced_image_blobs() {
+        let pool = test_pool().await;
+
+        let id = insert_item(&pool, &image_item("gc", "/data/images/gc/gc.png"))
+            .await
+            .unwrap();
+        delete_item(&pool, &id).await.unwrap();
+
+        assert_eq!(purge_unreferenced_image_blobs(&pool).await.unwrap(), 1);
+        assert_eq!(blob_ref_count(&pool, "gc").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_job_queue_lifecycle() {
+        let pool = test_pool().await;
+
+        enqueue_job(&pool, "thumbnail", r#"{"hash":"a"}"#)
+            .await
+            .unwrap();
+        enqueue_job(&pool, "thumbnail", r#"{"hash":"b"}"#)
+            .await
+            .unwrap();
+        // Duplicate enqueue is a no-op
+        enqueue_job(&pool, "thumbnail", r#"{"hash":"a"}"#)
+            .await
+            .unwrap();
+        assert_eq!(get_job_counts(&pool).await.unwrap().pending, 2);
+        assert_eq!(next_job_due_in(&pool).await.unwrap(), Some(0));
+
+        let claimed = claim_due_jobs(&pool, 1).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].payload, r#"{"hash":"a"}"#);
+        assert_eq!(claimed[0].attempts, 1);
+        let counts = get_job_counts(&pool).await.unwrap();
+        assert_eq!((counts.pending, counts.running), (1, 1));
+
+        complete_job(&pool, claimed[0].id).await.unwrap();
+        let counts = get_job_counts(&pool).await.unwrap();
+        assert_eq!((counts.pending, counts.running), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_job_retry_backoff_and_failure() {
+        let pool = test_pool().await;
+        enqueue_job(&pool, "thumbnail", "{}").await.unwrap();
+
+        let job = claim_due_jobs(&pool, 10).await.unwrap().remove(0);
+        fail_job(&pool, job.id, "file not ready", Some(60))
+            .await
+            .unwrap();
+
+        // Backed off: pending but not yet due
+        assert!(claim_due_jobs(&pool, 10).await.unwrap().is_empty());
+        let due_in = next_job_due_in(&pool).await.unwrap().unwrap();
+        assert!((58..=60).contains(&due_in));
+
+        sqlx::query("UPDATE jobs SET run_after = datetime('now')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let job = claim_due_jobs(&pool, 10).await.unwrap().remove(0);
+        assert_eq!(job.attempts, 2);
+
+        fail_job(&pool, job.id, "still broken", None).await.unwrap();
+        let counts = get_job_counts(&pool).await.unwrap();
+        assert_eq!((counts.pending, counts.failed), (0, 1));
+        assert_eq!(next_job_due_in(&pool).await.unwrap(), None);
+
+        // Re-enqueueing a failed job resets it
+        enqueue_job(&pool, "thumbnail", "{}").await.unwrap();
+        let job = claim_due_jobs(&pool, 10).await.unwrap().remove(0);
+        assert_eq!(job.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_requeue_running_jobs() {
+        let pool = test_pool().await;
+        enqueue_job(&pool, "thumbnail", "{}").await.unwrap();
+        claim_due_jobs(&pool, 10).await.unwrap();
+
+        assert_eq!(requeue_running_jobs(&pool).await.unwrap(), 1);
+        let counts = get_job_counts(&pool).await.unwrap();
+        assert_eq!((counts.pending, counts.running), (1, 0));
+    }
 }
@@ -0,0 +1,264 @@
+use crate::db::{
+    models::{Job, JobCounts},
+    queries, DbPool,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Notify, Semaphore};
+
+/// Job kind: render the on-disk thumbnail cache for one source file.
+pub const THUMBNAIL_JOB: &str = "thumbnail";
+
+/// Maximum number of jobs running at once.
+const MAX_PARALLEL_JOBS: usize = 2;
+
+/// Delay before the first retry; doubles with each further attempt.
+const BASE_BACKOFF_SECS: i64 = 2;
+
+/// Upper bound for the retry delay.
+const MAX_BACKOFF_SECS: i64 = 300;
+
+/// Payload of a `THUMBNAIL_JOB`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThumbnailJob {
+    /// Item to refresh in the UI once done (None for bulk regeneration).
+    pub item_id: Option<String>,
+    pub hash: String,
+    pub source: String,
+}
+
+impl ThumbnailJob {
+    /// Job for a newly recorded file item, or None if the file isn't an image.
+    pub fn for_file(item_id: &str, hash: &str, path: &str) -> Option<Self> {
+        crate::thumbnails::is_image_file(path).then(|| Self {
+            item_id: Some(item_id.to_string()),
+            hash: hash.to_string(),
+            source: path.to_string(),
+        })
+    }
+}
+
+/// Payload of the `job-progress` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobProgress {
+    #[serde(flatten)]
+    pub counts: JobCounts,
+    /// Jobs completed since app start.
+    pub completed: u64,
+}
+
+/// Shared queue state: wakes the worker and bounds parallelism.
+pub struct JobQueue {
+    notify: Notify,
+    slots: Arc<Semaphore>,
+    completed: AtomicU64,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self {
+            notify: Notify::new(),
+            slots: Arc::new(Semaphore::new(MAX_PARALLEL_JOBS)),
+            completed: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Retry delay after the given number of failed attempts (exponential, capped).
+pub fn backoff_secs(attempts: i64) -> i64 {
+    let exp = (attempts - 1).clamp(0, 16) as u32;
+    (BASE_BACKOFF_SECS * 2i64.pow(exp)).min(MAX_BACKOFF_SECS)
+}
+
+/// Persist a job and wake the worker.
+pub async fn enqueue(app: &AppHandle, kind: &str, payload: &impl Serialize) -> Result<(), String> {
+    let payload = serde_json::to_string(payload).map_err(|e| e.to_string())?;
+    let db = app.state::<DbPool>();
+    queries::enqueue_job(&db.0, kind, &payload)
+        .await
+        .map_err(|e| e.to_string())?;
+    app.state::<JobQueue>().notify.notify_one();
+    emit_progress(app).await;
+    Ok(())
+}
+
+/// Emit the current queue snapshot as a `job-progress` event.
+async fn emit_progress(app: &AppHandle) {
+    let db = app.state::<DbPool>();
+    let Ok(counts) = queries::get_job_counts(&db.0).await else {
+        return;
+    };
+    let completed = app.state::<JobQueue>().completed.load(Ordering::Relaxed);
+    let _ = app.emit("job-progress", JobProgress { counts, completed });
+}
+
+/// Execute one job. Errors are retried with backoff until `max_attempts`.
+async fn run_job(app: &AppHandle, job: &Job) -> Result<(), String> {
+    match job.kind.as_str() {
+        THUMBNAIL_JOB => {
+            let payload: ThumbnailJob =
+                serde_json::from_str(&job.payload).map_err(|e| e.to_string())?;
+            let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+            crate::commands::clipboard::write_thumbnails_from_file(
+                &app_data_dir,
+                &payload.hash,
+                &payload.source,
+            )
+            .await?;
+            if let Some(id) = payload.item_id {
+                // Notify frontend to refresh with the new thumbnail
+                crate::commands::clipboard::emit_clipboard_changed(app, &id);
+            }
+            Ok(())
+        }
+        other => Err(format!("Unknown job kind: {}", other)),
+    }
+}
+
+/// Run a claimed job and record its outcome in the queue.
+async fn process_job(app: &AppHandle, job: Job) {
+    let db = app.state::<DbPool>();
+    let result = run_job(app, &job).await;
+
+    let recorded = match &result {
+        Ok(()) => {
+            app.state::<JobQueue>()
+                .completed
+                .fetch_add(1, Ordering::Relaxed);
+            queries::complete_job(&db.0, job.id).await
+        }
+        Err(e) if job.attempts < job.max_attempts => {
+            let delay = backoff_secs(job.attempts);
+            log::info!(
+                "Job {} ({}) failed, retrying in {}s: {}",
+                job.id,
+                job.kind,
+                delay,
+                e
+            );
+            queries::fail_job(&db.0, job.id, e, Some(delay)).await
+        }
+        Err(e) => {
+            log::warn!(
+                "Job {} ({}) failed after {} attempts: {}",
+                job.id,
+                job.kind,
+                job.attempts,
+                e
+            );
+            queries::fail_job(&db.0, job.id, e, None).await
+        }
+    };
+    if let Err(e) = recorded {
+        log::warn!("Failed to record job {} result: {}", job.id, e);
+    }
+
+    emit_progress(app).await;
+}
+
+/// Start the background worker. Jobs interrupted by a previous exit are re-queued first.
+pub fn start_job_worker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let pool = app.state::<DbPool>().0.clone();
+        match queries::requeue_running_jobs(&pool).await {
+            Ok(n) if n > 0 => log::info!("Re-queued {} interrupted job(s)", n),
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to re-queue interrupted jobs: {}", e),
+        }
+
+        let queue = app.state::<JobQueue>();
+        loop {
+            let free = queue.slots.available_permits();
+            if free > 0 {
+                let jobs = match queries::claim_due_jobs(&pool, free as i64).await {
+                    Ok(jobs) => jobs,
+                    Err(e) => {
+                        log::warn!("Failed to claim jobs: {}", e);
+                        Vec::new()
+                    }
+                };
+                if !jobs.is_empty() {
+                    for job in jobs {
+                        let Ok(permit) = queue.slots.clone().acquire_owned().await else {
+                            return;
+                        };
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            process_job(&app, job).await;
+                            drop(permit);
+                            // A slot freed up — let the worker claim more
+                            app.state::<JobQueue>().notify.notify_one();
+                        });
+                    }
+                    continue;
+                }
+            }
+
+            // Sleep until a job is enqueued, a slot frees up, or a retry becomes due
+            let next_due = if free > 0 {
+                queries::next_job_due_in(&pool).await.ok().flatten()
+            } else {
+                None
+            };
+            match next_due {
+                Some(secs) => {
+                    let wait = Duration::from_secs(secs.max(1) as u64);
+                    tokio::select! {
+                        _ = queue.notify.notified() => {}
+                        _ = tokio::time::sleep(wait) => {}
+                    }
+                }
+                None => queue.notify.notified().await,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff_secs(1), 2);
+        assert_eq!(backoff_secs(2), 4);
+        assert_eq!(backoff_secs(3), 8);
+        assert_eq!(backoff_secs(8), 256);
+        assert_eq!(backoff_secs(9), MAX_BACKOFF_SECS);
+        assert_eq!(backoff_secs(100), MAX_BACKOFF_SECS);
+        // Defensive: never below the base delay
+        assert_eq!(backoff_secs(0), 2);
+    }
+
+    #[test]
+    fn test_thumbnail_job_payload_roundtrip() {
+        let job = ThumbnailJob {
+            item_id: Some("abc".into()),
+            hash: "h".into(),
+            source: "/tmp/a.png".into(),
+        };
+        let json = serde_json::to_string(&job).unwrap();
+        let back: ThumbnailJob = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.item_id.as_deref(), Some("abc"));
+        assert_eq!(back.source, "/tmp/a.png");
+    }
+
+    #[test]
+    fn test_copied_svg_file_queues_thumbnail_job() {
+        let job = ThumbnailJob::for_file("abc", "h", "/home/me/logo.svg").unwrap();
+        assert_eq!(job.item_id.as_deref(), Some("abc"));
+        assert_eq!(job.hash, "h");
+        assert_eq!(job.source, "/home/me/logo.svg");
+
+        assert!(ThumbnailJob::for_file("abc", "h", "/home/me/notes.txt").is_none());
+    }
+}
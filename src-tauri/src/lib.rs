@@ -1,10 +1,17 @@
 mod clipboard;
 mod commands;
 mod db;
+mod jobs;
+mod lock;
+mod maintenance;
 mod platform;
+mod thumbnails;
 
+use commands::capture as capture_cmd;
 use commands::clipboard as clip_cmd;
-use db::models::ContentType;
+use commands::lock as lock_cmd;
+use commands::maintenance as maintenance_cmd;
+use db::models::{ContentType, SelectionOrigin};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Flag to skip the next clipboard change event (set before self-initiated writes).
@@ -51,11 +58,19 @@ pub fn run() {
         .plugin(tauri_plugin_process::init());
 
     builder
+        // Thumbnails are served straight from the disk cache to the webview
+        .register_asynchronous_uri_scheme_protocol("thumb", |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                responder.respond(clip_cmd::thumbnail_protocol_response(&app, &request).await);
+            });
+        })
         .invoke_handler(tauri::generate_handler![
             clip_cmd::get_clipboard_items,
             clip_cmd::search_clipboard_items,
-            clip_cmd::get_thumbnail,
+            clip_cmd::regenerate_thumbnails,
             clip_cmd::get_item_detail,
+            clip_cmd::get_item_variants,
             clip_cmd::delete_clipboard_item,
             clip_cmd::paste_clipboard_item,
             clip_cmd::paste_as_plain_text,
@@ -65,7 +80,19 @@ pub fn run() {
             clip_cmd::get_setting,
             clip_cmd::set_setting,
             clip_cmd::clear_history,
+            clip_cmd::clear_history_filtered,
             clip_cmd::run_retention_cleanup,
+            clip_cmd::preview_retention,
+            clip_cmd::get_retention_rules,
+            clip_cmd::save_retention_rule,
+            clip_cmd::delete_retention_rule,
+            clip_cmd::get_app_capture_rules,
+            clip_cmd::save_app_capture_rule,
+            clip_cmd::delete_app_capture_rule,
+            clip_cmd::get_capture_ignore_rules,
+            clip_cmd::save_capture_ignore_rule,
+            clip_cmd::delete_capture_ignore_rule,
+            clip_cmd::test_capture_rules,
             clip_cmd::unregister_shortcut,
             clip_cmd::register_shortcut,
             clip_cmd::open_url,
@@ -80,6 +107,15 @@ pub fn run() {
             clip_cmd::get_storage_size,
             clip_cmd::set_tray_visible,
             clip_cmd::sync_system_theme,
+            lock_cmd::get_app_lock_status,
+            lock_cmd::unlock,
+            lock_cmd::lock_app,
+            lock_cmd::set_app_lock,
+            maintenance_cmd::get_maintenance_status,
+            maintenance_cmd::run_maintenance,
+            capture_cmd::get_capture_status,
+            capture_cmd::pause_capture,
+            capture_cmd::resume_capture,
         ])
         .setup(|app| {
             // Hide dock icon (tao overrides LSUIElement at startup, so must set programmatically)
@@ -123,6 +159,22 @@ pub fn run() {
                 std::sync::atomic::AtomicBool::new(false),
             ));
 
+            // Background job queue (thumbnails and other derived data)
+            app.manage(jobs::JobQueue::new());
+            app.manage(maintenance::Maintenance::new());
+            jobs::start_job_worker(app.handle().clone());
+
+            // Initialize app lock from settings (locked on startup if a PIN is set)
+            let app_lock = {
+                let pool = app.state::<db::DbPool>();
+                tauri::async_runtime::block_on(lock_cmd::load_app_lock(&pool))
+            };
+            app.manage(app_lock);
+            start_lock_idle_watcher(app.handle().clone());
+
+            // Capture pause (incognito) lasts until resumed or the app quits
+            app.manage(clipboard::pause::CapturePause::default());
+
             // Initialize NSPanel store (must be before init_platform which stores panels)
             #[cfg(target_os = "macos")]
             app.manage(platform::nspanel::PanelStore::new());
@@ -153,58 +205,47 @@ pub fn run() {
             let app_handle = app.handle().clone();
             start_clipboard_monitor(app_handle);
 
-            // Cleanup orphan image files left from previous sessions (best-effort)
-            let app_handle_gc = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                commands::clipboard::cleanup_orphan_images(&app_handle_gc).await;
-            });
-
-            // Run retention cleanup on startup (FR-018)
-            let app_handle_ret = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                let pool = match app_handle_ret.try_state::<db::DbPool>() {
-                    Some(p) => p,
-                    None => return,
+            // Linux: watch the PRIMARY selection (opt-in capture and mirroring)
+            #[cfg(target_os = "linux")]
+            {
+                let primary = {
+                    let pool = app.state::<db::DbPool>();
+                    tauri::async_runtime::block_on(clipboard::primary::load_primary_selection(
+                        &pool,
+                    ))
                 };
-                let policy = db::queries::get_setting(&pool.0, "retention_policy")
-                    .await
-                    .ok()
-                    .flatten()
-                    .unwrap_or_else(|| "unlimited".to_string());
-
-                if policy == "unlimited" {
-                    return;
-                }
-
-                let days = db::queries::get_setting(&pool.0, "retention_days")
-                    .await
+                app.manage(primary);
+                clipboard::primary::start_primary_watcher(app.handle().clone());
+
+                let owner_enabled = {
+                    let pool = app.state::<db::DbPool>();
+                    tauri::async_runtime::block_on(db::queries::get_setting(
+                        &pool.0,
+                        clipboard::owner::CLIPBOARD_OWNER_KEY,
+                    ))
                     .ok()
                     .flatten()
-                    .and_then(|v| v.parse::<i64>().ok())
-                    .unwrap_or(30);
-                let count = db::queries::get_setting(&pool.0, "retention_count")
-                    .await
-                    .ok()
-                    .flatten()
-                    .and_then(|v| v.parse::<i64>().ok())
-                    .unwrap_or(1000);
+                    .is_some_and(|v| v == "true")
+                };
+                app.manage(clipboard::owner::ClipboardOwner::new(owner_enabled));
+            }
 
-                // Collect image paths before deleting rows
-                let image_paths =
-                    db::queries::get_retention_overflow_image_paths(&pool.0, &policy, days, count)
-                        .await
-                        .unwrap_or_default();
+            // Sensitive items may have expired while the app was closed; later
+            // expiries are re-armed by the sweep itself
+            let app_handle_expiry = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                clip_cmd::sweep_expired_items(&app_handle_expiry).await;
+            });
 
-                match db::queries::cleanup_by_retention(&pool.0, &policy, days, count).await {
-                    Ok(deleted) if deleted > 0 => {
-                        log::info!("Startup retention cleanup: removed {} items", deleted);
-                        for path in image_paths {
-                            let _ = tokio::fs::remove_file(&path).await;
-                        }
-                    }
-                    Err(e) => log::warn!("Startup retention cleanup failed: {}", e),
-                    _ => {}
-                }
+            // Move legacy originals and DB thumbnail blobs to disk, then start the
+            // periodic maintenance (retention, orphan image/thumbnail GC, WAL checkpoint)
+            let app_handle_gc = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                commands::clipboard::migrate_legacy_images(&app_handle_gc).await;
+                commands::clipboard::migrate_thumbnail_blobs(&app_handle_gc).await;
+                commands::clipboard::backfill_image_metadata(&app_handle_gc).await;
+                commands::clipboard::backfill_perceptual_hashes(&app_handle_gc).await;
+                maintenance::start_maintenance_scheduler(app_handle_gc);
             });
 
             Ok(())
@@ -788,6 +829,20 @@ fn setup_blur_hide(app: &tauri::AppHandle) {
     }
 }
 
+/// Periodically re-engage the app lock after the idle timeout and notify the frontend.
+fn start_lock_idle_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+        loop {
+            interval.tick().await;
+            if app.state::<lock::AppLock>().poll_idle() {
+                log::info!("App lock re-engaged after idle timeout");
+                let _ = app.emit("app-locked", ());
+            }
+        }
+    });
+}
+
 fn start_clipboard_monitor(app: tauri::AppHandle) {
     let app_clone = app.clone();
 
@@ -828,6 +883,36 @@ async fn handle_clipboard_event(app: &tauri::AppHandle) {
         return;
     }
 
+    if app.state::<clipboard::pause::CapturePause>().is_paused() {
+        log::info!("Clipboard capture paused, skipping change");
+        return;
+    }
+
+    // Resolve the source app before reading, while it still has focus
+    let source = platform::source_app_provider()
+        .frontmost_app()
+        .unwrap_or_default();
+    if !clip_cmd::source_app_allowed(app, &source).await {
+        log::info!("Skipping clipboard change from excluded app {}", source.id);
+        return;
+    }
+
+    // Secrets marked by a password manager are skipped or kept as expiring masked items
+    let concealed = platform::platform_clipboard_concealed();
+    if concealed {
+        let pool = app.state::<db::DbPool>();
+        let mode = clipboard::ConcealedMode::from_setting(
+            db::queries::get_setting(&pool.0, clipboard::CONCEALED_MODE_KEY)
+                .await
+                .unwrap_or(None)
+                .as_deref(),
+        );
+        if mode == clipboard::ConcealedMode::Skip {
+            log::info!("Skipping concealed clipboard content");
+            return;
+        }
+    }
+
     // Determine content type and read clipboard
     let (content_type, content, plain_text, rich_content, file_path, file_name) =
         match extract_clipboard_content(app).await {
@@ -837,6 +922,10 @@ async fn handle_clipboard_event(app: &tauri::AppHandle) {
 
     log::info!("Clipboard changed: type={}", content_type.as_str());
 
+    // Mirrored into PRIMARY only once it's recorded, so skipped copies stay out
+    #[cfg(target_os = "linux")]
+    let mirror_text = plain_text.clone().filter(|_| content_type.is_text());
+
     // Process and store
     let result = clip_cmd::process_clipboard_change(
         app,
@@ -846,20 +935,49 @@ async fn handle_clipboard_event(app: &tauri::AppHandle) {
         rich_content,
         file_path,
         file_name,
-        String::new(), // source_app - TODO: M1-8
-        String::new(), // source_app_name - TODO: M1-8
+        source.id,
+        source.name,
+        SelectionOrigin::Clipboard,
+        concealed,
     )
     .await;
 
     match result {
-        Ok(Some(id)) => {
-            let _ = app.emit("clipboard-changed", serde_json::json!({ "id": id }));
+        Ok(Some(stored)) => {
+            clip_cmd::emit_clipboard_changed(app, &stored.id);
+            // Secrets, concealed or detected, never spread into PRIMARY or outlive their source
+            #[cfg(target_os = "linux")]
+            if !stored.sensitive {
+                if let Some(text) = mirror_text {
+                    app.state::<clipboard::primary::PrimarySelection>()
+                        .mirror_from_clipboard(&text);
+                }
+                take_clipboard_ownership(app).await;
+            }
         }
         Ok(None) => {}
         Err(e) => log::error!("Failed to process clipboard: {}", e),
     }
 }
 
+/// Linux: serve the stored content ourselves so it survives the source app exiting.
+#[cfg(target_os = "linux")]
+async fn take_clipboard_ownership(app: &tauri::AppHandle) {
+    let owner = app.state::<clipboard::owner::ClipboardOwner>();
+    if !owner.enabled() {
+        return;
+    }
+    let max_size_mb = match app.try_state::<db::DbPool>() {
+        Some(pool) => db::queries::get_setting(&pool.0, "max_item_size_mb")
+            .await
+            .unwrap_or(None)
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(clipboard::DEFAULT_MAX_ITEM_SIZE_MB),
+        None => clipboard::DEFAULT_MAX_ITEM_SIZE_MB,
+    };
+    owner.take_over(max_size_mb * 1024 * 1024);
+}
+
 async fn extract_clipboard_content(
     app: &tauri::AppHandle,
 ) -> Option<(
@@ -917,6 +1035,14 @@ async fn extract_clipboard_content(
 
     // Try image
     if let Ok(true) = tauri_plugin_clipboard_x::has_image().await {
+        // Prefer the source's own encoding (GIF/JPEG/WebP) over the plugin's PNG re-encode
+        if let Some(img_data) = platform::platform_read_original_image() {
+            if clipboard::exceeds_size_limit(img_data.len(), max_size_mb) {
+                log::info!("Skipping large image: {}B", img_data.len());
+                return None;
+            }
+            return Some((ContentType::Image, img_data, None, None, None, None));
+        }
         if let Ok(img_result) = tauri_plugin_clipboard_x::read_image(app.clone(), None).await {
             // Read the saved image file
             if let Ok(img_data) = tokio::fs::read(&img_result.path).await {
@@ -936,7 +1062,13 @@ async fn extract_clipboard_content(
                 .await
                 .unwrap_or_default();
             let html_bytes = html.as_bytes().to_vec();
-            let content_bytes = plain.as_bytes().to_vec();
+            // Hash the plain text so rich and plain copies of the same text dedup
+            // together; fall back to the HTML when there is no plain flavor
+            let content_bytes = if plain.is_empty() {
+                html_bytes.clone()
+            } else {
+                plain.as_bytes().to_vec()
+            };
             return Some((
                 ContentType::RichText,
                 content_bytes,
@@ -951,34 +1083,10 @@ async fn extract_clipboard_content(
     // Try text-based content: link (pure URL) or plain text
     if let Ok(true) = tauri_plugin_clipboard_x::has_text().await {
         if let Ok(text) = tauri_plugin_clipboard_x::read_text().await {
-            let trimmed = text.trim();
-            if !trimmed.is_empty() {
-                // Check if it's a pure URL
-                if let Ok(parsed) = url::Url::parse(trimmed) {
-                    if matches!(parsed.scheme(), "http" | "https") {
-                        let content = trimmed.as_bytes().to_vec();
-                        return Some((
-                            ContentType::Link,
-                            content,
-                            Some(trimmed.to_string()),
-                            None,
-                            None,
-                            None,
-                        ));
-                    }
-                }
-            }
-            // Fall through to plain text
             if !text.is_empty() {
+                let (content_type, text) = clipboard::classify_text(text);
                 let content = text.as_bytes().to_vec();
-                return Some((
-                    ContentType::PlainText,
-                    content,
-                    Some(text),
-                    None,
-                    None,
-                    None,
-                ));
+                return Some((content_type, content, Some(text), None, None, None));
             }
         }
     }
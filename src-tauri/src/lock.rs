@@ -0,0 +1,238 @@
+use argon2::Argon2;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Settings key holding the salted PIN/passphrase hash (`argon2id$salt$hash`, hex).
+pub const LOCK_HASH_KEY: &str = "app_lock_hash";
+
+/// Settings key for the idle timeout (minutes) after which the app re-locks.
+pub const LOCK_IDLE_KEY: &str = "app_lock_idle_minutes";
+
+/// Default idle timeout before re-locking.
+pub const DEFAULT_IDLE_MINUTES: u64 = 5;
+
+/// Minimum accepted PIN/passphrase length.
+pub const MIN_SECRET_LEN: usize = 4;
+
+/// Scheme tag of hashes written by `hash_secret`.
+const ARGON2_PREFIX: &str = "argon2id$";
+
+/// Derive the Argon2id hash of `secret` with the given salt (hex string),
+/// using the crate's recommended memory and time costs. None if the salt is
+/// too short for Argon2 (only possible for a corrupted stored value).
+fn derive(salt: &str, secret: &str) -> Option<String> {
+    let mut out = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret.as_bytes(), salt.as_bytes(), &mut out)
+        .ok()?;
+    Some(out.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Hash a PIN/passphrase with a fresh random salt.
+/// Returns `argon2id$salt$hash`, suitable for storing in `settings`.
+pub fn hash_secret(secret: &str) -> String {
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    let hash = derive(&salt, secret).expect("a 32-char salt is valid for Argon2");
+    format!("{}{}${}", ARGON2_PREFIX, salt, hash)
+}
+
+/// Verify a PIN/passphrase against a stored `argon2id$salt$hash` value.
+pub fn verify_secret(secret: &str, stored: &str) -> bool {
+    let Some((salt, expected)) = stored
+        .strip_prefix(ARGON2_PREFIX)
+        .and_then(|rest| rest.split_once('$'))
+    else {
+        return false;
+    };
+    let Some(actual) = derive(salt, secret) else {
+        return false;
+    };
+    // Constant-time comparison so timing doesn't leak matching prefixes
+    actual.len() == expected.len()
+        && actual
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+struct LockInner {
+    enabled: bool,
+    /// Time of the last successful unlock or gated access (None = locked).
+    last_activity: Option<Instant>,
+    idle: Duration,
+}
+
+impl LockInner {
+    /// Re-lock if the idle timeout has elapsed. Returns true if it just re-locked.
+    fn expire_if_idle(&mut self, now: Instant) -> bool {
+        match self.last_activity {
+            Some(last) if now.saturating_duration_since(last) >= self.idle => {
+                self.last_activity = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn is_locked_at(&mut self, now: Instant) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        self.expire_if_idle(now);
+        self.last_activity.is_none()
+    }
+}
+
+/// Shared state for the app lock: whether a PIN is set and whether it's currently unlocked.
+pub struct AppLock(Mutex<LockInner>);
+
+impl AppLock {
+    pub fn new(enabled: bool, idle_minutes: u64) -> Self {
+        Self(Mutex::new(LockInner {
+            enabled,
+            last_activity: None,
+            idle: Duration::from_secs(idle_minutes.max(1) * 60),
+        }))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.lock().unwrap().enabled
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.0.lock().unwrap().is_locked_at(Instant::now())
+    }
+
+    /// Gate for history-reading commands: errors while locked, otherwise
+    /// records activity so the idle timer restarts.
+    pub fn ensure_unlocked(&self) -> Result<(), String> {
+        let mut inner = self.0.lock().unwrap();
+        let now = Instant::now();
+        if inner.is_locked_at(now) {
+            return Err("App is locked".to_string());
+        }
+        if inner.enabled {
+            inner.last_activity = Some(now);
+        }
+        Ok(())
+    }
+
+    pub fn unlock(&self) {
+        self.0.lock().unwrap().last_activity = Some(Instant::now());
+    }
+
+    pub fn lock(&self) {
+        self.0.lock().unwrap().last_activity = None;
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        let mut inner = self.0.lock().unwrap();
+        inner.enabled = enabled;
+        if !enabled {
+            inner.last_activity = None;
+        }
+    }
+
+    pub fn idle_minutes(&self) -> u64 {
+        self.0.lock().unwrap().idle.as_secs() / 60
+    }
+
+    pub fn set_idle_minutes(&self, minutes: u64) {
+        self.0.lock().unwrap().idle = Duration::from_secs(minutes.max(1) * 60);
+    }
+
+    /// Called periodically by the idle watcher. Returns true if the lock just
+    /// re-engaged, so the caller can notify the frontend.
+    pub fn poll_idle(&self) -> bool {
+        let mut inner = self.0.lock().unwrap();
+        inner.enabled && inner.expire_if_idle(Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_secret() {
+        let stored = hash_secret("1234");
+        assert!(verify_secret("1234", &stored));
+        assert!(!verify_secret("12345", &stored));
+        assert!(!verify_secret("", &stored));
+    }
+
+    #[test]
+    fn test_hash_secret_is_salted() {
+        let a = hash_secret("correct horse");
+        let b = hash_secret("correct horse");
+        assert_ne!(a, b);
+        assert!(verify_secret("correct horse", &a));
+        assert!(verify_secret("correct horse", &b));
+    }
+
+    #[test]
+    fn test_verify_secret_malformed_hash() {
+        assert!(!verify_secret("1234", ""));
+        assert!(!verify_secret("1234", "no-separator"));
+        assert!(!verify_secret("1234", "salt$short"));
+        assert!(!verify_secret("1234", "argon2id$no-separator"));
+        assert!(!verify_secret("1234", "argon2id$salt$short"));
+    }
+
+    #[test]
+    fn test_disabled_lock_never_gates() {
+        let lock = AppLock::new(false, 5);
+        assert!(!lock.is_locked());
+        assert!(lock.ensure_unlocked().is_ok());
+    }
+
+    #[test]
+    fn test_enabled_lock_starts_locked() {
+        let lock = AppLock::new(true, 5);
+        assert!(lock.is_locked());
+        assert!(lock.ensure_unlocked().is_err());
+
+        lock.unlock();
+        assert!(!lock.is_locked());
+        assert!(lock.ensure_unlocked().is_ok());
+
+        lock.lock();
+        assert!(lock.is_locked());
+    }
+
+    #[test]
+    fn test_relocks_after_idle_timeout() {
+        let lock = AppLock::new(true, 5);
+        lock.unlock();
+
+        let mut inner = lock.0.lock().unwrap();
+        let start = inner.last_activity.unwrap();
+        assert!(!inner.is_locked_at(start + Duration::from_secs(4 * 60)));
+        assert!(inner.is_locked_at(start + Duration::from_secs(5 * 60)));
+        assert!(inner.last_activity.is_none());
+    }
+
+    #[test]
+    fn test_poll_idle_reports_transition_once() {
+        let lock = AppLock::new(true, 1);
+        lock.unlock();
+        // A zero timeout expires at once, without back-dating Instant (which can underflow)
+        lock.0.lock().unwrap().idle = Duration::ZERO;
+
+        assert!(lock.poll_idle());
+        assert!(!lock.poll_idle());
+        assert!(lock.is_locked());
+    }
+
+    #[test]
+    fn test_disabling_clears_unlock_state() {
+        let lock = AppLock::new(true, 5);
+        lock.unlock();
+        lock.set_enabled(false);
+        assert!(!lock.is_locked());
+
+        lock.set_enabled(true);
+        assert!(lock.is_locked());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::db::{queries, DbPool};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+/// Settings key for the hours between maintenance runs.
+pub const INTERVAL_KEY: &str = "maintenance_interval_hours";
+
+/// Settings key enabling `VACUUM` at the end of each run (`true` | `false`).
+pub const VACUUM_KEY: &str = "maintenance_vacuum";
+
+/// Default hours between maintenance runs.
+pub const DEFAULT_INTERVAL_HOURS: u64 = 6;
+
+/// Longest accepted interval (one week).
+const MAX_INTERVAL_HOURS: u64 = 168;
+
+/// Outcome of the last maintenance run, returned by `get_maintenance_status`
+/// and emitted as `maintenance-status`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MaintenanceStatus {
+    pub running: bool,
+    /// UTC time the last run finished (`YYYY-MM-DD HH:MM:SS`).
+    pub last_run_at: Option<String>,
+    pub last_duration_ms: u64,
+    pub retention_deleted: i64,
+    pub orphan_images_removed: u32,
+    pub orphan_thumbnails_removed: u32,
+    pub vacuumed: bool,
+    /// Failed steps of the last run; the remaining steps still ran.
+    pub errors: Vec<String>,
+    /// UTC time of the next scheduled run.
+    pub next_run_at: Option<String>,
+}
+
+/// Scheduler state: the last status and a handle to trigger an early run.
+pub struct Maintenance {
+    status: Mutex<MaintenanceStatus>,
+    wake: Notify,
+}
+
+impl Maintenance {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(MaintenanceStatus::default()),
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Run maintenance now instead of waiting for the interval. No-op while a run is in progress.
+    pub fn trigger(&self) {
+        if !self.status.lock().unwrap().running {
+            self.wake.notify_one();
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut MaintenanceStatus)) -> MaintenanceStatus {
+        let mut status = self.status.lock().unwrap();
+        f(&mut status);
+        status.clone()
+    }
+}
+
+impl Default for Maintenance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse the interval setting (hours), falling back to the default and clamping to 1h..1 week.
+pub fn interval_from_setting(value: Option<&str>) -> Duration {
+    let hours = value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_HOURS)
+        .clamp(1, MAX_INTERVAL_HOURS);
+    Duration::from_secs(hours * 3600)
+}
+
+fn utc_timestamp(offset: Duration) -> String {
+    let at = chrono::Utc::now() + chrono::Duration::from_std(offset).unwrap_or_default();
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// One pass: retention, orphan image/thumbnail GC, WAL checkpoint and optional VACUUM.
+/// Each step is best-effort; failures are collected into the status. The `size` policy
+/// measures logical usage rather than file sizes, so repeating it on every pass without
+/// VACUUM only deletes what newly went over budget.
+async fn run(app: &AppHandle) -> MaintenanceStatus {
+    let state = app.state::<Maintenance>();
+    let status = state.update(|s| s.running = true);
+    let _ = app.emit("maintenance-status", status);
+
+    let started = Instant::now();
+    let pool = app.state::<DbPool>().0.clone();
+    let mut errors = Vec::new();
+
+    let retention_deleted = crate::commands::clipboard::apply_retention_policy(app)
+        .await
+        .unwrap_or_else(|e| {
+            errors.push(format!("retention: {}", e));
+            0
+        });
+    let orphan_images_removed = crate::commands::clipboard::cleanup_orphan_images(app).await;
+    let orphan_thumbnails_removed =
+        crate::commands::clipboard::cleanup_orphan_thumbnails(app).await;
+
+    if let Err(e) = queries::checkpoint_wal(&pool).await {
+        errors.push(format!("wal checkpoint: {}", e));
+    }
+
+    let vacuum = queries::get_setting(&pool, VACUUM_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    let vacuumed = vacuum
+        && match queries::vacuum(&pool).await {
+            Ok(()) => true,
+            Err(e) => {
+                errors.push(format!("vacuum: {}", e));
+                false
+            }
+        };
+
+    for e in &errors {
+        log::warn!("Maintenance: {}", e);
+    }
+    log::info!(
+        "Maintenance finished in {:?}: {} expired item(s), {} orphan image(s), {} orphan thumbnail(s)",
+        started.elapsed(),
+        retention_deleted,
+        orphan_images_removed,
+        orphan_thumbnails_removed
+    );
+
+    state.update(|s| {
+        *s = MaintenanceStatus {
+            running: false,
+            last_run_at: Some(utc_timestamp(Duration::ZERO)),
+            last_duration_ms: started.elapsed().as_millis() as u64,
+            retention_deleted,
+            orphan_images_removed,
+            orphan_thumbnails_removed,
+            vacuumed,
+            errors,
+            next_run_at: None,
+        }
+    })
+}
+
+/// Start the background scheduler: runs immediately, then every configured interval
+/// or when triggered through `Maintenance::trigger`.
+pub fn start_maintenance_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            run(&app).await;
+
+            let interval = interval_from_setting(
+                queries::get_setting(&app.state::<DbPool>().0, INTERVAL_KEY)
+                    .await
+                    .ok()
+                    .flatten()
+                    .as_deref(),
+            );
+            let state = app.state::<Maintenance>();
+            let status = state.update(|s| s.next_run_at = Some(utc_timestamp(interval)));
+            let _ = app.emit("maintenance-status", status);
+
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = state.wake.notified() => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_from_setting() {
+        assert_eq!(interval_from_setting(None), Duration::from_secs(6 * 3600));
+        assert_eq!(
+            interval_from_setting(Some("24")),
+            Duration::from_secs(24 * 3600)
+        );
+        assert_eq!(interval_from_setting(Some("0")), Duration::from_secs(3600));
+        assert_eq!(
+            interval_from_setting(Some("10000")),
+            Duration::from_secs(168 * 3600)
+        );
+        assert_eq!(
+            interval_from_setting(Some("soon")),
+            Duration::from_secs(6 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_trigger_skipped_while_running() {
+        let m = Maintenance::new();
+        m.update(|s| s.running = true);
+        m.trigger();
+        m.update(|s| s.running = false);
+
+        // No stored permit from the skipped trigger
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let woke = rt.block_on(async {
+            tokio::time::timeout(Duration::from_millis(20), m.wake.notified())
+                .await
+                .is_ok()
+        });
+        assert!(!woke);
+
+        m.trigger();
+        let woke = rt.block_on(async {
+            tokio::time::timeout(Duration::from_millis(20), m.wake.notified())
+                .await
+                .is_ok()
+        });
+        assert!(woke);
+    }
+}
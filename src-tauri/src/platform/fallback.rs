@@ -1,6 +1,8 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::Manager;
 
+use super::{SourceApp, SourceAppProvider};
+
 /// Set when preview is being shown (regular window.show() steals focus on Windows).
 /// Prevents main's blur handler from hiding while preview opens.
 static PREVIEW_FOCUS_GUARD: AtomicBool = AtomicBool::new(false);
@@ -99,6 +101,15 @@ pub fn is_recopy_foreground() -> bool {
     false
 }
 
+/// No foreground-app lookup on this platform.
+pub struct NativeSourceApps;
+
+impl SourceAppProvider for NativeSourceApps {
+    fn frontmost_app(&self) -> Option<SourceApp> {
+        None
+    }
+}
+
 pub fn init_hud_panel(_app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
@@ -114,3 +125,14 @@ pub fn platform_hide_hud(app: &tauri::AppHandle) {
         let _ = window.hide();
     }
 }
+
+/// No platform clipboard access here: images are read through the clipboard plugin,
+/// which always yields PNG.
+pub fn platform_read_original_image() -> Option<Vec<u8>> {
+    None
+}
+
+/// No clipboard type inspection on this platform.
+pub fn platform_clipboard_concealed() -> bool {
+    false
+}
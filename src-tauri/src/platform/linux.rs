@@ -0,0 +1,1012 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::Manager;
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt as _, CreateWindowAux, EventMask,
+    PropMode, Property, SelectionNotifyEvent, SelectionRequestEvent, Window, WindowClass,
+    SELECTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+use super::{detect_session, Session, SourceApp, SourceAppProvider};
+
+/// App handle for looking up monitor scale factors.
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// Set when preview is being shown (window.show() steals focus).
+/// Prevents main's blur handler from hiding while preview opens.
+static PREVIEW_FOCUS_GUARD: AtomicBool = AtomicBool::new(false);
+
+/// Set before programmatically hiding the preview window.
+/// Prevents the preview blur handler from triggering close-all on explicit close.
+static PREVIEW_PROGRAMMATIC_HIDE: AtomicBool = AtomicBool::new(false);
+
+pub fn init_platform(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    APP_HANDLE.set(app.handle().clone()).ok();
+    Ok(())
+}
+
+pub fn platform_show_window(app: &tauri::AppHandle, _panel_position: &str) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+pub fn platform_hide_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+}
+
+pub fn platform_is_visible(app: &tauri::AppHandle) -> bool {
+    app.get_webview_window("main")
+        .map(|w| w.is_visible().unwrap_or(false))
+        .unwrap_or(false)
+}
+
+pub fn init_preview_panel(_app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    Ok(())
+}
+
+/// Show the preview window.
+/// Sets PREVIEW_FOCUS_GUARD before show so main's blur handler skips hiding,
+/// then returns focus to main so Space/Escape are still handled there.
+pub fn platform_show_preview(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("preview") {
+        PREVIEW_FOCUS_GUARD.store(true, Ordering::SeqCst);
+        let _ = window.show();
+        if let Some(main) = app.get_webview_window("main") {
+            let _ = main.set_focus();
+        }
+    }
+}
+
+/// Hide the preview window.
+/// Only sets PREVIEW_PROGRAMMATIC_HIDE when we actually have a window to hide.
+pub fn platform_hide_preview(app: &tauri::AppHandle) {
+    PREVIEW_FOCUS_GUARD.store(false, Ordering::SeqCst);
+    if let Some(window) = app.get_webview_window("preview") {
+        PREVIEW_PROGRAMMATIC_HIDE.store(true, Ordering::SeqCst);
+        let _ = window.hide();
+    }
+}
+
+/// True if preview is currently opening and has stolen focus from main.
+pub fn is_preview_focus_guard() -> bool {
+    PREVIEW_FOCUS_GUARD.load(Ordering::SeqCst)
+}
+
+/// Clear the focus guard once preview has fully received focus.
+pub fn set_preview_focus_guard(val: bool) {
+    PREVIEW_FOCUS_GUARD.store(val, Ordering::SeqCst);
+}
+
+/// Consume the programmatic-hide flag: returns true (and resets to false) if an explicit hide is in flight.
+pub fn take_preview_programmatic_hide() -> bool {
+    PREVIEW_PROGRAMMATIC_HIDE.swap(false, Ordering::SeqCst)
+}
+
+/// Bounds of the monitor under the cursor, minus docks and panels on its left, right
+/// and bottom edges. The top panel is reported by `platform_menu_bar_height` instead,
+/// matching the macOS menu bar. Returns (x, y, width, height) in logical coordinates.
+/// Needs X11 (or XWayland with the pointer over an X window); returns None otherwise.
+pub fn platform_cursor_monitor() -> Option<(f64, f64, f64, f64)> {
+    let geometry = x11_cursor_geometry()?;
+    let scale = monitor_scale(geometry.monitor);
+    let (m, i) = (geometry.monitor, geometry.insets);
+    Some((
+        (m.x + i.left) as f64 / scale,
+        m.y as f64 / scale,
+        (m.w - i.left - i.right).max(1) as f64 / scale,
+        (m.h - i.bottom).max(1) as f64 / scale,
+    ))
+}
+
+/// Height of the top panel (GNOME top bar, KDE/Xfce top panels) on the cursor's monitor,
+/// in logical pixels. 0 when there is none or X11 is unavailable.
+pub fn platform_menu_bar_height() -> f64 {
+    x11_cursor_geometry()
+        .map(|g| g.insets.top as f64 / monitor_scale(g.monitor))
+        .unwrap_or(0.0)
+}
+
+pub fn platform_resign_before_paste(app: &tauri::AppHandle) {
+    platform_hide_window(app);
+}
+
+pub fn on_window_focused() {}
+
+/// Check if the active X11 window belongs to the Recopy process.
+#[allow(dead_code)]
+pub fn is_recopy_foreground() -> bool {
+    let Some((conn, root)) = x11_connect() else {
+        return false;
+    };
+    x11_active_window(&conn, root)
+        .and_then(|window| {
+            let pid_atom = x11_atom(&conn, "_NET_WM_PID")?;
+            x11_cardinals(&conn, window, pid_atom, AtomEnum::CARDINAL)?
+                .first()
+                .copied()
+        })
+        .is_some_and(|pid| pid == std::process::id())
+}
+
+/// Source apps from the active X11 window: `_NET_WM_PID` resolved through
+/// `/proc/<pid>/exe`, named by its `WM_CLASS` class.
+pub struct NativeSourceApps;
+
+impl SourceAppProvider for NativeSourceApps {
+    fn frontmost_app(&self) -> Option<SourceApp> {
+        let (conn, root) = x11_connect()?;
+        let window = x11_active_window(&conn, root)?;
+        let pid_atom = x11_atom(&conn, "_NET_WM_PID")?;
+        let pid = *x11_cardinals(&conn, window, pid_atom, AtomEnum::CARDINAL)?.first()?;
+        let exe = std::fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+        let class = conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+            .ok()
+            .and_then(|c| c.reply().ok())
+            .and_then(|r| wm_class_name(&r.value));
+        Some(SourceApp::from_executable(
+            &exe.to_string_lossy(),
+            class.as_deref(),
+        ))
+    }
+}
+
+/// Class name (the second string) of a raw `WM_CLASS` value (`instance\0class\0`).
+fn wm_class_name(raw: &[u8]) -> Option<String> {
+    raw.split(|&b| b == 0)
+        .filter(|s| !s.is_empty())
+        .nth(1)
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+}
+
+pub fn init_hud_panel(_app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    Ok(())
+}
+
+pub fn platform_show_hud(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("hud") {
+        let _ = window.show();
+    }
+}
+
+pub fn platform_hide_hud(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("hud") {
+        let _ = window.hide();
+    }
+}
+
+/// MIME targets for image encodings that re-encoding to PNG would degrade.
+/// Checked in order when capturing; GIF first so animations survive.
+const ORIGINAL_IMAGE_TYPES: &[&str] = &["image/gif", "image/jpeg", "image/webp"];
+
+/// How long the CLIPBOARD owner gets to hand over an original image.
+const ORIGINAL_IMAGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Read the original encoded image bytes if the CLIPBOARD owner offers GIF/JPEG/WebP.
+/// Returns None for PNG-only content, which the clipboard plugin reads losslessly.
+pub fn platform_read_original_image() -> Option<Vec<u8>> {
+    match detect_session() {
+        Session::Wayland => wayland_read_original_image(),
+        _ => x11_read_original_image(),
+    }
+}
+
+fn wayland_read_original_image() -> Option<Vec<u8>> {
+    use std::io::Read;
+    use wl_clipboard_rs::paste::{
+        get_contents, get_mime_types_ordered, ClipboardType, MimeType, Seat,
+    };
+
+    let offered = get_mime_types_ordered(ClipboardType::Regular, Seat::Unspecified).ok()?;
+    let mime = ORIGINAL_IMAGE_TYPES
+        .iter()
+        .find(|m| offered.iter().any(|o| o == *m))?;
+    let (mut pipe, _) = get_contents(
+        ClipboardType::Regular,
+        Seat::Unspecified,
+        MimeType::Specific(mime),
+    )
+    .ok()?;
+    let mut data = Vec::new();
+    pipe.read_to_end(&mut data).ok()?;
+    Some(data).filter(|d| !d.is_empty())
+}
+
+/// Write a stored original image to CLIPBOARD under its own MIME type plus
+/// image/png for apps that only take PNG. Served in the background until another
+/// client takes the clipboard.
+pub fn platform_write_image_to_clipboard(path: &str, mime: &str) -> Result<(), String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read image file: {}", e))?;
+    let img =
+        image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+    let mut png = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+
+    // Original first: receivers that take the first type they accept keep its encoding
+    let targets = vec![(mime.to_string(), bytes), ("image/png".to_string(), png)];
+    match detect_session() {
+        Session::Wayland => wayland_write_clipboard(targets),
+        _ => {
+            let server = X11ClipboardServer::own(targets)
+                .ok_or_else(|| "Failed to take clipboard ownership".to_string())?;
+            std::thread::Builder::new()
+                .name("clipboard-image".into())
+                .spawn(move || server.run())
+                .map(|_| ())
+                .map_err(|e| format!("Failed to start clipboard server: {}", e))
+        }
+    }
+}
+
+fn wayland_write_clipboard(targets: Vec<(String, Vec<u8>)>) -> Result<(), String> {
+    use wl_clipboard_rs::copy::{MimeSource, MimeType, Options, Source};
+
+    let sources = targets
+        .into_iter()
+        .map(|(mime, data)| MimeSource {
+            source: Source::Bytes(data.into()),
+            mime_type: MimeType::Specific(mime),
+        })
+        .collect();
+    let mut options = Options::new();
+    options.omit_additional_text_mime_types(true);
+    // Served from a background thread until another app copies
+    options
+        .copy_multi(sources)
+        .map_err(|e| format!("Failed to write image: {}", e))
+}
+
+/// Whether a password manager marked the CLIPBOARD content as secret.
+pub fn platform_clipboard_concealed() -> bool {
+    let types = match detect_session() {
+        Session::Wayland => wl_clipboard_rs::paste::get_mime_types_ordered(
+            wl_clipboard_rs::paste::ClipboardType::Regular,
+            wl_clipboard_rs::paste::Seat::Unspecified,
+        )
+        .ok(),
+        _ => x11_clipboard_targets(),
+    };
+    crate::clipboard::is_concealed(&types.unwrap_or_default())
+}
+
+/// Scale factor of the Tauri monitor at the same physical position (1.0 if unknown).
+fn monitor_scale(monitor: Rect) -> f64 {
+    APP_HANDLE
+        .get()
+        .and_then(|app| app.available_monitors().ok())
+        .and_then(|monitors| {
+            monitors
+                .into_iter()
+                .find(|m| m.position().x == monitor.x && m.position().y == monitor.y)
+        })
+        .map(|m| m.scale_factor())
+        .unwrap_or(1.0)
+}
+
+// ---------------------------------------------------------------------------
+// Monitor and work-area geometry (physical pixels, root window coordinates)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+}
+
+impl Rect {
+    fn contains(&self, px: i32, py: i32) -> bool {
+        px >= self.x && px < self.x + self.w && py >= self.y && py < self.y + self.h
+    }
+}
+
+/// Space reserved by panels and docks on each edge of a monitor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Insets {
+    top: i32,
+    bottom: i32,
+    left: i32,
+    right: i32,
+}
+
+struct CursorGeometry {
+    monitor: Rect,
+    insets: Insets,
+}
+
+fn monitor_at(monitors: &[Rect], px: i32, py: i32) -> Option<Rect> {
+    monitors.iter().copied().find(|m| m.contains(px, py))
+}
+
+/// Insets of `monitor` given a work area that lies within it.
+fn insets_from_work_area(monitor: Rect, work: Rect) -> Insets {
+    Insets {
+        top: (work.y - monitor.y).clamp(0, monitor.h),
+        bottom: ((monitor.y + monitor.h) - (work.y + work.h)).clamp(0, monitor.h),
+        left: (work.x - monitor.x).clamp(0, monitor.w),
+        right: ((monitor.x + monitor.w) - (work.x + work.w)).clamp(0, monitor.w),
+    }
+}
+
+/// Per-monitor work areas (`_GTK_WORKAREAS_D<n>`, set by Mutter): the one centred in `monitor`.
+fn work_area_for_monitor(areas: &[u32], monitor: Rect) -> Option<Rect> {
+    areas
+        .chunks_exact(4)
+        .map(|a| Rect {
+            x: a[0] as i32,
+            y: a[1] as i32,
+            w: a[2] as i32,
+            h: a[3] as i32,
+        })
+        .find(|a| monitor.contains(a.x + a.w / 2, a.y + a.h / 2))
+}
+
+fn spans_overlap(start: u32, end: u32, lo: i32, hi: i32) -> bool {
+    // An unset span (0, 0) covers the whole edge
+    (start == 0 && end == 0) || ((start as i32) < hi && (end as i32) >= lo)
+}
+
+/// Insets of `monitor` from dock windows' `_NET_WM_STRUT_PARTIAL` values, which are
+/// measured from the edges of the whole root window.
+fn insets_from_struts(monitor: Rect, root_w: i32, root_h: i32, struts: &[[u32; 12]]) -> Insets {
+    let m = monitor;
+    let mut insets = Insets::default();
+    for s in struts {
+        let [left, right, top, bottom, ly0, ly1, ry0, ry1, tx0, tx1, bx0, bx1] = *s;
+        if top > 0 && spans_overlap(tx0, tx1, m.x, m.x + m.w) {
+            insets.top = insets.top.max(top as i32 - m.y);
+        }
+        if bottom > 0 && spans_overlap(bx0, bx1, m.x, m.x + m.w) {
+            insets.bottom = insets.bottom.max((m.y + m.h) - (root_h - bottom as i32));
+        }
+        if left > 0 && spans_overlap(ly0, ly1, m.y, m.y + m.h) {
+            insets.left = insets.left.max(left as i32 - m.x);
+        }
+        if right > 0 && spans_overlap(ry0, ry1, m.y, m.y + m.h) {
+            insets.right = insets.right.max((m.x + m.w) - (root_w - right as i32));
+        }
+    }
+    Insets {
+        top: insets.top.clamp(0, m.h),
+        bottom: insets.bottom.clamp(0, m.h),
+        left: insets.left.clamp(0, m.w),
+        right: insets.right.clamp(0, m.w),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// X11 backend
+// ---------------------------------------------------------------------------
+
+fn x11_connect() -> Option<(RustConnection, Window)> {
+    let (conn, screen) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots[screen].root;
+    Some((conn, root))
+}
+
+/// Look up an existing atom (None if no client has created it).
+fn x11_atom(conn: &RustConnection, name: &str) -> Option<Atom> {
+    let atom = conn
+        .intern_atom(true, name.as_bytes())
+        .ok()?
+        .reply()
+        .ok()?
+        .atom;
+    (atom != x11rb::NONE).then_some(atom)
+}
+
+fn x11_cardinals(
+    conn: &RustConnection,
+    window: Window,
+    property: Atom,
+    type_: impl Into<Atom>,
+) -> Option<Vec<u32>> {
+    let reply = conn
+        .get_property(false, window, property, type_, 0, 4096)
+        .ok()?
+        .reply()
+        .ok()?;
+    let values = reply.value32()?.collect();
+    Some(values)
+}
+
+/// Intern an atom, creating it if needed.
+fn x11_intern(conn: &RustConnection, name: &str) -> Option<Atom> {
+    Some(
+        conn.intern_atom(false, name.as_bytes())
+            .ok()?
+            .reply()
+            .ok()?
+            .atom,
+    )
+}
+
+/// Hidden window that receives CLIPBOARD conversions (and the property changes of
+/// incremental transfers). Destroyed along with the connection.
+fn x11_transfer_window(conn: &RustConnection, root: Window) -> Option<Window> {
+    let window = conn.generate_id().ok()?;
+    conn.create_window(
+        0,
+        window,
+        root,
+        0,
+        0,
+        1,
+        1,
+        0,
+        WindowClass::INPUT_ONLY,
+        0,
+        &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+    )
+    .ok()?;
+    Some(window)
+}
+
+/// Wait until `deadline` for an event matching `pred`, dropping others.
+fn x11_wait_for(
+    conn: &RustConnection,
+    deadline: std::time::Instant,
+    pred: impl Fn(&Event) -> bool,
+) -> Option<Event> {
+    loop {
+        match conn.poll_for_event().ok()? {
+            Some(event) if pred(&event) => return Some(event),
+            Some(_) => {}
+            None if std::time::Instant::now() >= deadline => return None,
+            None => std::thread::sleep(std::time::Duration::from_millis(2)),
+        }
+    }
+}
+
+/// Ask the CLIPBOARD owner to convert to `target` into `property` on `window`.
+/// Returns None if it refuses or doesn't answer before `deadline`.
+fn x11_convert_clipboard(
+    conn: &RustConnection,
+    window: Window,
+    target: Atom,
+    property: Atom,
+    deadline: std::time::Instant,
+) -> Option<()> {
+    let clipboard = x11_intern(conn, "CLIPBOARD")?;
+    conn.convert_selection(window, clipboard, target, property, x11rb::CURRENT_TIME)
+        .ok()?;
+    conn.flush().ok()?;
+    match x11_wait_for(
+        conn,
+        deadline,
+        |e| matches!(e, Event::SelectionNotify(n) if n.requestor == window),
+    )? {
+        Event::SelectionNotify(n) if n.property != x11rb::NONE => Some(()),
+        _ => None,
+    }
+}
+
+/// Names of the targets the CLIPBOARD owner offers.
+fn x11_clipboard_targets() -> Option<Vec<String>> {
+    const TIMEOUT: std::time::Duration = std::time::Duration::from_millis(300);
+
+    let (conn, root) = x11_connect()?;
+    let (targets, property) = (
+        x11_intern(&conn, "TARGETS")?,
+        x11_intern(&conn, "RECOPY_TARGETS")?,
+    );
+    let window = x11_transfer_window(&conn, root)?;
+    x11_convert_clipboard(
+        &conn,
+        window,
+        targets,
+        property,
+        std::time::Instant::now() + TIMEOUT,
+    )?;
+
+    let atoms = x11_cardinals(&conn, window, property, AtomEnum::ATOM)?;
+    let cookies = atoms
+        .into_iter()
+        .filter_map(|atom| conn.get_atom_name(atom).ok())
+        .collect::<Vec<_>>();
+    Some(
+        cookies
+            .into_iter()
+            .filter_map(|cookie| cookie.reply().ok())
+            .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+            .collect(),
+    )
+}
+
+/// Read the first original image encoding the CLIPBOARD owner offers, following
+/// incremental (INCR) transfers for images larger than one X request.
+fn x11_read_original_image() -> Option<Vec<u8>> {
+    let offered = x11_clipboard_targets()?;
+    let mime = ORIGINAL_IMAGE_TYPES
+        .iter()
+        .find(|m| offered.iter().any(|o| o == *m))?;
+
+    let (conn, root) = x11_connect()?;
+    let (target, property, incr) = (
+        x11_intern(&conn, mime)?,
+        x11_intern(&conn, "RECOPY_IMAGE")?,
+        x11_intern(&conn, "INCR")?,
+    );
+    let window = x11_transfer_window(&conn, root)?;
+    let deadline = std::time::Instant::now() + ORIGINAL_IMAGE_TIMEOUT;
+    x11_convert_clipboard(&conn, window, target, property, deadline)?;
+
+    // Reading with delete also acknowledges an INCR header, starting the transfer
+    let read = |conn: &RustConnection| {
+        let reply = conn
+            .get_property(true, window, property, AtomEnum::ANY, 0, u32::MAX / 4)
+            .ok()?
+            .reply()
+            .ok()?;
+        conn.flush().ok()?;
+        Some(reply)
+    };
+    let head = read(&conn)?;
+    if head.type_ != incr {
+        return Some(head.value).filter(|d| !d.is_empty());
+    }
+
+    // Each chunk arrives as a new property value; an empty one ends the transfer
+    let mut data = Vec::new();
+    loop {
+        x11_wait_for(&conn, deadline, |e| {
+            matches!(e, Event::PropertyNotify(p)
+                if p.window == window && p.atom == property && p.state == Property::NEW_VALUE)
+        })?;
+        let chunk = read(&conn)?;
+        if chunk.value.is_empty() {
+            return Some(data).filter(|d| !d.is_empty());
+        }
+        data.extend_from_slice(&chunk.value);
+    }
+}
+
+/// An incremental (INCR) transfer of one served target to a requestor.
+struct X11Transfer {
+    requestor: Window,
+    property: Atom,
+    /// Index into `X11ClipboardServer::served`.
+    index: usize,
+    /// Bytes already sent.
+    offset: usize,
+}
+
+/// CLIPBOARD owned by Recopy, answering conversions of `served` (target, data) pairs.
+struct X11ClipboardServer {
+    conn: RustConnection,
+    window: Window,
+    clipboard: Atom,
+    targets: Atom,
+    incr: Atom,
+    served: Vec<(Atom, Vec<u8>)>,
+    transfers: Vec<X11Transfer>,
+}
+
+impl X11ClipboardServer {
+    /// Take CLIPBOARD for the given (MIME type, data) pairs.
+    fn own(targets: Vec<(String, Vec<u8>)>) -> Option<Self> {
+        let (conn, root) = x11_connect()?;
+        let window = x11_transfer_window(&conn, root)?;
+        let clipboard = x11_intern(&conn, "CLIPBOARD")?;
+        let served = targets
+            .into_iter()
+            .map(|(mime, data)| Some((x11_intern(&conn, &mime)?, data)))
+            .collect::<Option<Vec<_>>>()?;
+        conn.set_selection_owner(window, clipboard, x11rb::CURRENT_TIME)
+            .ok()?;
+        let owner = conn
+            .get_selection_owner(clipboard)
+            .ok()?
+            .reply()
+            .ok()?
+            .owner;
+        if owner != window {
+            return None;
+        }
+        Some(Self {
+            targets: x11_intern(&conn, "TARGETS")?,
+            incr: x11_intern(&conn, "INCR")?,
+            conn,
+            window,
+            clipboard,
+            served,
+            transfers: Vec::new(),
+        })
+    }
+
+    /// Answer requests until another client takes CLIPBOARD.
+    fn run(mut self) {
+        use x11rb::connection::RequestConnection;
+
+        // Room for the ChangeProperty request header
+        let max_bytes = self.conn.maximum_request_bytes().saturating_sub(32);
+        while let Ok(event) = self.conn.wait_for_event() {
+            let handled = match event {
+                Event::SelectionClear(e) if e.selection == self.clipboard => return,
+                Event::SelectionRequest(req) => self.answer(&req, max_bytes),
+                Event::PropertyNotify(p) if p.state == Property::DELETE => {
+                    self.send_chunk(p.window, p.atom, max_bytes)
+                }
+                _ => Some(()),
+            };
+            if handled.is_none() {
+                log::warn!("Stopped serving the clipboard image after an X11 error");
+                return;
+            }
+        }
+    }
+
+    fn answer(&mut self, req: &SelectionRequestEvent, max_bytes: usize) -> Option<()> {
+        // Obsolete clients leave the property unset and expect the target name
+        let property = if req.property == x11rb::NONE {
+            req.target
+        } else {
+            req.property
+        };
+        let granted = if req.selection != self.clipboard || req.owner != self.window {
+            false
+        } else if req.target == self.targets {
+            let mut atoms = vec![self.targets];
+            atoms.extend(self.served.iter().map(|(target, _)| *target));
+            self.conn
+                .change_property32(
+                    PropMode::REPLACE,
+                    req.requestor,
+                    property,
+                    AtomEnum::ATOM,
+                    &atoms,
+                )
+                .ok()?;
+            true
+        } else if let Some(index) = self.served.iter().position(|(t, _)| *t == req.target) {
+            let data = &self.served[index].1;
+            if data.len() <= max_bytes {
+                self.conn
+                    .change_property8(PropMode::REPLACE, req.requestor, property, req.target, data)
+                    .ok()?;
+            } else {
+                // Announce the size; chunks follow each time the requestor deletes the property
+                self.conn
+                    .change_window_attributes(
+                        req.requestor,
+                        &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+                    )
+                    .ok()?;
+                self.conn
+                    .change_property32(
+                        PropMode::REPLACE,
+                        req.requestor,
+                        property,
+                        self.incr,
+                        &[data.len() as u32],
+                    )
+                    .ok()?;
+                self.transfers.push(X11Transfer {
+                    requestor: req.requestor,
+                    property,
+                    index,
+                    offset: 0,
+                });
+            }
+            true
+        } else {
+            false
+        };
+
+        let notify = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: req.time,
+            requestor: req.requestor,
+            selection: req.selection,
+            target: req.target,
+            property: if granted { property } else { x11rb::NONE },
+        };
+        self.conn
+            .send_event(false, req.requestor, EventMask::NO_EVENT, notify)
+            .ok()?;
+        self.conn.flush().ok()
+    }
+
+    /// Send the next chunk of an incremental transfer; an empty chunk ends it.
+    fn send_chunk(&mut self, requestor: Window, property: Atom, max_bytes: usize) -> Option<()> {
+        let Some(pos) = self
+            .transfers
+            .iter()
+            .position(|t| t.requestor == requestor && t.property == property)
+        else {
+            return Some(());
+        };
+        let transfer = &mut self.transfers[pos];
+        let (target, data) = &self.served[transfer.index];
+        let end = (transfer.offset + max_bytes).min(data.len());
+        self.conn
+            .change_property8(
+                PropMode::REPLACE,
+                requestor,
+                property,
+                *target,
+                &data[transfer.offset..end],
+            )
+            .ok()?;
+        if transfer.offset == end {
+            self.transfers.swap_remove(pos);
+        } else {
+            transfer.offset = end;
+        }
+        self.conn.flush().ok()
+    }
+}
+
+/// The window in `_NET_ACTIVE_WINDOW`, if the window manager sets it.
+pub(super) fn x11_active_window(conn: &RustConnection, root: Window) -> Option<Window> {
+    let atom = x11_atom(conn, "_NET_ACTIVE_WINDOW")?;
+    x11_cardinals(conn, root, atom, AtomEnum::WINDOW)?
+        .first()
+        .copied()
+        .filter(|&w| w != 0)
+}
+
+/// Physical bounds of every monitor, from RandR (falling back to the whole screen).
+fn x11_monitors(conn: &RustConnection, root: Window) -> Vec<Rect> {
+    let monitors = conn
+        .randr_get_monitors(root, true)
+        .ok()
+        .and_then(|c| c.reply().ok())
+        .map(|r| r.monitors)
+        .unwrap_or_default();
+    if !monitors.is_empty() {
+        return monitors
+            .iter()
+            .map(|m| Rect {
+                x: m.x as i32,
+                y: m.y as i32,
+                w: m.width as i32,
+                h: m.height as i32,
+            })
+            .collect();
+    }
+    let screen = conn.setup().roots.iter().find(|s| s.root == root);
+    screen
+        .map(|s| Rect {
+            x: 0,
+            y: 0,
+            w: s.width_in_pixels as i32,
+            h: s.height_in_pixels as i32,
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Panel insets of `monitor`: Mutter's per-monitor work areas, then dock struts
+/// (KDE, Xfce, ...), then `_NET_WORKAREA` when there is a single monitor.
+fn x11_insets(conn: &RustConnection, root: Window, monitor: Rect, monitor_count: usize) -> Insets {
+    let desktop = x11_atom(conn, "_NET_CURRENT_DESKTOP")
+        .and_then(|a| x11_cardinals(conn, root, a, AtomEnum::CARDINAL))
+        .and_then(|v| v.first().copied())
+        .unwrap_or(0);
+
+    if let Some(work) = x11_atom(conn, &format!("_GTK_WORKAREAS_D{}", desktop))
+        .and_then(|a| x11_cardinals(conn, root, a, AtomEnum::CARDINAL))
+        .and_then(|areas| work_area_for_monitor(&areas, monitor))
+    {
+        return insets_from_work_area(monitor, work);
+    }
+
+    let struts: Vec<[u32; 12]> = match (
+        x11_atom(conn, "_NET_CLIENT_LIST"),
+        x11_atom(conn, "_NET_WM_STRUT_PARTIAL"),
+    ) {
+        (Some(list_atom), Some(strut_atom)) => {
+            x11_cardinals(conn, root, list_atom, AtomEnum::WINDOW)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|w| x11_cardinals(conn, w, strut_atom, AtomEnum::CARDINAL))
+                .filter_map(|v| <[u32; 12]>::try_from(v).ok())
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    if !struts.is_empty() {
+        let (root_w, root_h) = conn
+            .setup()
+            .roots
+            .iter()
+            .find(|s| s.root == root)
+            .map(|s| (s.width_in_pixels as i32, s.height_in_pixels as i32))
+            .unwrap_or((monitor.w, monitor.h));
+        return insets_from_struts(monitor, root_w, root_h, &struts);
+    }
+
+    if monitor_count == 1 {
+        let workarea = x11_atom(conn, "_NET_WORKAREA")
+            .and_then(|a| x11_cardinals(conn, root, a, AtomEnum::CARDINAL))
+            .unwrap_or_default();
+        let start = desktop as usize * 4;
+        if let Some(a) = workarea.get(start..start + 4) {
+            let work = Rect {
+                x: a[0] as i32,
+                y: a[1] as i32,
+                w: a[2] as i32,
+                h: a[3] as i32,
+            };
+            return insets_from_work_area(monitor, work);
+        }
+    }
+    Insets::default()
+}
+
+/// The monitor under the pointer and its panel insets.
+fn x11_cursor_geometry() -> Option<CursorGeometry> {
+    let (conn, root) = x11_connect()?;
+    let pointer = conn.query_pointer(root).ok()?.reply().ok()?;
+    let monitors = x11_monitors(&conn, root);
+    let monitor = monitor_at(&monitors, pointer.root_x as i32, pointer.root_y as i32)?;
+    let insets = x11_insets(&conn, root, monitor, monitors.len());
+    Some(CursorGeometry { monitor, insets })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEFT: Rect = Rect {
+        x: 0,
+        y: 0,
+        w: 1920,
+        h: 1080,
+    };
+    const RIGHT: Rect = Rect {
+        x: 1920,
+        y: 0,
+        w: 2560,
+        h: 1440,
+    };
+
+    #[test]
+    fn test_wm_class_name() {
+        assert_eq!(
+            wm_class_name(b"Navigator\0firefox\0").as_deref(),
+            Some("firefox")
+        );
+        assert_eq!(
+            wm_class_name(b"gnome-terminal-server\0Gnome-terminal\0").as_deref(),
+            Some("Gnome-terminal")
+        );
+        assert_eq!(wm_class_name(b"only\0"), None);
+        assert_eq!(wm_class_name(b""), None);
+    }
+
+    #[test]
+    fn test_monitor_at() {
+        let monitors = [LEFT, RIGHT];
+        assert_eq!(monitor_at(&monitors, 10, 10), Some(LEFT));
+        assert_eq!(monitor_at(&monitors, 1920, 1200), Some(RIGHT));
+        // Below the shorter monitor
+        assert_eq!(monitor_at(&monitors, 100, 1200), None);
+    }
+
+    #[test]
+    fn test_gtk_work_areas() {
+        // Top bar on both monitors, dock on the left one
+        let areas = [72, 32, 1848, 1048, 1920, 32, 2560, 1408];
+        let work = work_area_for_monitor(&areas, RIGHT).unwrap();
+        assert_eq!(
+            insets_from_work_area(RIGHT, work),
+            Insets {
+                top: 32,
+                ..Default::default()
+            }
+        );
+        let work = work_area_for_monitor(&areas, LEFT).unwrap();
+        assert_eq!(
+            insets_from_work_area(LEFT, work),
+            Insets {
+                top: 32,
+                left: 72,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_insets_from_struts() {
+        let (root_w, root_h) = (4480, 1440);
+        // KDE-style bottom panel spanning only the left monitor
+        let bottom_left = [0, 0, 0, 404, 0, 0, 0, 0, 0, 0, 0, 1919];
+        // Top panel on the right monitor
+        let top_right = [0, 0, 0, 28, 0, 0, 0, 0, 1920, 4479, 0, 0];
+        let struts = [bottom_left, top_right];
+
+        // The left monitor is 360px shorter than the root, so 44px of the strut lands on it
+        assert_eq!(
+            insets_from_struts(LEFT, root_w, root_h, &struts),
+            Insets {
+                bottom: 44,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            insets_from_struts(RIGHT, root_w, root_h, &struts),
+            Insets {
+                top: 28,
+                ..Default::default()
+            }
+        );
+    }
+
+    /// Needs an X server: `xvfb-run cargo test -- --ignored platform::linux`
+    #[test]
+    #[ignore]
+    fn test_x11_geometry_under_xvfb() {
+        use x11rb::protocol::xproto::{CreateWindowAux, PropMode, WindowClass};
+        use x11rb::wrapper::ConnectionExt as _;
+
+        let (conn, root) = x11_connect().expect("no X display");
+        let geometry = x11_cursor_geometry().expect("pointer not on a monitor");
+        assert!(geometry.monitor.w > 0 && geometry.monitor.h > 0);
+
+        // Simulate a window manager with a 30px top dock on the whole screen
+        let dock = conn.generate_id().unwrap();
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            dock,
+            root,
+            0,
+            0,
+            100,
+            30,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        let intern = |name: &str| {
+            conn.intern_atom(false, name.as_bytes())
+                .unwrap()
+                .reply()
+                .unwrap()
+                .atom
+        };
+        let strut = [0u32, 0, 30, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        conn.change_property32(
+            PropMode::REPLACE,
+            dock,
+            intern("_NET_WM_STRUT_PARTIAL"),
+            AtomEnum::CARDINAL,
+            &strut,
+        )
+        .unwrap();
+        conn.change_property32(
+            PropMode::REPLACE,
+            root,
+            intern("_NET_CLIENT_LIST"),
+            AtomEnum::WINDOW,
+            &[dock],
+        )
+        .unwrap();
+        conn.sync().unwrap();
+
+        assert_eq!(platform_menu_bar_height(), 30.0);
+        let (_, _, _, h) = platform_cursor_monitor().unwrap();
+        assert_eq!(h, geometry.monitor.h as f64);
+        assert!(!is_recopy_foreground());
+
+        conn.delete_property(root, intern("_NET_CLIENT_LIST"))
+            .unwrap();
+        conn.destroy_window(dock).unwrap();
+        conn.sync().unwrap();
+    }
+}
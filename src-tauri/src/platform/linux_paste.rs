@@ -0,0 +1,542 @@
+//! Auto-paste on Linux: XTest on X11, a `/dev/uinput` virtual keyboard on Wayland,
+//! or a user-configured command (e.g. `xdotool` / `ydotool`) as the fallback.
+
+use std::time::Duration;
+
+/// Settings key for the paste backend (`auto` | `xtest` | `uinput` | `command` | `off`).
+pub const PASTE_BACKEND_KEY: &str = "linux_paste_backend";
+
+/// Settings key for the key combination (`auto` | `ctrl_v` | `ctrl_shift_v` | `shift_insert`).
+pub const PASTE_KEYS_KEY: &str = "linux_paste_keys";
+
+/// Settings key for the fallback command. `{keys}` expands to e.g. `ctrl+v`.
+pub const PASTE_COMMAND_KEY: &str = "linux_paste_command";
+
+/// Time for the previous window to get focus back after the panel hides.
+const FOCUS_SETTLE: Duration = Duration::from_millis(80);
+
+/// How the paste keystroke is delivered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PasteBackend {
+    /// XTest on X11; uinput on Wayland; then the command if one is set.
+    #[default]
+    Auto,
+    XTest,
+    Uinput,
+    Command,
+    Off,
+}
+
+impl PasteBackend {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("xtest") => Self::XTest,
+            Some("uinput") => Self::Uinput,
+            Some("command") => Self::Command,
+            Some("off") => Self::Off,
+            _ => Self::Auto,
+        }
+    }
+}
+
+/// Which key combination pastes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PasteKeys {
+    /// Ctrl+Shift+V when the focused X11 window is a terminal, Ctrl+V otherwise.
+    #[default]
+    Auto,
+    CtrlV,
+    CtrlShiftV,
+    ShiftInsert,
+}
+
+impl PasteKeys {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("ctrl_v") => Self::CtrlV,
+            Some("ctrl_shift_v") => Self::CtrlShiftV,
+            Some("shift_insert") => Self::ShiftInsert,
+            _ => Self::Auto,
+        }
+    }
+}
+
+/// Paste settings, read by the caller from the settings table.
+#[derive(Debug, Clone, Default)]
+pub struct PasteConfig {
+    pub backend: PasteBackend,
+    pub keys: PasteKeys,
+    pub command: String,
+}
+
+/// A concrete key combination, pressed in order and released in reverse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chord {
+    CtrlV,
+    CtrlShiftV,
+    ShiftInsert,
+}
+
+const XK_CONTROL_L: u32 = 0xffe3;
+const XK_SHIFT_L: u32 = 0xffe1;
+const XK_INSERT: u32 = 0xff63;
+const XK_V: u32 = 0x0076;
+
+const KEY_LEFTCTRL: u16 = 29;
+const KEY_LEFTSHIFT: u16 = 42;
+const KEY_V: u16 = 47;
+const KEY_INSERT: u16 = 110;
+
+impl Chord {
+    /// X keysyms, resolved to keycodes through the server's keyboard mapping.
+    fn keysyms(self) -> &'static [u32] {
+        match self {
+            Chord::CtrlV => &[XK_CONTROL_L, XK_V],
+            Chord::CtrlShiftV => &[XK_CONTROL_L, XK_SHIFT_L, XK_V],
+            Chord::ShiftInsert => &[XK_SHIFT_L, XK_INSERT],
+        }
+    }
+
+    /// evdev key codes. These are physical positions, so V assumes a QWERTY-like layout.
+    fn evdev_codes(self) -> &'static [u16] {
+        match self {
+            Chord::CtrlV => &[KEY_LEFTCTRL, KEY_V],
+            Chord::CtrlShiftV => &[KEY_LEFTCTRL, KEY_LEFTSHIFT, KEY_V],
+            Chord::ShiftInsert => &[KEY_LEFTSHIFT, KEY_INSERT],
+        }
+    }
+
+    /// `{keys}` value for the fallback command (xdotool syntax).
+    fn command_arg(self) -> &'static str {
+        match self {
+            Chord::CtrlV => "ctrl+v",
+            Chord::CtrlShiftV => "ctrl+shift+v",
+            Chord::ShiftInsert => "shift+Insert",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Session {
+    X11,
+    Wayland,
+    Unknown,
+}
+
+fn session_from_env(
+    wayland_display: Option<&str>,
+    session_type: Option<&str>,
+    display: Option<&str>,
+) -> Session {
+    let set = |v: Option<&str>| v.is_some_and(|v| !v.is_empty());
+    if set(wayland_display) || session_type == Some("wayland") {
+        Session::Wayland
+    } else if set(display) || session_type == Some("x11") {
+        Session::X11
+    } else {
+        Session::Unknown
+    }
+}
+
+pub(crate) fn detect_session() -> Session {
+    let var = |key| std::env::var(key).ok();
+    session_from_env(
+        var("WAYLAND_DISPLAY").as_deref(),
+        var("XDG_SESSION_TYPE").as_deref(),
+        var("DISPLAY").as_deref(),
+    )
+}
+
+/// Backends to try, in order. XTest is skipped on Wayland: through XWayland it only
+/// reaches X clients.
+fn backend_order(backend: PasteBackend, session: Session, has_command: bool) -> Vec<PasteBackend> {
+    let mut order = match backend {
+        PasteBackend::Off => return vec![],
+        PasteBackend::Auto => match session {
+            Session::Wayland => vec![PasteBackend::Uinput],
+            Session::X11 | Session::Unknown => vec![PasteBackend::XTest, PasteBackend::Uinput],
+        },
+        explicit => return vec![explicit],
+    };
+    if has_command {
+        order.push(PasteBackend::Command);
+    }
+    order
+}
+
+/// Lowercase WM_CLASS names of terminal emulators, which paste with Ctrl+Shift+V.
+const TERMINAL_CLASSES: &[&str] = &[
+    "alacritty",
+    "foot",
+    "ghostty",
+    "gnome-terminal",
+    "gnome-terminal-server",
+    "guake",
+    "kgx",
+    "kitty",
+    "konsole",
+    "lxterminal",
+    "mate-terminal",
+    "org.gnome.console",
+    "org.wezfurlong.wezterm",
+    "ptyxis",
+    "qterminal",
+    "sakura",
+    "st-256color",
+    "terminator",
+    "terminology",
+    "tilda",
+    "tilix",
+    "urxvt",
+    "wezterm",
+    "wezterm-gui",
+    "xfce4-terminal",
+    "xterm",
+    "yakuake",
+];
+
+/// Instance and class names from a raw `WM_CLASS` value (`instance\0class\0`).
+fn wm_class_names(raw: &[u8]) -> Vec<String> {
+    raw.split(|&b| b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).to_lowercase())
+        .collect()
+}
+
+fn is_terminal(wm_class: &[String]) -> bool {
+    wm_class
+        .iter()
+        .any(|name| TERMINAL_CLASSES.contains(&name.as_str()))
+}
+
+fn resolve_chord(keys: PasteKeys, terminal: bool) -> Chord {
+    match keys {
+        PasteKeys::CtrlV => Chord::CtrlV,
+        PasteKeys::CtrlShiftV => Chord::CtrlShiftV,
+        PasteKeys::ShiftInsert => Chord::ShiftInsert,
+        PasteKeys::Auto if terminal => Chord::CtrlShiftV,
+        PasteKeys::Auto => Chord::CtrlV,
+    }
+}
+
+/// Send the paste keystroke to the focused window, trying each configured backend in turn.
+/// Blocking; returns the errors of every backend that failed.
+pub fn simulate_paste_keys(config: &PasteConfig) -> Result<(), String> {
+    let session = detect_session();
+    let order = backend_order(config.backend, session, !config.command.trim().is_empty());
+    if order.is_empty() {
+        return Ok(());
+    }
+
+    std::thread::sleep(FOCUS_SETTLE);
+
+    // Terminal detection needs the focused window, which only X11 exposes
+    let terminal = config.keys == PasteKeys::Auto
+        && session != Session::Wayland
+        && x11::active_window_class().is_some_and(|class| is_terminal(&class));
+    let chord = resolve_chord(config.keys, terminal);
+
+    let mut errors = Vec::new();
+    for backend in order {
+        let result = match backend {
+            PasteBackend::XTest => x11::send_chord(chord),
+            PasteBackend::Uinput => uinput::send_chord(chord),
+            PasteBackend::Command => run_command(&config.command, chord),
+            PasteBackend::Auto | PasteBackend::Off => Ok(()),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) => errors.push(format!("{:?}: {}", backend, e)),
+        }
+    }
+    Err(errors.join("; "))
+}
+
+fn run_command(template: &str, chord: Chord) -> Result<(), String> {
+    let command = template.replace("{keys}", chord.command_arg());
+    let mut parts = command.split_whitespace();
+    let program = parts.next().ok_or("No paste command configured")?;
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .status()
+        .map_err(|e| format!("{}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+mod x11 {
+    use super::{wm_class_names, Chord};
+    use x11rb::connection::{Connection, RequestConnection};
+    use x11rb::protocol::xproto::{
+        AtomEnum, ConnectionExt as _, Keycode, Window, KEY_PRESS_EVENT, KEY_RELEASE_EVENT,
+    };
+    use x11rb::protocol::xtest::{self, ConnectionExt as _};
+    use x11rb::rust_connection::RustConnection;
+
+    fn connect() -> Result<(RustConnection, Window), String> {
+        let (conn, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        let root = conn.setup().roots[screen].root;
+        Ok((conn, root))
+    }
+
+    /// Keycodes producing `keysyms` in the server's current keyboard mapping.
+    pub(super) fn keycodes(conn: &RustConnection, keysyms: &[u32]) -> Result<Vec<Keycode>, String> {
+        let setup = conn.setup();
+        let min = setup.min_keycode;
+        let mapping = conn
+            .get_keyboard_mapping(min, setup.max_keycode - min + 1)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        let per_keycode = mapping.keysyms_per_keycode as usize;
+        if per_keycode == 0 {
+            return Err("Empty keyboard mapping".to_string());
+        }
+
+        keysyms
+            .iter()
+            .map(|sym| {
+                mapping
+                    .keysyms
+                    .chunks(per_keycode)
+                    .position(|syms| syms.contains(sym))
+                    .map(|i| min + i as u8)
+                    .ok_or_else(|| format!("No keycode for keysym {:#x}", sym))
+            })
+            .collect()
+    }
+
+    pub(super) fn send_chord(chord: Chord) -> Result<(), String> {
+        let (conn, root) = connect()?;
+        if conn
+            .extension_information(xtest::X11_EXTENSION_NAME)
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            return Err("XTest extension not available".to_string());
+        }
+
+        let codes = keycodes(&conn, chord.keysyms())?;
+        let fake = |event_type: u8, code: Keycode| {
+            conn.xtest_fake_input(event_type, code, x11rb::CURRENT_TIME, root, 0, 0, 0)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        };
+        for &code in &codes {
+            fake(KEY_PRESS_EVENT, code)?;
+        }
+        for &code in codes.iter().rev() {
+            fake(KEY_RELEASE_EVENT, code)?;
+        }
+
+        // Round-trip so the server has processed the events before the connection closes
+        conn.get_input_focus()
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// WM_CLASS names of the window in `_NET_ACTIVE_WINDOW`.
+    pub(super) fn active_window_class() -> Option<Vec<String>> {
+        let (conn, root) = connect().ok()?;
+        let window = crate::platform::linux::x11_active_window(&conn, root)?;
+        let class = conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+            .ok()?
+            .reply()
+            .ok()?;
+        Some(wm_class_names(&class.value))
+    }
+
+    /// Connect to `$DISPLAY` (Xvfb in tests) for the integration test.
+    #[cfg(test)]
+    pub(super) fn test_connection() -> Option<RustConnection> {
+        connect().ok().map(|(conn, _)| conn)
+    }
+}
+
+mod uinput {
+    use super::Chord;
+    use std::fs::{File, OpenOptions};
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    // _IOW('U', 100, int), _IOW('U', 101, int), _IOW('U', 3, struct uinput_setup), _IO('U', 1)
+    const UI_SET_EVBIT: u32 = 0x4004_5564;
+    const UI_SET_KEYBIT: u32 = 0x4004_5565;
+    const UI_DEV_SETUP: u32 = 0x405c_5503;
+    const UI_DEV_CREATE: u32 = 0x5501;
+
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const SYN_REPORT: u16 = 0;
+    const BUS_VIRTUAL: u16 = 0x06;
+
+    /// Time for the compositor to pick up a newly created device.
+    const DEVICE_SETTLE: Duration = Duration::from_millis(200);
+
+    /// The virtual keyboard, created on first use and kept for the app's lifetime.
+    static DEVICE: Mutex<Option<File>> = Mutex::new(None);
+
+    fn check(ret: libc::c_int, what: &str) -> Result<(), String> {
+        if ret < 0 {
+            Err(format!("{}: {}", what, std::io::Error::last_os_error()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn create_device() -> Result<File, String> {
+        let file = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/uinput")
+            .map_err(|e| format!("/dev/uinput: {}", e))?;
+        let fd = file.as_raw_fd();
+
+        let mut setup: libc::uinput_setup = unsafe { std::mem::zeroed() };
+        setup.id = libc::input_id {
+            bustype: BUS_VIRTUAL,
+            vendor: 0,
+            product: 0,
+            version: 1,
+        };
+        for (dst, src) in setup.name.iter_mut().zip(b"Recopy virtual keyboard") {
+            *dst = *src as libc::c_char;
+        }
+
+        unsafe {
+            check(
+                libc::ioctl(fd, UI_SET_EVBIT as _, EV_KEY as libc::c_int),
+                "UI_SET_EVBIT",
+            )?;
+            for code in [Chord::CtrlV, Chord::CtrlShiftV, Chord::ShiftInsert]
+                .iter()
+                .flat_map(|c| c.evdev_codes())
+            {
+                check(
+                    libc::ioctl(fd, UI_SET_KEYBIT as _, *code as libc::c_int),
+                    "UI_SET_KEYBIT",
+                )?;
+            }
+            check(
+                libc::ioctl(fd, UI_DEV_SETUP as _, &setup as *const libc::uinput_setup),
+                "UI_DEV_SETUP",
+            )?;
+            check(libc::ioctl(fd, UI_DEV_CREATE as _), "UI_DEV_CREATE")?;
+        }
+
+        std::thread::sleep(DEVICE_SETTLE);
+        Ok(file)
+    }
+
+    fn emit(file: &mut File, event_type: u16, code: u16, value: i32) -> std::io::Result<()> {
+        // Zeroed timestamp: the kernel stamps uinput events itself
+        let mut event: libc::input_event = unsafe { std::mem::zeroed() };
+        event.type_ = event_type;
+        event.code = code;
+        event.value = value;
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                &event as *const libc::input_event as *const u8,
+                std::mem::size_of::<libc::input_event>(),
+            )
+        };
+        file.write_all(bytes)
+    }
+
+    fn press_release(file: &mut File, codes: &[u16]) -> std::io::Result<()> {
+        for &code in codes {
+            emit(file, EV_KEY, code, 1)?;
+            emit(file, EV_SYN, SYN_REPORT, 0)?;
+        }
+        for &code in codes.iter().rev() {
+            emit(file, EV_KEY, code, 0)?;
+            emit(file, EV_SYN, SYN_REPORT, 0)?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn send_chord(chord: Chord) -> Result<(), String> {
+        let mut device = DEVICE.lock().unwrap();
+        let file = match device.as_mut() {
+            Some(file) => file,
+            None => device.insert(create_device()?),
+        };
+        if let Err(e) = press_release(file, chord.evdev_codes()) {
+            // Recreate the device on the next paste
+            *device = None;
+            return Err(e.to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_from_env() {
+        assert_eq!(
+            session_from_env(Some("wayland-0"), None, Some(":0")),
+            Session::Wayland
+        );
+        assert_eq!(
+            session_from_env(None, Some("wayland"), None),
+            Session::Wayland
+        );
+        assert_eq!(session_from_env(Some(""), None, Some(":1")), Session::X11);
+        assert_eq!(session_from_env(None, None, None), Session::Unknown);
+    }
+
+    #[test]
+    fn test_backend_order() {
+        use PasteBackend::*;
+        assert_eq!(
+            backend_order(Auto, Session::X11, false),
+            vec![XTest, Uinput]
+        );
+        assert_eq!(
+            backend_order(Auto, Session::Wayland, true),
+            vec![Uinput, Command]
+        );
+        assert_eq!(backend_order(XTest, Session::Wayland, true), vec![XTest]);
+        assert!(backend_order(Off, Session::X11, true).is_empty());
+        assert_eq!(PasteBackend::from_setting(Some("bogus")), Auto);
+    }
+
+    #[test]
+    fn test_terminal_chord() {
+        let class = wm_class_names(b"gnome-terminal-server\0Gnome-terminal\0");
+        assert_eq!(class, vec!["gnome-terminal-server", "gnome-terminal"]);
+        assert!(is_terminal(&class));
+        assert!(!is_terminal(&wm_class_names(b"Navigator\0firefox\0")));
+
+        assert_eq!(resolve_chord(PasteKeys::Auto, true), Chord::CtrlShiftV);
+        assert_eq!(resolve_chord(PasteKeys::Auto, false), Chord::CtrlV);
+        assert_eq!(
+            resolve_chord(PasteKeys::ShiftInsert, true),
+            Chord::ShiftInsert
+        );
+    }
+
+    /// Needs an X server: `xvfb-run cargo test -- --ignored linux_paste`
+    #[test]
+    #[ignore]
+    fn test_xtest_under_xvfb() {
+        let conn = x11::test_connection().expect("no X display");
+        for chord in [Chord::CtrlV, Chord::CtrlShiftV, Chord::ShiftInsert] {
+            let codes = x11::keycodes(&conn, chord.keysyms()).unwrap();
+            assert_eq!(codes.len(), chord.keysyms().len());
+            x11::send_chord(chord).unwrap();
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use objc2::MainThreadMarker;
 use tauri::{Emitter, Manager};
 
+use super::{SourceApp, SourceAppProvider};
+
 use super::nspanel::{
     self, CollectionBehavior, EventHandler, PanelExt, PanelLevel, PanelType, StyleMask,
 };
@@ -235,21 +237,85 @@ pub fn platform_hide_preview(app: &tauri::AppHandle) {
     });
 }
 
-/// Write raw image bytes directly to NSPasteboard, bypassing decode→encode cycle.
-/// Reads the PNG file from disk and writes it directly as NSPasteboardTypePNG.
-pub fn platform_write_image_to_pasteboard(path: &str) -> Result<(), String> {
+/// Pasteboard types (UTIs) for image encodings that re-encoding to PNG would degrade.
+/// Checked in order when capturing; GIF first so animations survive.
+const ORIGINAL_IMAGE_TYPES: &[(&str, &str)] = &[
+    ("com.compuserve.gif", "image/gif"),
+    ("public.jpeg", "image/jpeg"),
+    ("org.webmproject.webp", "image/webp"),
+];
+
+/// Read the original encoded image bytes from NSPasteboard, if the source offered
+/// a format worth preserving (GIF/JPEG/WebP). Returns None for PNG/TIFF-only content,
+/// which the clipboard plugin already reads losslessly.
+pub fn platform_read_original_image() -> Option<Vec<u8>> {
+    use objc2_app_kit::NSPasteboard;
+    use objc2_foundation::NSString;
+
+    unsafe {
+        let pasteboard = NSPasteboard::generalPasteboard();
+        ORIGINAL_IMAGE_TYPES.iter().find_map(|(uti, _)| {
+            pasteboard
+                .dataForType(&NSString::from_str(uti))
+                .map(|data| data.to_vec())
+                .filter(|bytes| !bytes.is_empty())
+        })
+    }
+}
+
+/// Whether the source marked the pasteboard content concealed or transient
+/// (nspasteboard.org conventions followed by password managers).
+pub fn platform_clipboard_concealed() -> bool {
+    use objc2_app_kit::NSPasteboard;
+
+    unsafe {
+        let types: Vec<String> = NSPasteboard::generalPasteboard()
+            .types()
+            .map(|types| types.iter().map(|t| t.to_string()).collect())
+            .unwrap_or_default();
+        crate::clipboard::is_concealed(&types)
+    }
+}
+
+/// Write image bytes directly to NSPasteboard, bypassing decode→encode cycle.
+/// Reads the stored original from disk and writes it under its own pasteboard type.
+/// Non-PNG originals also get a PNG representation for apps that only accept PNG.
+pub fn platform_write_image_to_pasteboard(path: &str, mime: &str) -> Result<(), String> {
     let bytes = std::fs::read(path).map_err(|e| format!("Failed to read image file: {}", e))?;
 
+    let original_uti = ORIGINAL_IMAGE_TYPES
+        .iter()
+        .find(|(_, m)| *m == mime)
+        .map(|(uti, _)| *uti);
+    let png_bytes = match original_uti {
+        Some(_) => {
+            let img = image::load_from_memory(&bytes)
+                .map_err(|e| format!("Failed to decode image: {}", e))?;
+            let mut buf = Vec::new();
+            img.write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+                .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+            buf
+        }
+        None => bytes.clone(),
+    };
+
     use objc2::runtime::ProtocolObject;
     use objc2_app_kit::{NSPasteboard, NSPasteboardItem, NSPasteboardTypePNG, NSPasteboardWriting};
-    use objc2_foundation::{NSArray, NSData};
+    use objc2_foundation::{NSArray, NSData, NSString};
 
     unsafe {
         let pasteboard = NSPasteboard::generalPasteboard();
         pasteboard.clearContents();
 
-        let ns_data = NSData::with_bytes(&bytes);
         let item = NSPasteboardItem::new();
+        // Original first: receivers pick the first type they understand
+        if let Some(uti) = original_uti {
+            let original = NSData::with_bytes(&bytes);
+            if !item.setData_forType(&original, &NSString::from_str(uti)) {
+                return Err("Failed to set pasteboard item data".to_string());
+            }
+        }
+        let ns_data = NSData::with_bytes(&png_bytes);
         let set_ok = item.setData_forType(&ns_data, NSPasteboardTypePNG);
         if !set_ok {
             return Err("Failed to set pasteboard item data".to_string());
@@ -287,6 +353,29 @@ pub fn is_recopy_foreground() -> bool {
     false
 }
 
+/// Source apps from `NSWorkspace.frontmostApplication`, identified by bundle id.
+pub struct NativeSourceApps;
+
+impl SourceAppProvider for NativeSourceApps {
+    fn frontmost_app(&self) -> Option<SourceApp> {
+        use objc2_app_kit::NSWorkspace;
+
+        unsafe {
+            let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+            let name = app.localizedName().map(|n| n.to_string());
+            // Command-line tools without a bundle are identified by name
+            let id = app
+                .bundleIdentifier()
+                .map(|id| id.to_string())
+                .or_else(|| name.clone())?;
+            Some(SourceApp {
+                name: name.unwrap_or_else(|| id.clone()),
+                id,
+            })
+        }
+    }
+}
+
 /// Detect which monitor contains the mouse cursor and return its bounds.
 /// Returns (x, y, width, height) in logical coordinates (CG points, top-left origin).
 /// Used by show_main_window() to position the panel on the correct monitor.
@@ -1,6 +1,9 @@
 #[cfg(target_os = "macos")]
 pub mod nspanel;
 
+mod source_app;
+pub use source_app::{SourceApp, SourceAppProvider};
+
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "macos")]
@@ -11,9 +14,24 @@ mod windows;
 #[cfg(target_os = "windows")]
 pub use windows::*;
 
-#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+#[cfg(target_os = "linux")]
+mod linux_paste;
+#[cfg(target_os = "linux")]
+pub(crate) use linux_paste::{detect_session, Session};
+#[cfg(target_os = "linux")]
+pub use linux_paste::{
+    simulate_paste_keys, PasteBackend, PasteConfig, PasteKeys, PASTE_BACKEND_KEY,
+    PASTE_COMMAND_KEY, PASTE_KEYS_KEY,
+};
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::*;
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
 mod fallback;
-#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
 pub use fallback::*;
 
 pub(crate) fn preview_top_inset_for_target(menu_h: f64, reserve_safe_top: bool) -> f64 {
@@ -28,6 +46,11 @@ pub(crate) fn preview_top_inset_for_target(menu_h: f64, reserve_safe_top: bool)
     }
 }
 
+/// Source app provider for the current OS.
+pub fn source_app_provider() -> &'static dyn SourceAppProvider {
+    &NativeSourceApps
+}
+
 pub fn platform_preview_top_inset() -> f64 {
     preview_top_inset_for_target(platform_menu_bar_height(), cfg!(target_os = "macos"))
 }
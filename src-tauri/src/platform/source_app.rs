@@ -0,0 +1,97 @@
+/// The application a clipboard item was copied from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceApp {
+    /// Stable identifier: bundle id on macOS, executable path elsewhere.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+}
+
+impl SourceApp {
+    /// Identify an app by its executable path, named `name` when known and after
+    /// the executable otherwise.
+    pub fn from_executable(path: &str, name: Option<&str>) -> Self {
+        let name = name
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| executable_stem(path).to_string());
+        Self {
+            id: path.to_string(),
+            name,
+        }
+    }
+
+    /// Whether `app` names this app: its id, executable name or display name,
+    /// ignoring case.
+    pub fn matches(&self, app: &str) -> bool {
+        let app = app.trim();
+        !app.is_empty()
+            && [
+                self.id.as_str(),
+                executable_stem(&self.id),
+                self.name.as_str(),
+            ]
+            .iter()
+            .any(|candidate| candidate.eq_ignore_ascii_case(app))
+    }
+}
+
+/// File name without extension, splitting on either path separator.
+fn executable_stem(path: &str) -> &str {
+    let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    match file.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => file,
+    }
+}
+
+/// Identifies the application the user is copying from.
+pub trait SourceAppProvider: Send + Sync {
+    /// The application owning the foreground window, if it can be determined.
+    fn frontmost_app(&self) -> Option<SourceApp>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_executable() {
+        assert_eq!(
+            SourceApp::from_executable("/usr/lib/firefox/firefox", Some("Firefox")),
+            SourceApp {
+                id: "/usr/lib/firefox/firefox".into(),
+                name: "Firefox".into(),
+            }
+        );
+        assert_eq!(
+            SourceApp::from_executable(r"C:\Program Files\Git\git-bash.exe", None).name,
+            "git-bash"
+        );
+        assert_eq!(
+            SourceApp::from_executable("/usr/bin/kitty", Some("  ")).name,
+            "kitty"
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let app = SourceApp::from_executable("/opt/Bitwarden/bitwarden", Some("Bitwarden"));
+        assert!(app.matches("bitwarden"));
+        assert!(app.matches("/opt/Bitwarden/bitwarden"));
+        assert!(!app.matches("Bitwarden Desktop"));
+        assert!(!app.matches(""));
+
+        let app = SourceApp::from_executable(r"C:\Windows\System32\mstsc.exe", None);
+        assert!(app.matches("MSTSC"));
+
+        let app = SourceApp {
+            id: "com.1password.1password".into(),
+            name: "1Password".into(),
+        };
+        assert!(app.matches("com.1password.1password"));
+        assert!(app.matches("1password"));
+        assert!(!SourceApp::default().matches("1password"));
+    }
+}
@@ -4,6 +4,8 @@ use std::sync::{
 };
 use tauri::{Emitter, Manager};
 
+use super::{SourceApp, SourceAppProvider};
+
 // ---------------------------------------------------------------------------
 // Win32 FFI declarations (raw, no external crate dependency)
 // ---------------------------------------------------------------------------
@@ -28,6 +30,13 @@ mod win32 {
     pub const SWP_NOSIZE: u32 = 0x0001;
     pub const SWP_NOACTIVATE: u32 = 0x0010;
 
+    // Clipboard formats / global memory
+    pub const CF_DIB: u32 = 8;
+    pub const GMEM_MOVEABLE: u32 = 0x0002;
+
+    // OpenProcess access right
+    pub const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
     // Hook / messages
     pub const WH_KEYBOARD_LL: i32 = 13;
     pub const WH_MOUSE_LL: i32 = 14;
@@ -214,6 +223,14 @@ mod win32 {
         pub fn GetCurrentProcessId() -> u32;
         pub fn GetCurrentThreadId() -> u32;
         pub fn GetWindowThreadProcessId(hwnd: HWND, pid: *mut u32) -> u32;
+        pub fn OpenProcess(desired_access: u32, inherit_handle: i32, pid: u32) -> isize;
+        pub fn QueryFullProcessImageNameW(
+            process: isize,
+            flags: u32,
+            name: *mut u16,
+            size: *mut u32,
+        ) -> i32;
+        pub fn CloseHandle(handle: isize) -> i32;
         pub fn GetMessageW(msg: *mut MSG, hwnd: HWND, filter_min: u32, filter_max: u32) -> i32;
         pub fn PostThreadMessageW(thread_id: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> i32;
         pub fn SetWindowLongPtrW(hwnd: HWND, index: i32, new_long: isize) -> isize;
@@ -230,6 +247,18 @@ mod win32 {
         pub fn LoadLibraryW(name: *const u16) -> isize;
         pub fn GetProcAddress(hmodule: isize, name: *const u8) -> usize;
         pub fn FreeLibrary(hmodule: isize) -> i32;
+        pub fn OpenClipboard(hwnd_owner: HWND) -> i32;
+        pub fn CloseClipboard() -> i32;
+        pub fn EmptyClipboard() -> i32;
+        pub fn IsClipboardFormatAvailable(format: u32) -> i32;
+        pub fn GetClipboardData(format: u32) -> isize;
+        pub fn SetClipboardData(format: u32, hmem: isize) -> isize;
+        pub fn RegisterClipboardFormatW(name: *const u16) -> u32;
+        pub fn GlobalAlloc(flags: u32, bytes: usize) -> isize;
+        pub fn GlobalLock(hmem: isize) -> *mut u8;
+        pub fn GlobalUnlock(hmem: isize) -> i32;
+        pub fn GlobalSize(hmem: isize) -> usize;
+        pub fn GlobalFree(hmem: isize) -> isize;
     }
 }
 
@@ -305,6 +334,38 @@ fn is_hook_active() -> bool {
     HOOK_HANDLE.load(Ordering::SeqCst) != 0
 }
 
+/// Source apps from the foreground window's process, identified by executable path.
+pub struct NativeSourceApps;
+
+impl SourceAppProvider for NativeSourceApps {
+    fn frontmost_app(&self) -> Option<SourceApp> {
+        unsafe {
+            let fg = win32::GetForegroundWindow();
+            if fg == 0 {
+                return None;
+            }
+            let mut pid: u32 = 0;
+            win32::GetWindowThreadProcessId(fg, &mut pid);
+            if pid == 0 {
+                return None;
+            }
+            let process = win32::OpenProcess(win32::PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if process == 0 {
+                return None;
+            }
+            let mut buf = [0u16; 1024];
+            let mut len = buf.len() as u32;
+            let ok = win32::QueryFullProcessImageNameW(process, 0, buf.as_mut_ptr(), &mut len);
+            win32::CloseHandle(process);
+            if ok == 0 {
+                return None;
+            }
+            let path = String::from_utf16_lossy(&buf[..len as usize]);
+            Some(SourceApp::from_executable(&path, None))
+        }
+    }
+}
+
 /// Check if the current foreground window belongs to the Recopy process.
 #[allow(dead_code)]
 pub fn is_recopy_foreground() -> bool {
@@ -884,6 +945,131 @@ pub fn simulate_paste_keys() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Original-format images (GIF/JPEG/WebP) on the clipboard
+// ---------------------------------------------------------------------------
+
+/// Registered clipboard format names for encodings worth preserving, with their MIME type.
+/// Checked in order when capturing; GIF first so animations survive.
+const ORIGINAL_IMAGE_FORMATS: &[(&str, &str)] = &[
+    ("GIF", "image/gif"),
+    ("JFIF", "image/jpeg"),
+    ("image/webp", "image/webp"),
+];
+
+fn register_clipboard_format(name: &str) -> u32 {
+    let wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe { win32::RegisterClipboardFormatW(wide.as_ptr()) }
+}
+
+/// Read the original encoded image bytes if the source offered GIF/JPEG/WebP.
+/// Returns None for bitmap/PNG-only content, which the clipboard plugin handles.
+pub fn platform_read_original_image() -> Option<Vec<u8>> {
+    unsafe {
+        if win32::OpenClipboard(0) == 0 {
+            return None;
+        }
+        let bytes = ORIGINAL_IMAGE_FORMATS.iter().find_map(|(name, _)| {
+            let format = register_clipboard_format(name);
+            if format == 0 || win32::IsClipboardFormatAvailable(format) == 0 {
+                return None;
+            }
+            let hmem = win32::GetClipboardData(format);
+            if hmem == 0 {
+                return None;
+            }
+            let ptr = win32::GlobalLock(hmem);
+            if ptr.is_null() {
+                return None;
+            }
+            let data = std::slice::from_raw_parts(ptr, win32::GlobalSize(hmem)).to_vec();
+            win32::GlobalUnlock(hmem);
+            Some(data).filter(|d| !d.is_empty())
+        });
+        win32::CloseClipboard();
+        bytes
+    }
+}
+
+/// Whether the source excluded the clipboard content from monitoring, as password
+/// managers do for secrets.
+pub fn platform_clipboard_concealed() -> bool {
+    let format = register_clipboard_format("ExcludeClipboardContentFromMonitorProcessing");
+    format != 0 && unsafe { win32::IsClipboardFormatAvailable(format) } != 0
+}
+
+/// Encode an image as a packed CF_DIB (BITMAPINFOHEADER + bottom-up 32-bit BGRA rows).
+fn encode_dib(img: &image::DynamicImage) -> Vec<u8> {
+    let rgba = img.to_rgba8();
+    let (w, h) = rgba.dimensions();
+    let mut buf = Vec::with_capacity(40 + (w * h * 4) as usize);
+    buf.extend_from_slice(&40u32.to_le_bytes()); // biSize
+    buf.extend_from_slice(&(w as i32).to_le_bytes()); // biWidth
+    buf.extend_from_slice(&(h as i32).to_le_bytes()); // biHeight (positive = bottom-up)
+    buf.extend_from_slice(&1u16.to_le_bytes()); // biPlanes
+    buf.extend_from_slice(&32u16.to_le_bytes()); // biBitCount
+    buf.extend_from_slice(&[0u8; 24]); // BI_RGB, size, resolution, palette fields
+    for row in rgba.rows().rev() {
+        for px in row {
+            buf.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+        }
+    }
+    buf
+}
+
+/// Copy bytes into a movable global block and hand it to the clipboard.
+/// Must be called between OpenClipboard/CloseClipboard.
+unsafe fn set_clipboard_bytes(format: u32, bytes: &[u8]) -> bool {
+    let hmem = win32::GlobalAlloc(win32::GMEM_MOVEABLE, bytes.len());
+    if hmem == 0 {
+        return false;
+    }
+    let ptr = win32::GlobalLock(hmem);
+    if ptr.is_null() {
+        win32::GlobalFree(hmem);
+        return false;
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+    win32::GlobalUnlock(hmem);
+    // On success the system owns the memory
+    if win32::SetClipboardData(format, hmem) == 0 {
+        win32::GlobalFree(hmem);
+        return false;
+    }
+    true
+}
+
+/// Write a stored original image to the clipboard in a single session:
+/// its own registered format plus PNG and CF_DIB for apps that only take bitmaps.
+/// One session keeps the monitor to a single change event.
+pub fn platform_write_image_to_clipboard(path: &str, mime: &str) -> Result<(), String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read image file: {}", e))?;
+    let img =
+        image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+    let mut png = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    let dib = encode_dib(&img);
+
+    unsafe {
+        if win32::OpenClipboard(0) == 0 {
+            return Err("Failed to open clipboard".to_string());
+        }
+        win32::EmptyClipboard();
+        let mut ok = set_clipboard_bytes(win32::CF_DIB, &dib);
+        ok &= set_clipboard_bytes(register_clipboard_format("PNG"), &png);
+        if let Some((name, _)) = ORIGINAL_IMAGE_FORMATS.iter().find(|(_, m)| *m == mime) {
+            ok &= set_clipboard_bytes(register_clipboard_format(name), &bytes);
+        }
+        win32::CloseClipboard();
+        if !ok {
+            return Err("Failed to set clipboard data".to_string());
+        }
+    }
+
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Foreground restore
 // ---------------------------------------------------------------------------
@@ -0,0 +1,332 @@
+use image::DynamicImage;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+mod render;
+
+/// Widths (px) rendered into the cache, smallest first.
+pub const THUMBNAIL_SIZES: &[u32] = &[200, 400, 800];
+
+/// Width served when the caller doesn't ask for a specific size (card thumbnails).
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 400;
+
+/// JPEG quality for opaque thumbnails.
+const JPEG_QUALITY: u8 = 80;
+
+/// File extensions the renderer backends handle; files with these are previewed
+/// and thumbnailed as images.
+pub const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "bmp", "ico", "svg", "svgz", "tif", "tiff", "avif", "tga",
+    "qoi", "pnm", "pbm", "pgm", "ppm", "hdr", "exr", "dds",
+];
+
+/// Whether a file path has one of the `IMAGE_EXTENSIONS` (case-insensitive).
+pub fn is_image_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// A rendered thumbnail ready to be written to the cache.
+pub struct Thumbnail {
+    pub size: u32,
+    pub ext: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+/// Directory holding cached thumbnails for a content hash: `thumbnails/{hash[0..2]}/`.
+fn shard_dir(app_data_dir: &Path, hash: &str) -> PathBuf {
+    app_data_dir
+        .join("thumbnails")
+        .join(hash.get(..2).unwrap_or("00"))
+}
+
+/// Cache location of one rendition: `thumbnails/{hash[0..2]}/{hash}@{size}.{ext}`.
+pub fn thumbnail_path(app_data_dir: &Path, hash: &str, size: u32, ext: &str) -> PathBuf {
+    shard_dir(app_data_dir, hash).join(format!("{}@{}.{}", hash, size, ext))
+}
+
+/// Decode source bytes with the matching renderer backend. `ext` is the source
+/// file extension when known; it helps pick a backend for formats without magic
+/// bytes. Vector images are rasterized at the largest cached size.
+pub fn decode_image(image_data: &[u8], ext: Option<&str>) -> Result<DynamicImage, String> {
+    let largest = THUMBNAIL_SIZES
+        .last()
+        .copied()
+        .unwrap_or(DEFAULT_THUMBNAIL_SIZE);
+    render::decode(image_data, ext, largest)
+}
+
+/// Render the cached sizes from encoded image bytes (see `decode_image`).
+pub fn render_thumbnails(image_data: &[u8], ext: Option<&str>) -> Result<Vec<Thumbnail>, String> {
+    render_image_thumbnails(&decode_image(image_data, ext)?)
+}
+
+/// Render the cached sizes from a decoded image.
+/// Images are never upscaled: sizes wider than the source collapse into one
+/// rendition at the source width. Opaque images are stored as JPEG, images
+/// with transparency as lossless WebP.
+pub fn render_image_thumbnails(img: &DynamicImage) -> Result<Vec<Thumbnail>, String> {
+    let (w, h) = (img.width(), img.height());
+    let has_alpha = img.color().has_alpha();
+    let mut out = Vec::new();
+
+    for &size in THUMBNAIL_SIZES {
+        let thumb = if w > size {
+            let new_height = ((size as f64 / w as f64 * h as f64) as u32).max(1);
+            img.resize(size, new_height, image::imageops::FilterType::Lanczos3)
+        } else {
+            img.clone()
+        };
+
+        let mut buf = Vec::new();
+        let ext = if has_alpha {
+            let encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut buf);
+            thumb
+                .write_with_encoder(encoder)
+                .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+            "webp"
+        } else {
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
+                Cursor::new(&mut buf),
+                JPEG_QUALITY,
+            );
+            thumb
+                .to_rgb8()
+                .write_with_encoder(encoder)
+                .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+            "jpg"
+        };
+        out.push(Thumbnail {
+            size,
+            ext,
+            bytes: buf,
+        });
+
+        // Larger sizes would be identical copies of the source
+        if w <= size {
+            break;
+        }
+    }
+
+    Ok(out)
+}
+
+/// Render and write all cached sizes for a content hash, replacing any previous renditions.
+pub fn write_thumbnails(
+    app_data_dir: &Path,
+    hash: &str,
+    image_data: &[u8],
+    ext: Option<&str>,
+) -> Result<(), String> {
+    write_image_thumbnails(app_data_dir, hash, &decode_image(image_data, ext)?)
+}
+
+/// Write all cached sizes of an already decoded image, replacing any previous renditions.
+pub fn write_image_thumbnails(
+    app_data_dir: &Path,
+    hash: &str,
+    img: &DynamicImage,
+) -> Result<(), String> {
+    let thumbs = render_image_thumbnails(img)?;
+
+    remove_thumbnails(app_data_dir, hash);
+    let dir = shard_dir(app_data_dir, hash);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnail dir: {}", e))?;
+
+    for thumb in thumbs {
+        let path = thumbnail_path(app_data_dir, hash, thumb.size, thumb.ext);
+        let tmp = path.with_extension(format!("{}.tmp", thumb.ext));
+        std::fs::write(&tmp, &thumb.bytes)
+            .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to move thumbnail: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// List cached renditions for a hash as (size, path), sorted by size.
+fn cached_renditions(app_data_dir: &Path, hash: &str) -> Vec<(u32, PathBuf)> {
+    let prefix = format!("{}@", hash);
+    let mut found: Vec<(u32, PathBuf)> = std::fs::read_dir(shard_dir(app_data_dir, hash))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (size, ext) = name.strip_prefix(&prefix)?.split_once('.')?;
+            if ext != "jpg" && ext != "webp" {
+                return None;
+            }
+            Some((size.parse().ok()?, entry.path()))
+        })
+        .collect();
+    found.sort_by_key(|(size, _)| *size);
+    found
+}
+
+/// Find the best cached rendition for a requested width: the smallest one at least
+/// that wide, otherwise the largest available. Returns the path and its MIME type.
+pub fn find_thumbnail(
+    app_data_dir: &Path,
+    hash: &str,
+    size: u32,
+) -> Option<(PathBuf, &'static str)> {
+    let renditions = cached_renditions(app_data_dir, hash);
+    let path = renditions
+        .iter()
+        .find(|(s, _)| *s >= size)
+        .or(renditions.last())
+        .map(|(_, p)| p.clone())?;
+    let mime = if path.extension().is_some_and(|e| e == "webp") {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    };
+    Some((path, mime))
+}
+
+/// Remove every cached rendition of a hash (best-effort).
+pub fn remove_thumbnails(app_data_dir: &Path, hash: &str) {
+    for (_, path) in cached_renditions(app_data_dir, hash) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Remove cached thumbnails whose hash is not in `known_hashes`. Returns the number of files removed.
+pub fn remove_orphan_thumbnails(
+    app_data_dir: &Path,
+    known_hashes: &std::collections::HashSet<String>,
+) -> u32 {
+    let Ok(shards) = std::fs::read_dir(app_data_dir.join("thumbnails")) else {
+        return 0;
+    };
+
+    let mut removed = 0u32;
+    for shard in shards.flatten() {
+        let Ok(files) = std::fs::read_dir(shard.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let name = file.file_name().to_string_lossy().to_string();
+            let hash = name.split('@').next().unwrap_or_default();
+            if !known_hashes.contains(hash) && std::fs::remove_file(file.path()).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_png(img: image::DynamicImage) -> Vec<u8> {
+        let mut buf = Vec::new();
+        img.write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)
+            .unwrap();
+        buf
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("recopy-test-thumbs-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_is_image_file_uses_renderer_extensions() {
+        assert!(is_image_file("/home/me/logo.svg"));
+        assert!(is_image_file("/home/me/scan.AVIF"));
+        assert!(!is_image_file("/home/me/notes.txt"));
+        assert!(!is_image_file("/home/me/svg"));
+    }
+
+    #[test]
+    fn test_render_thumbnails_sizes() {
+        let img = image::RgbImage::from_fn(1000, 500, |_, _| image::Rgb([255u8, 0, 0]));
+        let thumbs =
+            render_thumbnails(&encode_png(image::DynamicImage::ImageRgb8(img)), None).unwrap();
+
+        let sizes: Vec<u32> = thumbs.iter().map(|t| t.size).collect();
+        assert_eq!(sizes, vec![200, 400, 800]);
+        assert!(thumbs.iter().all(|t| t.ext == "jpg"));
+
+        let t400 = image::load_from_memory(&thumbs[1].bytes).unwrap();
+        assert_eq!(t400.width(), 400);
+        assert_eq!(t400.height(), 200);
+    }
+
+    #[test]
+    fn test_render_thumbnails_small_image_no_upscale() {
+        let img = image::RgbImage::from_fn(300, 100, |_, _| image::Rgb([0u8, 255, 0]));
+        let thumbs =
+            render_thumbnails(&encode_png(image::DynamicImage::ImageRgb8(img)), None).unwrap();
+
+        // 200px rendition + one at the source width; no 800px copy
+        assert_eq!(thumbs.len(), 2);
+        let full = image::load_from_memory(&thumbs[1].bytes).unwrap();
+        assert_eq!((full.width(), full.height()), (300, 100));
+    }
+
+    #[test]
+    fn test_render_thumbnails_keeps_alpha_as_webp() {
+        let img = image::RgbaImage::from_fn(50, 50, |_, _| image::Rgba([0u8, 0, 255, 128]));
+        let thumbs =
+            render_thumbnails(&encode_png(image::DynamicImage::ImageRgba8(img)), None).unwrap();
+        assert_eq!(thumbs.len(), 1);
+        assert_eq!(thumbs[0].ext, "webp");
+        let decoded = image::load_from_memory(&thumbs[0].bytes).unwrap();
+        assert!(decoded.color().has_alpha());
+    }
+
+    #[test]
+    fn test_render_thumbnails_invalid_data() {
+        assert!(render_thumbnails(b"not an image", None).is_err());
+        assert!(render_thumbnails(&[], None).is_err());
+    }
+
+    #[test]
+    fn test_write_find_and_remove_thumbnails() {
+        let dir = temp_dir("cache");
+        let img = image::RgbImage::from_fn(600, 300, |_, _| image::Rgb([9u8, 9, 9]));
+        let data = encode_png(image::DynamicImage::ImageRgb8(img));
+
+        write_thumbnails(&dir, "abcdef", &data, None).unwrap();
+        assert!(thumbnail_path(&dir, "abcdef", 200, "jpg").exists());
+
+        // Exact, in-between and oversized requests
+        let (p, mime) = find_thumbnail(&dir, "abcdef", 400).unwrap();
+        assert!(p.ends_with("abcdef@400.jpg"));
+        assert_eq!(mime, "image/jpeg");
+        let (p, _) = find_thumbnail(&dir, "abcdef", 300).unwrap();
+        assert!(p.ends_with("abcdef@400.jpg"));
+        let (p, _) = find_thumbnail(&dir, "abcdef", 2000).unwrap();
+        assert!(p.ends_with("abcdef@800.jpg"));
+
+        assert!(find_thumbnail(&dir, "missing", 400).is_none());
+
+        remove_thumbnails(&dir, "abcdef");
+        assert!(find_thumbnail(&dir, "abcdef", 400).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_remove_orphan_thumbnails() {
+        let dir = temp_dir("orphans");
+        let img = image::RgbImage::from_fn(10, 10, |_, _| image::Rgb([1u8, 2, 3]));
+        let data = encode_png(image::DynamicImage::ImageRgb8(img));
+        write_thumbnails(&dir, "keep01", &data, None).unwrap();
+        write_thumbnails(&dir, "drop01", &data, None).unwrap();
+
+        let known = std::collections::HashSet::from(["keep01".to_string()]);
+        assert_eq!(remove_orphan_thumbnails(&dir, &known), 1);
+        assert!(find_thumbnail(&dir, "keep01", 200).is_some());
+        assert!(find_thumbnail(&dir, "drop01", 200).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,231 @@
+use image::DynamicImage;
+use std::sync::{Arc, OnceLock};
+
+/// Largest SVG side (px) we rasterize, guards against extreme aspect ratios.
+const MAX_SVG_SIDE: u32 = 4096;
+
+/// A backend that turns encoded source bytes into a raster image for the thumbnail cache.
+pub trait ThumbnailRenderer: Send + Sync {
+    /// Short backend name, used in logs and errors.
+    fn name(&self) -> &'static str;
+
+    /// Whether this backend handles the data. `ext` is the lowercase source file
+    /// extension when known (files), `None` for raw clipboard bytes.
+    fn can_render(&self, data: &[u8], ext: Option<&str>) -> bool;
+
+    /// Decode the data. Vector backends rasterize at `width`; raster backends ignore it.
+    fn render(&self, data: &[u8], ext: Option<&str>, width: u32) -> Result<DynamicImage, String>;
+}
+
+/// Raster formats supported by the `image` crate (PNG, JPEG, GIF, WebP, BMP, ICO,
+/// TIFF, TGA, QOI, PNM, HDR, EXR, DDS, and AVIF with the `avif` feature).
+pub struct RasterRenderer;
+
+impl RasterRenderer {
+    fn format(data: &[u8], ext: Option<&str>) -> Option<image::ImageFormat> {
+        // Content sniffing first; extension for formats without magic bytes (TGA)
+        image::guess_format(data)
+            .ok()
+            .or_else(|| ext.and_then(image::ImageFormat::from_extension))
+    }
+}
+
+impl ThumbnailRenderer for RasterRenderer {
+    fn name(&self) -> &'static str {
+        "raster"
+    }
+
+    fn can_render(&self, data: &[u8], ext: Option<&str>) -> bool {
+        Self::format(data, ext).is_some_and(|f| f.reading_enabled())
+    }
+
+    fn render(&self, data: &[u8], ext: Option<&str>, _width: u32) -> Result<DynamicImage, String> {
+        match Self::format(data, ext) {
+            Some(format) => image::load_from_memory_with_format(data, format),
+            None => image::load_from_memory(data),
+        }
+        .map_err(|e| format!("Failed to load image: {}", e))
+    }
+}
+
+/// SVG/SVGZ rasterized with resvg.
+pub struct SvgRenderer;
+
+impl SvgRenderer {
+    fn looks_like_svg(data: &[u8]) -> bool {
+        let head = &data[..data.len().min(1024)];
+        let text = String::from_utf8_lossy(head);
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        (text.starts_with("<svg") || text.starts_with("<?xml") || text.starts_with("<!DOCTYPE svg"))
+            && text.contains("<svg")
+    }
+
+    /// System fonts for `<text>` elements, loaded once.
+    fn fontdb() -> Arc<resvg::usvg::fontdb::Database> {
+        static FONTS: OnceLock<Arc<resvg::usvg::fontdb::Database>> = OnceLock::new();
+        FONTS
+            .get_or_init(|| {
+                let mut db = resvg::usvg::fontdb::Database::new();
+                db.load_system_fonts();
+                Arc::new(db)
+            })
+            .clone()
+    }
+}
+
+impl ThumbnailRenderer for SvgRenderer {
+    fn name(&self) -> &'static str {
+        "svg"
+    }
+
+    fn can_render(&self, data: &[u8], ext: Option<&str>) -> bool {
+        matches!(ext, Some("svg" | "svgz")) || Self::looks_like_svg(data)
+    }
+
+    fn render(&self, data: &[u8], _ext: Option<&str>, width: u32) -> Result<DynamicImage, String> {
+        use resvg::{tiny_skia, usvg};
+
+        let opts = usvg::Options {
+            fontdb: Self::fontdb(),
+            // Never follow external references from clipboard content
+            image_href_resolver: usvg::ImageHrefResolver {
+                resolve_data: usvg::ImageHrefResolver::default_data_resolver(),
+                resolve_string: Box::new(|_, _| None),
+            },
+            ..Default::default()
+        };
+        let tree = usvg::Tree::from_data(data, &opts)
+            .map_err(|e| format!("Failed to parse SVG: {}", e))?;
+
+        let size = tree.size();
+        let mut scale = width as f32 / size.width();
+        if size.height() * scale > MAX_SVG_SIDE as f32 {
+            scale = MAX_SVG_SIDE as f32 / size.height();
+        }
+        let w = ((size.width() * scale).round() as u32).max(1);
+        let h = ((size.height() * scale).round() as u32).max(1);
+
+        let mut pixmap =
+            tiny_skia::Pixmap::new(w, h).ok_or_else(|| "Invalid SVG size".to_string())?;
+        resvg::render(
+            &tree,
+            tiny_skia::Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+
+        // tiny-skia stores premultiplied alpha
+        let rgba: Vec<u8> = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|p| {
+                let c = p.demultiply();
+                [c.red(), c.green(), c.blue(), c.alpha()]
+            })
+            .collect();
+        image::RgbaImage::from_raw(w, h, rgba)
+            .map(DynamicImage::ImageRgba8)
+            .ok_or_else(|| "Failed to convert SVG raster".to_string())
+    }
+}
+
+/// Registered backends, tried in order.
+static RENDERERS: &[&dyn ThumbnailRenderer] = &[&SvgRenderer, &RasterRenderer];
+
+/// Decode source bytes with the first backend that accepts them.
+pub fn decode(data: &[u8], ext: Option<&str>, width: u32) -> Result<DynamicImage, String> {
+    let ext = ext.map(|e| e.to_ascii_lowercase());
+    let renderer = RENDERERS
+        .iter()
+        .find(|r| r.can_render(data, ext.as_deref()))
+        .ok_or_else(|| "Failed to load image: unsupported format".to_string())?;
+    renderer
+        .render(data, ext.as_deref(), width)
+        .map_err(|e| format!("{} ({})", e, renderer.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16x8 image: left half red, right half blue.
+    fn assert_two_tone(img: &DynamicImage) {
+        let rgba = img.to_rgba8();
+        let (w, h) = rgba.dimensions();
+        let left = rgba.get_pixel(w / 4, h / 2);
+        let right = rgba.get_pixel(w * 3 / 4, h / 2);
+        assert!(left[0] > 150 && left[2] < 100, "left {:?}", left);
+        assert!(right[2] > 150 && right[0] < 100, "right {:?}", right);
+    }
+
+    #[test]
+    fn test_raster_fixtures() {
+        let fixtures: &[(&str, &[u8])] = &[
+            ("tiff", include_bytes!("fixtures/sample.tiff")),
+            ("bmp", include_bytes!("fixtures/sample.bmp")),
+            ("ico", include_bytes!("fixtures/sample.ico")),
+            ("gif", include_bytes!("fixtures/sample.gif")),
+            ("qoi", include_bytes!("fixtures/sample.qoi")),
+            ("tga", include_bytes!("fixtures/sample.tga")),
+        ];
+        for (ext, data) in fixtures {
+            assert!(RasterRenderer.can_render(data, Some(ext)), "{}", ext);
+            assert!(!SvgRenderer.can_render(data, Some(ext)), "{}", ext);
+            let img = decode(data, Some(ext), 800).unwrap_or_else(|e| panic!("{}: {}", ext, e));
+            assert_eq!((img.width(), img.height()), (16, 8), "{}", ext);
+            assert_two_tone(&img);
+        }
+    }
+
+    #[test]
+    fn test_raster_sniffs_without_extension() {
+        let tiff = include_bytes!("fixtures/sample.tiff");
+        assert!(decode(tiff, None, 800).is_ok());
+        // TGA has no magic bytes: only the extension identifies it
+        let tga = include_bytes!("fixtures/sample.tga");
+        assert!(!RasterRenderer.can_render(tga, None));
+        assert!(decode(tga, Some("TGA"), 800).is_ok());
+    }
+
+    #[test]
+    fn test_svg_fixture_rasterized_at_width() {
+        let svg = include_bytes!("fixtures/sample.svg");
+        assert!(SvgRenderer.can_render(svg, None));
+        let img = decode(svg, None, 800).unwrap();
+        // 40x20 viewBox scaled up to the requested width
+        assert_eq!((img.width(), img.height()), (800, 400));
+        assert_two_tone(&img);
+        // Outside the circle stays transparent
+        assert_eq!(img.to_rgba8().get_pixel(799, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_svgz_fixture() {
+        let svgz = include_bytes!("fixtures/sample.svgz");
+        assert!(SvgRenderer.can_render(svgz, Some("svgz")));
+        let img = decode(svgz, Some("svgz"), 200).unwrap();
+        assert_eq!((img.width(), img.height()), (200, 100));
+        assert_two_tone(&img);
+    }
+
+    #[test]
+    fn test_svg_tall_aspect_is_capped() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="1000"/>"#;
+        let img = decode(svg, Some("svg"), 800).unwrap();
+        assert_eq!(img.height(), MAX_SVG_SIDE);
+        assert!(img.width() < 800);
+    }
+
+    #[test]
+    fn test_svg_ignores_external_images() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="10" height="10"><image xlink:href="/etc/passwd" width="10" height="10"/></svg>"#;
+        let img = decode(svg, None, 10).unwrap();
+        assert!(img.to_rgba8().pixels().all(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn test_unsupported_data() {
+        assert!(decode(b"<html><body>hi</body></html>", None, 200).is_err());
+        assert!(decode(b"<svg broken", Some("svg"), 200).is_err());
+        assert!(decode(&[0u8; 16], Some("xyz"), 200).is_err());
+    }
+}
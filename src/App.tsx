@@ -13,10 +13,15 @@ import { ViewTabs } from "./components/ViewTabs";
 import { ClipboardList } from "./components/ClipboardList";
 import { SettingsPage } from "./components/SettingsPage";
 import { UpdateBanner } from "./components/UpdateBanner";
+import { CapturePauseButton } from "./components/CapturePauseButton";
+import { UnlockPrompt } from "./components/UnlockPrompt";
 import { useClipboardStore } from "./stores/clipboard-store";
 import { useSettingsStore, type ShowEventPayload } from "./stores/settings-store";
 import { useUpdateStore } from "./stores/update-store";
+import { useCaptureStore } from "./stores/capture-store";
+import { useLockStore } from "./stores/lock-store";
 import { useKeyboardNav } from "./hooks/useKeyboardNav";
+import type { CaptureStatus } from "./lib/types";
 
 // Expose update store to devtools console for UI testing
 if (import.meta.env.DEV) {
@@ -50,6 +55,11 @@ function MainApp() {
   const settingsLoaded = useSettingsStore((s) => s.loaded);
   const updateCheckInterval = useSettingsStore((s) => s.settings.update_check_interval);
   const checkForUpdate = useUpdateStore((s) => s.checkForUpdate);
+  const fetchCaptureStatus = useCaptureStore((s) => s.fetchStatus);
+  const syncCaptureStatus = useCaptureStore((s) => s.syncStatus);
+  const locked = useLockStore((s) => s.locked);
+  const fetchLockStatus = useLockStore((s) => s.fetchStatus);
+  const markLocked = useLockStore((s) => s.markLocked);
   const panelRef = useRef<HTMLDivElement>(null);
 
   // Keyboard navigation
@@ -85,6 +95,32 @@ function MainApp() {
     };
   }, [refreshOnChange]);
 
+  // Capture pause lives in the backend; follow it from any window or the auto-resume timer
+  useEffect(() => {
+    fetchCaptureStatus();
+    const unlistenPaused = listen<CaptureStatus>("capture-paused", (event) => {
+      syncCaptureStatus(event.payload);
+    });
+    const unlistenResumed = listen("capture-resumed", () => {
+      syncCaptureStatus({ paused: false, resumes_at: null });
+    });
+    return () => {
+      unlistenPaused.then((fn) => fn());
+      unlistenResumed.then((fn) => fn());
+    };
+  }, [fetchCaptureStatus, syncCaptureStatus]);
+
+  // The backend re-locks after the idle timeout or on "Lock now"; hide the history until unlocked
+  useEffect(() => {
+    fetchLockStatus();
+    const unlisten = listen("app-locked", () => {
+      markLocked();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [fetchLockStatus, markLocked]);
+
   // Dismiss context menus on blur (always needed regardless of close_on_blur setting).
   // Use window-scoped listener so other windows (settings) don't trigger this.
   useEffect(() => {
@@ -125,6 +161,8 @@ function MainApp() {
   useEffect(() => {
     const unlisten = listen<ShowEventPayload>("recopy-show", async (event) => {
       syncSettingsFromEvent(event.payload);
+      fetchCaptureStatus();
+      fetchLockStatus();
       await onPanelShow();
 
       // Wait for React re-render + useLayoutEffect (scroll) to complete before animating.
@@ -150,7 +188,7 @@ function MainApp() {
     return () => {
       unlisten.then((fn) => fn());
     };
-  }, [onPanelShow, syncSettingsFromEvent]);
+  }, [onPanelShow, syncSettingsFromEvent, fetchCaptureStatus, fetchLockStatus]);
 
   const panelPosition = useSettingsStore((s) => s.settings.panel_position);
   const menuBarHeight = useSettingsStore((s) => s.menuBarHeight);
@@ -182,11 +220,14 @@ function MainApp() {
               </span>
               <div className="flex-1" />
               <ViewTabs />
+              <span className="ml-1">
+                <CapturePauseButton />
+              </span>
               <Button
                 variant="ghost"
                 size="icon-sm"
                 onClick={() => invoke("open_settings_window")}
-                className="ml-1 text-muted-foreground hover:text-foreground hover:bg-overlay"
+                className="text-muted-foreground hover:text-foreground hover:bg-overlay"
               >
                 <Settings size={16} />
               </Button>
@@ -205,14 +246,17 @@ function MainApp() {
             <ViewTabs />
             <SearchBar />
             <TypeFilter />
-            <Button
-              variant="ghost"
-              size="icon-sm"
-              onClick={() => invoke("open_settings_window")}
-              className="absolute right-4 text-muted-foreground hover:text-foreground hover:bg-overlay"
-            >
-              <Settings size={16} />
-            </Button>
+            <div className="absolute right-4 flex items-center gap-1">
+              <CapturePauseButton openUp={isTop} />
+              <Button
+                variant="ghost"
+                size="icon-sm"
+                onClick={() => invoke("open_settings_window")}
+                className="text-muted-foreground hover:text-foreground hover:bg-overlay"
+              >
+                <Settings size={16} />
+              </Button>
+            </div>
           </div>
         )}
 
@@ -221,7 +265,7 @@ function MainApp() {
           className="flex-1 min-h-0 pb-1"
           style={isTop && menuBarHeight > 0 ? { paddingTop: menuBarHeight } : undefined}
         >
-          <ClipboardList />
+          {locked ? <UnlockPrompt /> : <ClipboardList />}
         </div>
       </div>
     </div>
@@ -0,0 +1,85 @@
+import { useEffect, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Pause, Play } from "lucide-react";
+import { useCaptureStore } from "../stores/capture-store";
+import { Button } from "./ui/button";
+
+const PAUSE_OPTIONS = [
+  { secs: 15 * 60, label: "capture.pause15m" },
+  { secs: 60 * 60, label: "capture.pause1h" },
+  { secs: null, label: "capture.pauseUntilResumed" },
+] as const;
+
+/** Pause clipboard capture for a while (screen sharing, entering credentials), or resume it. */
+export function CapturePauseButton({ openUp = false }: { openUp?: boolean }) {
+  const { t } = useTranslation();
+  const paused = useCaptureStore((s) => s.paused);
+  const resumesAt = useCaptureStore((s) => s.resumesAt);
+  const pause = useCaptureStore((s) => s.pause);
+  const resume = useCaptureStore((s) => s.resume);
+  const [open, setOpen] = useState(false);
+  const menuRef = useRef<HTMLDivElement>(null);
+
+  // Close the duration menu on any click outside it
+  useEffect(() => {
+    if (!open) return;
+    const onPointerDown = (e: PointerEvent) => {
+      if (!menuRef.current?.contains(e.target as Node)) setOpen(false);
+    };
+    document.addEventListener("pointerdown", onPointerDown);
+    return () => document.removeEventListener("pointerdown", onPointerDown);
+  }, [open]);
+
+  if (paused) {
+    const until = resumesAt
+      ? new Date(resumesAt + "Z").toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" })
+      : null;
+    return (
+      <Button
+        variant="ghost"
+        size="sm"
+        onClick={resume}
+        title={t("capture.resume")}
+        className="h-7 px-2 text-xs text-primary hover:bg-overlay"
+      >
+        <Play size={13} />
+        {until ? t("capture.pausedUntil", { time: until }) : t("capture.paused")}
+      </Button>
+    );
+  }
+
+  return (
+    <div ref={menuRef} className="relative">
+      <Button
+        variant="ghost"
+        size="icon-sm"
+        onClick={() => setOpen((o) => !o)}
+        title={t("capture.pause")}
+        aria-label={t("capture.pause")}
+        className="text-muted-foreground hover:text-foreground hover:bg-overlay"
+      >
+        <Pause size={16} />
+      </Button>
+      {open && (
+        <div
+          className={`absolute right-0 z-50 min-w-[9rem] rounded-md border border-border/50 bg-popover text-popover-foreground p-1 shadow-md ${
+            openUp ? "bottom-full mb-1" : "top-full mt-1"
+          }`}
+        >
+          {PAUSE_OPTIONS.map(({ secs, label }) => (
+            <button
+              key={label}
+              onClick={() => {
+                setOpen(false);
+                pause(secs);
+              }}
+              className="w-full text-left rounded-sm px-2 py-1.5 text-xs hover:bg-overlay cursor-pointer"
+            >
+              {t(label)}
+            </button>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
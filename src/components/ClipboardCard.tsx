@@ -28,6 +28,10 @@ export function ClipboardCard({ item, selected, onClick, quickIndex }: Clipboard
   };
 
   const card = (() => {
+    // Masked secrets carry no content to render in the type-specific cards
+    if (item.is_sensitive && item.content_type !== "image" && item.content_type !== "file") {
+      return <TextCard item={item} selected={selected} onClick={onClick} />;
+    }
     switch (item.content_type) {
       case "plain_text":
         return <TextCard item={item} selected={selected} onClick={onClick} />;
@@ -22,6 +22,17 @@ const IMAGE_EXTS = new Set([
   "ico",
   "tiff",
   "tif",
+  "svgz",
+  "avif",
+  "tga",
+  "qoi",
+  "pnm",
+  "pbm",
+  "pgm",
+  "ppm",
+  "hdr",
+  "exr",
+  "dds",
 ]);
 
 export function FileCard({ item, selected, onClick }: FileCardProps) {
@@ -43,6 +43,9 @@ export function ImageCard({ item, selected, onClick }: ImageCardProps) {
         )}
       </div>
       <div className="flex items-center justify-end text-sm text-muted-foreground mt-auto pt-1.5">
+        {item.variant_count > 0 && (
+          <span className="mr-auto">{t("card.variants", { count: item.variant_count })}</span>
+        )}
         <span>{relativeTime(item.updated_at)}</span>
       </div>
     </div>
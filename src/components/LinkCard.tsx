@@ -85,6 +85,7 @@ export function LinkCard({ item, selected, onClick }: LinkCardProps) {
         {url}
       </pre>
       <div className="flex items-center justify-end text-sm text-muted-foreground mt-auto pt-1.5">
+        {item.selection === "primary" && <span className="mr-auto">{t("card.selection")}</span>}
         <span>{relativeTime(item.updated_at)}</span>
       </div>
     </div>
@@ -4,7 +4,7 @@ import { convertFileSrc } from "@tauri-apps/api/core";
 import DOMPurify from "dompurify";
 import { useSettingsStore } from "../stores/settings-store";
 import { ImageIcon, File } from "lucide-react";
-import type { ItemDetail, PreviewResponse, FilePreviewData } from "../lib/types";
+import type { ItemDetail, ImageMetadata, PreviewResponse, FilePreviewData } from "../lib/types";
 
 export function PreviewPage() {
   const [detail, setDetail] = useState<ItemDetail | null>(null);
@@ -86,7 +86,11 @@ function PreviewContent({ detail }: { detail: ItemDetail }) {
       );
     case "image":
       return (
-        <WithTitleBar title={getTitle(detail)} size={detail.content_size}>
+        <WithTitleBar
+          title={getTitle(detail)}
+          size={detail.content_size}
+          subtitle={detail.image_metadata && formatImageMetadata(detail.image_metadata)}
+        >
           <ImagePreview imagePath={detail.image_path} />
         </WithTitleBar>
       );
@@ -119,16 +123,23 @@ function PreviewContent({ detail }: { detail: ItemDetail }) {
 function WithTitleBar({
   title,
   size,
+  subtitle,
   children,
 }: {
   title: string;
   size: number;
+  subtitle?: string;
   children: React.ReactNode;
 }) {
   return (
     <div className="flex flex-col w-full h-full">
       <div className="shrink-0 flex items-center px-3 py-1.5">
         <span className="text-xs font-medium text-foreground/70 truncate flex-1">{title}</span>
+        {subtitle && (
+          <span className="text-xs text-muted-foreground truncate ml-2" title={subtitle}>
+            {subtitle}
+          </span>
+        )}
         <span className="text-xs text-muted-foreground shrink-0 ml-2">{formatSize(size)}</span>
       </div>
       <div className="flex-1 min-h-0 overflow-hidden px-2 pb-2 rounded-b-xl">{children}</div>
@@ -136,6 +147,17 @@ function WithTitleBar({
   );
 }
 
+/** "4000×3000 · JPEG · Canon EOS R5 · 2024-05-06 07:08 · 35.67500, 139.75000" */
+function formatImageMetadata(meta: ImageMetadata): string {
+  const parts = [`${meta.width}×${meta.height}`, meta.format.toUpperCase()];
+  if (meta.camera) parts.push(meta.camera);
+  if (meta.taken_at) parts.push(meta.taken_at.replace("T", " ").slice(0, 16));
+  if (meta.gps_latitude != null && meta.gps_longitude != null) {
+    parts.push(`${meta.gps_latitude.toFixed(5)}, ${meta.gps_longitude.toFixed(5)}`);
+  }
+  return parts.join(" · ");
+}
+
 function getTitle(detail: ItemDetail): string {
   if (detail.file_name) return detail.file_name;
   if (detail.content_type === "image" && detail.image_path) {
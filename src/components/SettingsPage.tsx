@@ -4,6 +4,18 @@ import { invoke } from "@tauri-apps/api/core";
 import { platform } from "@tauri-apps/plugin-os";
 import { useSettingsStore, type Settings as AppSettings } from "../stores/settings-store";
 import { useUpdateStore } from "../stores/update-store";
+import { useLockStore } from "../stores/lock-store";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  AppCaptureRule,
+  CaptureIgnoreKind,
+  CaptureIgnoreRule,
+  ItemFilter,
+  MaintenanceStatus,
+  RetentionPreview,
+  RetentionRule,
+} from "../lib/types";
+import { relativeTime } from "../lib/time";
 import {
   Settings,
   Clock,
@@ -25,6 +37,8 @@ import {
   PanelTop,
   PanelLeft,
   PanelRight,
+  Plus,
+  Lock,
 } from "lucide-react";
 import { Button } from "./ui/button";
 import { Card, CardContent } from "./ui/card";
@@ -166,6 +180,111 @@ function GeneralSettings({
         />
       </SettingRow>
 
+      {platform() === "linux" && (
+        <>
+          <SettingRow
+            label={t("settings.general.pasteBackend")}
+            description={t("settings.general.pasteBackendDesc")}
+          >
+            <div className="relative">
+              <select
+                value={settings.linux_paste_backend}
+                onChange={(e) => updateSetting("linux_paste_backend", e.target.value)}
+                className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-3 pr-7 py-1.5 text-sm cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring hover:border-muted-foreground/40 transition-colors"
+              >
+                <option value="auto">{t("settings.general.pasteBackendAuto")}</option>
+                <option value="xtest">{t("settings.general.pasteBackendXtest")}</option>
+                <option value="uinput">{t("settings.general.pasteBackendUinput")}</option>
+                <option value="command">{t("settings.general.pasteBackendCommand")}</option>
+                <option value="off">{t("settings.general.pasteBackendOff")}</option>
+              </select>
+              <ChevronDown
+                size={13}
+                className="absolute right-2 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+              />
+            </div>
+          </SettingRow>
+
+          <SettingRow
+            label={t("settings.general.pasteKeys")}
+            description={t("settings.general.pasteKeysDesc")}
+          >
+            <div className="relative">
+              <select
+                value={settings.linux_paste_keys}
+                onChange={(e) => updateSetting("linux_paste_keys", e.target.value)}
+                className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-3 pr-7 py-1.5 text-sm cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring hover:border-muted-foreground/40 transition-colors"
+              >
+                <option value="auto">{t("settings.general.pasteKeysAuto")}</option>
+                <option value="ctrl_v">Ctrl+V</option>
+                <option value="ctrl_shift_v">Ctrl+Shift+V</option>
+                <option value="shift_insert">Shift+Insert</option>
+              </select>
+              <ChevronDown
+                size={13}
+                className="absolute right-2 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+              />
+            </div>
+          </SettingRow>
+
+          <SettingRow
+            label={t("settings.general.pasteCommand")}
+            description={t("settings.general.pasteCommandDesc")}
+          >
+            <input
+              defaultValue={settings.linux_paste_command}
+              onBlur={(e) => updateSetting("linux_paste_command", e.target.value.trim())}
+              placeholder="xdotool key --clearmodifiers {keys}"
+              className="bg-input/60 text-foreground border border-border/50 rounded-lg px-3 py-1.5 text-sm w-56 focus:outline-none focus:ring-1 focus:ring-ring"
+            />
+          </SettingRow>
+
+          <SettingRow
+            label={t("settings.general.primaryCapture")}
+            description={t("settings.general.primaryCaptureDesc")}
+          >
+            <Switch
+              checked={settings.primary_capture === "true"}
+              onCheckedChange={(v) => updateSetting("primary_capture", v ? "true" : "false")}
+            />
+          </SettingRow>
+
+          <SettingRow
+            label={t("settings.general.primarySync")}
+            description={t("settings.general.primarySyncDesc")}
+          >
+            <div className="relative">
+              <select
+                value={settings.primary_sync}
+                onChange={(e) => updateSetting("primary_sync", e.target.value)}
+                className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-3 pr-7 py-1.5 text-sm cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring hover:border-muted-foreground/40 transition-colors"
+              >
+                <option value="off">{t("settings.general.primarySyncOff")}</option>
+                <option value="to_clipboard">{t("settings.general.primarySyncToClipboard")}</option>
+                <option value="from_clipboard">
+                  {t("settings.general.primarySyncFromClipboard")}
+                </option>
+                <option value="both">{t("settings.general.primarySyncBoth")}</option>
+              </select>
+              <ChevronDown
+                size={13}
+                className="absolute right-2 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+              />
+            </div>
+          </SettingRow>
+
+          <SettingRow
+            label={t("settings.general.clipboardOwner")}
+            description={t("settings.general.clipboardOwnerDesc")}
+          >
+            <Switch
+              checked={settings.clipboard_owner === "true"}
+              onCheckedChange={(v) => updateSetting("clipboard_owner", v ? "true" : "false")}
+            />
+          </SettingRow>
+        </>
+      )}
+
       {platform() === "macos" && (
         <SettingRow
           label={t("settings.general.showTrayIcon")}
@@ -278,6 +397,7 @@ function HistorySettings({
             <option value="unlimited">{t("settings.history.unlimited")}</option>
             <option value="days">{t("settings.history.keepDays")}</option>
             <option value="count">{t("settings.history.keepCount")}</option>
+            <option value="size">{t("settings.history.keepSize")}</option>
           </select>
           <ChevronDown
             size={13}
@@ -318,10 +438,33 @@ function HistorySettings({
         </SettingRow>
       )}
 
+      {settings.retention_policy === "size" && (
+        <SettingRow
+          label={t("settings.history.maxStorage")}
+          description={t("settings.history.maxStorageDesc")}
+        >
+          <div className="flex items-center gap-2">
+            <input
+              type="number"
+              min="50"
+              max="1000000"
+              value={settings.retention_size_mb}
+              onChange={(e) => updateSetting("retention_size_mb", e.target.value)}
+              className="bg-input/60 text-foreground border border-border/50 rounded-lg px-3 py-1.5 text-sm w-24 focus:outline-none focus:ring-1 focus:ring-ring"
+            />
+            <span className="text-xs text-muted-foreground">MB</span>
+          </div>
+        </SettingRow>
+      )}
+
+      <RetentionRules />
+
       {settings.retention_policy !== "unlimited" && (
         <p className="text-xs text-muted-foreground/70">{t("settings.history.retentionHint")}</p>
       )}
 
+      <RetentionPreviewRow settings={settings} />
+
       <SettingRow
         label={t("settings.history.maxSize")}
         description={t("settings.history.maxSizeDesc")}
@@ -339,6 +482,65 @@ function HistorySettings({
         </div>
       </SettingRow>
 
+      <SettingRow
+        label={t("settings.history.textDedup")}
+        description={t("settings.history.textDedupDesc")}
+      >
+        <div className="relative">
+          <select
+            value={settings.text_dedup_mode}
+            onChange={(e) => updateSetting("text_dedup_mode", e.target.value)}
+            className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-3 pr-7 py-1.5 text-sm cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring hover:border-muted-foreground/40 transition-colors"
+          >
+            <option value="exact">{t("settings.history.textDedupExact")}</option>
+            <option value="normalized">{t("settings.history.textDedupNormalized")}</option>
+          </select>
+          <ChevronDown
+            size={13}
+            className="absolute right-2 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+          />
+        </div>
+      </SettingRow>
+
+      <SettingRow
+        label={t("settings.history.nearDuplicates")}
+        description={t("settings.history.nearDuplicatesDesc")}
+      >
+        <div className="relative">
+          <select
+            value={settings.near_duplicate_mode}
+            onChange={(e) => updateSetting("near_duplicate_mode", e.target.value)}
+            className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-3 pr-7 py-1.5 text-sm cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring hover:border-muted-foreground/40 transition-colors"
+          >
+            <option value="off">{t("settings.history.nearDuplicatesOff")}</option>
+            <option value="bump">{t("settings.history.nearDuplicatesBump")}</option>
+            <option value="group">{t("settings.history.nearDuplicatesGroup")}</option>
+          </select>
+          <ChevronDown
+            size={13}
+            className="absolute right-2 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+          />
+        </div>
+      </SettingRow>
+
+      {settings.near_duplicate_mode !== "off" && (
+        <SettingRow
+          label={t("settings.history.similarityThreshold")}
+          description={t("settings.history.similarityThresholdDesc")}
+        >
+          <input
+            type="number"
+            min="0"
+            max="16"
+            value={settings.image_similarity_threshold}
+            onChange={(e) => updateSetting("image_similarity_threshold", e.target.value)}
+            className="bg-input/60 text-foreground border border-border/50 rounded-lg px-3 py-1.5 text-sm w-20 focus:outline-none focus:ring-1 focus:ring-ring"
+          />
+        </SettingRow>
+      )}
+
+      <MaintenanceSettings settings={settings} updateSetting={updateSetting} />
+
       <SettingRow
         label={t("settings.history.storageUsed")}
         description={t("settings.history.storageUsedDesc")}
@@ -369,17 +571,414 @@ function HistorySettings({
               : t("settings.history.clearAll")}
         </Button>
       </SettingRow>
+
+      <ClearFilteredRow
+        onCleared={() =>
+          invoke<number>("get_storage_size")
+            .then(setStorageSize)
+            .catch(() => {})
+        }
+      />
+    </div>
+  );
+}
+
+function ClearFilteredRow({ onCleared }: { onCleared: () => void }) {
+  const { t } = useTranslation();
+  const { clearHistoryFiltered } = useSettingsStore();
+  const [contentType, setContentType] = useState("");
+  const [olderThanDays, setOlderThanDays] = useState("");
+  const [sourceApp, setSourceApp] = useState("");
+  const [ungrouped, setUngrouped] = useState(false);
+  const [confirm, setConfirm] = useState(false);
+  const [cleared, setCleared] = useState<number | null>(null);
+
+  const handleClear = async () => {
+    if (!confirm) {
+      setConfirm(true);
+      return;
+    }
+    const filter: ItemFilter = {
+      content_type: contentType || undefined,
+      source_app: sourceApp.trim() || undefined,
+      older_than_days: olderThanDays === "" ? undefined : Math.max(0, Number(olderThanDays)),
+      ungrouped,
+    };
+    const count = await clearHistoryFiltered(filter);
+    setCleared(count);
+    onCleared();
+    setConfirm(false);
+    setTimeout(() => setCleared(null), 3000);
+  };
+
+  const inputClass =
+    "bg-input/60 text-foreground border border-border/50 rounded-lg px-2 py-1 text-xs focus:outline-none focus:ring-1 focus:ring-ring";
+
+  return (
+    <div className="py-2 space-y-2">
+      <div>
+        <p className="text-sm text-foreground">{t("settings.history.clearFiltered")}</p>
+        <p className="text-xs text-muted-foreground">{t("settings.history.clearFilteredDesc")}</p>
+      </div>
+      <div className="flex flex-wrap items-center gap-2">
+        <select
+          value={contentType}
+          onChange={(e) => setContentType(e.target.value)}
+          className={`${inputClass} cursor-pointer`}
+        >
+          <option value="">{t("filter.all")}</option>
+          {RULE_TYPES.map(({ value, label }) => (
+            <option key={value} value={value}>
+              {t(label)}
+            </option>
+          ))}
+        </select>
+        <input
+          type="number"
+          min="0"
+          value={olderThanDays}
+          onChange={(e) => setOlderThanDays(e.target.value)}
+          placeholder={t("settings.history.clearOlderThan")}
+          className={`${inputClass} w-24`}
+        />
+        <input
+          value={sourceApp}
+          onChange={(e) => setSourceApp(e.target.value)}
+          placeholder={t("settings.history.rulesSourceApp")}
+          className={`${inputClass} flex-1 min-w-0`}
+        />
+        <label className="flex items-center gap-1 text-xs text-muted-foreground cursor-pointer">
+          <input
+            type="checkbox"
+            checked={ungrouped}
+            onChange={(e) => setUngrouped(e.target.checked)}
+          />
+          {t("settings.history.clearUngrouped")}
+        </label>
+        <Button
+          variant={confirm ? "destructive" : "ghost"}
+          size="sm"
+          onClick={handleClear}
+          className={confirm ? "" : "text-destructive hover:bg-destructive/10"}
+        >
+          <Trash2 size={13} />
+          {confirm
+            ? t("settings.history.confirmClear")
+            : cleared !== null
+              ? t("settings.history.cleared", { count: cleared })
+              : t("settings.history.clearMatching")}
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+function MaintenanceSettings({
+  settings,
+  updateSetting,
+}: {
+  settings: AppSettings;
+  updateSetting: (key: keyof AppSettings, value: string) => Promise<void>;
+}) {
+  const { t } = useTranslation();
+  const [status, setStatus] = useState<MaintenanceStatus | null>(null);
+
+  useEffect(() => {
+    invoke<MaintenanceStatus>("get_maintenance_status")
+      .then(setStatus)
+      .catch(() => {});
+    const unlisten = listen<MaintenanceStatus>("maintenance-status", (e) => setStatus(e.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const summary = !status?.last_run_at
+    ? t("settings.history.maintenanceNever")
+    : t("settings.history.maintenanceLastRun", {
+        time: relativeTime(status.last_run_at),
+        items: status.retention_deleted,
+        files: status.orphan_images_removed + status.orphan_thumbnails_removed,
+      });
+
+  return (
+    <>
+      <SettingRow label={t("settings.history.maintenance")} description={summary}>
+        <div className="flex items-center gap-2">
+          <div className="relative">
+            <select
+              value={settings.maintenance_interval_hours}
+              onChange={(e) => updateSetting("maintenance_interval_hours", e.target.value)}
+              className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-3 pr-7 py-1.5 text-sm cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring hover:border-muted-foreground/40 transition-colors"
+            >
+              <option value="1">{t("settings.history.maintenanceHourly")}</option>
+              <option value="6">{t("settings.history.maintenanceSixHours")}</option>
+              <option value="24">{t("settings.history.maintenanceDaily")}</option>
+            </select>
+            <ChevronDown
+              size={13}
+              className="absolute right-2 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+            />
+          </div>
+          <Button
+            variant="ghost"
+            size="sm"
+            disabled={status?.running}
+            onClick={() => invoke("run_maintenance").catch(() => {})}
+          >
+            <RefreshCw size={13} className={status?.running ? "animate-spin" : ""} />
+            {t("settings.history.maintenanceRunNow")}
+          </Button>
+        </div>
+      </SettingRow>
+
+      {status && status.errors.length > 0 && (
+        <p className="text-xs text-destructive">{status.errors.join("; ")}</p>
+      )}
+
+      <SettingRow
+        label={t("settings.history.vacuum")}
+        description={t("settings.history.vacuumDesc")}
+      >
+        <Switch
+          checked={settings.maintenance_vacuum === "true"}
+          onCheckedChange={(v) => updateSetting("maintenance_vacuum", v ? "true" : "false")}
+        />
+      </SettingRow>
+    </>
+  );
+}
+
+function RetentionPreviewRow({ settings }: { settings: AppSettings }) {
+  const { t } = useTranslation();
+  const [preview, setPreview] = useState<RetentionPreview | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  // Stale once the policy inputs change
+  useEffect(() => {
+    setPreview(null);
+  }, [
+    settings.retention_policy,
+    settings.retention_days,
+    settings.retention_count,
+    settings.retention_size_mb,
+  ]);
+
+  const runPreview = async () => {
+    setLoading(true);
+    try {
+      setPreview(
+        await invoke<RetentionPreview>("preview_retention", {
+          policy: settings.retention_policy,
+          days: Number(settings.retention_days),
+          count: Number(settings.retention_count),
+          sizeMb: Number(settings.retention_size_mb),
+        }),
+      );
+    } catch (e) {
+      console.error("Failed to preview retention:", e);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const typeLabel = (value: string) =>
+    t(RULE_TYPES.find((rt) => rt.value === value)?.label ?? value);
+
+  return (
+    <>
+      <SettingRow
+        label={t("settings.history.preview")}
+        description={t("settings.history.previewDesc")}
+      >
+        <Button variant="ghost" size="sm" onClick={runPreview} disabled={loading}>
+          {t("settings.history.previewRun")}
+        </Button>
+      </SettingRow>
+
+      {preview && (
+        <div className="text-xs text-muted-foreground space-y-1 pb-2">
+          <p>
+            {preview.total_items === 0
+              ? t("settings.history.previewNone")
+              : t("settings.history.previewResult", {
+                  count: preview.total_items,
+                  size: formatBytes(preview.total_bytes),
+                })}
+          </p>
+          {preview.by_type.length > 0 && (
+            <p>
+              {preview.by_type
+                .map((s) => `${typeLabel(s.content_type)} ${s.items} (${formatBytes(s.bytes)})`)
+                .join(" · ")}
+            </p>
+          )}
+          {preview.sample.length > 0 && (
+            <ul className="space-y-0.5 text-muted-foreground/70">
+              {preview.sample.slice(0, 5).map((item) => (
+                <li key={item.id} className="truncate">
+                  {item.plain_text || item.file_name || typeLabel(item.content_type)}
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+    </>
+  );
+}
+
+const RULE_TYPES: { value: string; label: string }[] = [
+  { value: "plain_text", label: "filter.text" },
+  { value: "rich_text", label: "filter.rich" },
+  { value: "image", label: "filter.image" },
+  { value: "file", label: "filter.file" },
+  { value: "link", label: "filter.link" },
+];
+
+const RULE_LIMITS = [
+  { key: "max_days", label: "settings.history.rulesDays" },
+  { key: "max_count", label: "settings.history.rulesCount" },
+  { key: "max_size_mb", label: "settings.history.rulesSize" },
+] as const;
+
+function RetentionRules() {
+  const { t } = useTranslation();
+  const [rules, setRules] = useState<RetentionRule[]>([]);
+  const [draftType, setDraftType] = useState("image");
+  const [draftApp, setDraftApp] = useState("");
+
+  const load = () =>
+    invoke<RetentionRule[]>("get_retention_rules")
+      .then(setRules)
+      .catch(() => {});
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  const save = async (rule: RetentionRule) => {
+    try {
+      await invoke("save_retention_rule", { rule });
+      await load();
+    } catch (e) {
+      console.error("Failed to save retention rule:", e);
+    }
+  };
+
+  const remove = async (id: number) => {
+    try {
+      await invoke("delete_retention_rule", { id });
+      await load();
+    } catch (e) {
+      console.error("Failed to delete retention rule:", e);
+    }
+  };
+
+  const setLimit = (id: number, key: (typeof RULE_LIMITS)[number]["key"], value: string) => {
+    const limit = value === "" ? null : Math.max(0, Number(value));
+    setRules((prev) => prev.map((r) => (r.id === id ? { ...r, [key]: limit } : r)));
+  };
+
+  const typeLabel = (value: string) =>
+    t(RULE_TYPES.find((rt) => rt.value === value)?.label ?? value);
+
+  return (
+    <div className="py-3 border-b border-border/20 space-y-2">
+      <div className="space-y-0.5">
+        <div className="text-sm font-medium">{t("settings.history.rules")}</div>
+        <div className="text-xs text-muted-foreground/80">{t("settings.history.rulesDesc")}</div>
+      </div>
+
+      {rules.map((rule) => (
+        <div key={rule.id} className="flex items-center gap-2 text-sm">
+          <span className="w-16 shrink-0">{typeLabel(rule.content_type)}</span>
+          <span className="flex-1 min-w-0 truncate text-xs text-muted-foreground">
+            {rule.source_app || t("settings.history.rulesAnyApp")}
+          </span>
+          {RULE_LIMITS.map(({ key, label }) => (
+            <input
+              key={key}
+              type="number"
+              min="1"
+              placeholder={t(label)}
+              title={t(label)}
+              value={rule[key] ?? ""}
+              onChange={(e) => setLimit(rule.id, key, e.target.value)}
+              onBlur={() => save(rule)}
+              className="bg-input/60 text-foreground border border-border/50 rounded-lg px-2 py-1 text-xs w-16 focus:outline-none focus:ring-1 focus:ring-ring"
+            />
+          ))}
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => remove(rule.id)}
+            aria-label={t("settings.history.rulesDelete")}
+            className="text-destructive hover:bg-destructive/10"
+          >
+            <Trash2 size={13} />
+          </Button>
+        </div>
+      ))}
+
+      <div className="flex items-center gap-2">
+        <div className="relative">
+          <select
+            value={draftType}
+            onChange={(e) => setDraftType(e.target.value)}
+            className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-2 pr-6 py-1 text-xs cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring"
+          >
+            {RULE_TYPES.map(({ value, label }) => (
+              <option key={value} value={value}>
+                {t(label)}
+              </option>
+            ))}
+          </select>
+          <ChevronDown
+            size={12}
+            className="absolute right-1.5 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+          />
+        </div>
+        <input
+          type="text"
+          value={draftApp}
+          placeholder={t("settings.history.rulesSourceApp")}
+          onChange={(e) => setDraftApp(e.target.value)}
+          className="flex-1 min-w-0 bg-input/60 text-foreground border border-border/50 rounded-lg px-2 py-1 text-xs focus:outline-none focus:ring-1 focus:ring-ring"
+        />
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={() => {
+            save({
+              id: 0,
+              content_type: draftType,
+              source_app: draftApp,
+              max_days: null,
+              max_count: null,
+              max_size_mb: null,
+            });
+            setDraftApp("");
+          }}
+        >
+          <Plus size={13} />
+          {t("settings.history.rulesAdd")}
+        </Button>
+      </div>
     </div>
   );
 }
 
 function PrivacySettings() {
   const { t } = useTranslation();
+  const { settings, updateSetting } = useSettingsStore();
 
   return (
     <div className="space-y-1">
       <SectionTitle>{t("settings.privacy.title")}</SectionTitle>
 
+      <AppLockSettings />
+
       {platform() === "macos" && (
         <Card className="border-border/50 bg-card/60 py-0">
           <CardContent className="p-4 space-y-3">
@@ -406,6 +1005,64 @@ function PrivacySettings() {
         </Card>
       )}
 
+      <SettingRow
+        label={t("settings.privacy.stripImageMetadata")}
+        description={t("settings.privacy.stripImageMetadataDesc")}
+      >
+        <Switch
+          checked={settings.strip_image_metadata === "true"}
+          onCheckedChange={(v) => updateSetting("strip_image_metadata", v ? "true" : "false")}
+        />
+      </SettingRow>
+
+      <SettingRow
+        label={t("settings.privacy.concealed")}
+        description={t("settings.privacy.concealedDesc")}
+      >
+        <div className="relative">
+          <select
+            value={settings.concealed_mode}
+            onChange={(e) => updateSetting("concealed_mode", e.target.value)}
+            className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-3 pr-7 py-1.5 text-sm cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring hover:border-muted-foreground/40 transition-colors"
+          >
+            <option value="skip">{t("settings.privacy.concealedSkip")}</option>
+            <option value="mask">{t("settings.privacy.concealedMask")}</option>
+          </select>
+          <ChevronDown
+            size={13}
+            className="absolute right-2 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+          />
+        </div>
+      </SettingRow>
+
+      <SettingRow
+        label={t("settings.privacy.sensitiveDetection")}
+        description={t("settings.privacy.sensitiveDetectionDesc")}
+      >
+        <Switch
+          checked={settings.sensitive_detection === "true"}
+          onCheckedChange={(v) => updateSetting("sensitive_detection", v ? "true" : "false")}
+        />
+      </SettingRow>
+
+      {settings.sensitive_detection === "true" && <SensitivePatterns />}
+
+      <SettingRow
+        label={t("settings.privacy.sensitiveTtl")}
+        description={t("settings.privacy.sensitiveTtlDesc")}
+      >
+        <div className="flex items-center gap-2">
+          <input
+            type="number"
+            min="1"
+            value={settings.sensitive_ttl_secs}
+            onChange={(e) => updateSetting("sensitive_ttl_secs", e.target.value)}
+            className="bg-input/60 text-foreground border border-border/50 rounded-lg px-3 py-1.5 text-sm w-20 focus:outline-none focus:ring-1 focus:ring-ring"
+          />
+          <span className="text-xs text-muted-foreground">{t("settings.privacy.seconds")}</span>
+        </div>
+      </SettingRow>
+
       <Card className="border-border/50 bg-card/60 py-0">
         <CardContent className="p-4 space-y-2">
           <h3 className="text-sm font-medium flex items-center gap-2">
@@ -418,10 +1075,446 @@ function PrivacySettings() {
         </CardContent>
       </Card>
 
-      {/* Hidden until FR-003 is implemented */}
-      {/* <SettingRow label={t("settings.privacy.exclusionList")} description={t("settings.privacy.exclusionListDesc")}>
-        <span className="text-xs text-muted-foreground/60 px-2 py-1 rounded-md bg-overlay-hover">{t("settings.privacy.comingSoon")}</span>
-      </SettingRow> */}
+      <AppCaptureRules />
+
+      <CaptureIgnoreRules />
+    </div>
+  );
+}
+
+function AppLockSettings() {
+  const { t } = useTranslation();
+  const { settings, updateSetting } = useSettingsStore();
+  const { enabled, fetchStatus, setLock, lockNow } = useLockStore();
+  const [current, setCurrent] = useState("");
+  const [next, setNext] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    fetchStatus();
+  }, [fetchStatus]);
+
+  const apply = async (secret: string | null) => {
+    try {
+      await setLock(secret, enabled ? current : null);
+      setCurrent("");
+      setNext("");
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const inputClass =
+    "flex-1 min-w-0 bg-input/60 text-foreground border border-border/50 rounded-lg px-2 py-1 text-xs focus:outline-none focus:ring-1 focus:ring-ring";
+
+  return (
+    <>
+      <div className="py-3 border-b border-border/20 space-y-2">
+        <div className="space-y-0.5">
+          <div className="text-sm font-medium flex items-center gap-2">
+            <Lock size={14} className="text-primary" />
+            {t("settings.privacy.appLock")}
+          </div>
+          <div className="text-xs text-muted-foreground/80">
+            {enabled ? t("settings.privacy.appLockOnDesc") : t("settings.privacy.appLockDesc")}
+          </div>
+        </div>
+        <div className="flex items-center gap-2">
+          {enabled && (
+            <input
+              type="password"
+              value={current}
+              placeholder={t("settings.privacy.appLockCurrent")}
+              onChange={(e) => setCurrent(e.target.value)}
+              className={inputClass}
+            />
+          )}
+          <input
+            type="password"
+            value={next}
+            placeholder={t("settings.privacy.appLockNew")}
+            onChange={(e) => setNext(e.target.value)}
+            className={inputClass}
+          />
+          <Button variant="ghost" size="sm" disabled={!next} onClick={() => apply(next)}>
+            {enabled ? t("settings.privacy.appLockChange") : t("settings.privacy.appLockSet")}
+          </Button>
+          {enabled && (
+            <Button
+              variant="ghost"
+              size="sm"
+              disabled={!current}
+              onClick={() => apply(null)}
+              className="text-destructive hover:bg-destructive/10"
+            >
+              {t("settings.privacy.appLockRemove")}
+            </Button>
+          )}
+        </div>
+        {error && <p className="text-xs text-destructive">{error}</p>}
+      </div>
+
+      {enabled && (
+        <SettingRow
+          label={t("settings.privacy.appLockIdle")}
+          description={t("settings.privacy.appLockIdleDesc")}
+        >
+          <div className="flex items-center gap-2">
+            <input
+              type="number"
+              min="1"
+              value={settings.app_lock_idle_minutes}
+              onChange={(e) => updateSetting("app_lock_idle_minutes", e.target.value)}
+              className="bg-input/60 text-foreground border border-border/50 rounded-lg px-3 py-1.5 text-sm w-20 focus:outline-none focus:ring-1 focus:ring-ring"
+            />
+            <span className="text-xs text-muted-foreground">{t("settings.privacy.minutes")}</span>
+            <Button variant="ghost" size="sm" onClick={lockNow}>
+              {t("settings.privacy.appLockNow")}
+            </Button>
+          </div>
+        </SettingRow>
+      )}
+    </>
+  );
+}
+
+function SensitivePatterns() {
+  const { t } = useTranslation();
+  const { settings, updateSetting } = useSettingsStore();
+  const [draft, setDraft] = useState(settings.sensitive_patterns);
+  const [invalid, setInvalid] = useState(false);
+
+  // The backend rejects patterns that don't compile, leaving the stored value unchanged
+  const save = async () => {
+    if (draft === settings.sensitive_patterns) return;
+    await updateSetting("sensitive_patterns", draft);
+    setInvalid(useSettingsStore.getState().settings.sensitive_patterns !== draft);
+  };
+
+  return (
+    <div className="py-3 border-b border-border/20 space-y-2">
+      <div className="space-y-0.5">
+        <div className="text-sm font-medium">{t("settings.privacy.sensitivePatterns")}</div>
+        <div className="text-xs text-muted-foreground/80">
+          {t("settings.privacy.sensitivePatternsDesc")}
+        </div>
+      </div>
+      <textarea
+        value={draft}
+        rows={3}
+        spellCheck={false}
+        placeholder={t("settings.privacy.sensitivePatternsPlaceholder")}
+        onChange={(e) => {
+          setDraft(e.target.value);
+          setInvalid(false);
+        }}
+        onBlur={save}
+        className="w-full bg-input/60 text-foreground border border-border/50 rounded-lg px-2 py-1 text-xs font-mono focus:outline-none focus:ring-1 focus:ring-ring"
+      />
+      {invalid && (
+        <p className="text-xs text-destructive">{t("settings.privacy.sensitivePatternsInvalid")}</p>
+      )}
+    </div>
+  );
+}
+
+const CAPTURE_ACTIONS = [
+  { value: "exclude", label: "settings.privacy.exclusionExclude" },
+  { value: "include", label: "settings.privacy.exclusionInclude" },
+] as const;
+
+function AppCaptureRules() {
+  const { t } = useTranslation();
+  const [rules, setRules] = useState<AppCaptureRule[]>([]);
+  const [draftApp, setDraftApp] = useState("");
+  const [draftAction, setDraftAction] = useState<AppCaptureRule["action"]>("exclude");
+
+  const load = () =>
+    invoke<AppCaptureRule[]>("get_app_capture_rules")
+      .then(setRules)
+      .catch(() => {});
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  const save = async (rule: AppCaptureRule) => {
+    try {
+      await invoke("save_app_capture_rule", { rule });
+      await load();
+    } catch (e) {
+      console.error("Failed to save app capture rule:", e);
+    }
+  };
+
+  const remove = async (id: number) => {
+    try {
+      await invoke("delete_app_capture_rule", { id });
+      await load();
+    } catch (e) {
+      console.error("Failed to delete app capture rule:", e);
+    }
+  };
+
+  const actionSelect = (
+    value: AppCaptureRule["action"],
+    onChange: (action: AppCaptureRule["action"]) => void,
+  ) => (
+    <div className="relative">
+      <select
+        value={value}
+        onChange={(e) => onChange(e.target.value as AppCaptureRule["action"])}
+        className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-2 pr-6 py-1 text-xs cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring"
+      >
+        {CAPTURE_ACTIONS.map(({ value, label }) => (
+          <option key={value} value={value}>
+            {t(label)}
+          </option>
+        ))}
+      </select>
+      <ChevronDown
+        size={12}
+        className="absolute right-1.5 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+      />
+    </div>
+  );
+
+  return (
+    <div className="py-3 border-b border-border/20 space-y-2">
+      <div className="space-y-0.5">
+        <div className="text-sm font-medium">{t("settings.privacy.exclusionList")}</div>
+        <div className="text-xs text-muted-foreground/80">{t("settings.privacy.exclusionListDesc")}</div>
+      </div>
+
+      <div className="max-h-48 overflow-y-auto space-y-1">
+        {rules.map((rule) => (
+          <div key={rule.id} className="flex items-center gap-2 text-sm">
+            <span className="flex-1 min-w-0 truncate text-xs">{rule.app}</span>
+            {actionSelect(rule.action, (action) => save({ ...rule, action }))}
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => remove(rule.id)}
+              aria-label={t("settings.privacy.exclusionDelete")}
+              className="text-destructive hover:bg-destructive/10"
+            >
+              <Trash2 size={13} />
+            </Button>
+          </div>
+        ))}
+      </div>
+
+      <div className="flex items-center gap-2">
+        <input
+          type="text"
+          value={draftApp}
+          placeholder={t("settings.privacy.exclusionApp")}
+          onChange={(e) => setDraftApp(e.target.value)}
+          className="flex-1 min-w-0 bg-input/60 text-foreground border border-border/50 rounded-lg px-2 py-1 text-xs focus:outline-none focus:ring-1 focus:ring-ring"
+        />
+        {actionSelect(draftAction, setDraftAction)}
+        <Button
+          variant="ghost"
+          size="sm"
+          disabled={!draftApp.trim()}
+          onClick={() => {
+            save({ id: 0, app: draftApp, action: draftAction });
+            setDraftApp("");
+          }}
+        >
+          <Plus size={13} />
+          {t("settings.privacy.exclusionAdd")}
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+const IGNORE_KINDS: { value: CaptureIgnoreKind; label: string }[] = [
+  { value: "regex", label: "settings.privacy.ignoreRegex" },
+  { value: "min_length", label: "settings.privacy.ignoreMinLength" },
+  { value: "max_length", label: "settings.privacy.ignoreMaxLength" },
+  { value: "whitespace", label: "settings.privacy.ignoreWhitespace" },
+  { value: "extension", label: "settings.privacy.ignoreExtension" },
+];
+
+function CaptureIgnoreRules() {
+  const { t } = useTranslation();
+  const [rules, setRules] = useState<CaptureIgnoreRule[]>([]);
+  const [draftKind, setDraftKind] = useState<CaptureIgnoreKind>("regex");
+  const [draftValue, setDraftValue] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [sampleFile, setSampleFile] = useState(false);
+  const [sample, setSample] = useState("");
+  const [testResult, setTestResult] = useState<CaptureIgnoreRule | null | undefined>(undefined);
+
+  const load = () =>
+    invoke<CaptureIgnoreRule[]>("get_capture_ignore_rules")
+      .then(setRules)
+      .catch(() => {});
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  const save = async (rule: CaptureIgnoreRule) => {
+    try {
+      await invoke("save_capture_ignore_rule", { rule });
+      setError(null);
+      await load();
+      return true;
+    } catch (e) {
+      console.error("Failed to save capture ignore rule:", e);
+      setError(String(e));
+      return false;
+    }
+  };
+
+  const remove = async (id: number) => {
+    try {
+      await invoke("delete_capture_ignore_rule", { id });
+      await load();
+    } catch (e) {
+      console.error("Failed to delete capture ignore rule:", e);
+    }
+  };
+
+  const runTest = async () => {
+    try {
+      const rule = await invoke<CaptureIgnoreRule | null>("test_capture_rules", {
+        contentType: sampleFile ? "file" : "plain_text",
+        sample,
+      });
+      setTestResult(rule);
+    } catch (e) {
+      console.error("Failed to test capture rules:", e);
+    }
+  };
+
+  const describe = (rule: CaptureIgnoreRule) => {
+    const label = t(IGNORE_KINDS.find((k) => k.value === rule.kind)?.label ?? rule.kind);
+    return rule.kind === "whitespace" ? label : `${label}: ${rule.value}`;
+  };
+
+  const inputClass =
+    "flex-1 min-w-0 bg-input/60 text-foreground border border-border/50 rounded-lg px-2 py-1 text-xs focus:outline-none focus:ring-1 focus:ring-ring";
+
+  return (
+    <div className="py-3 border-b border-border/20 space-y-2">
+      <div className="space-y-0.5">
+        <div className="text-sm font-medium">{t("settings.privacy.ignoreRules")}</div>
+        <div className="text-xs text-muted-foreground/80">{t("settings.privacy.ignoreRulesDesc")}</div>
+      </div>
+
+      <div className="max-h-48 overflow-y-auto space-y-1">
+        {rules.map((rule) => (
+          <div key={rule.id} className="flex items-center gap-2 text-sm">
+            <Switch
+              checked={rule.enabled}
+              onCheckedChange={(enabled) => save({ ...rule, enabled })}
+            />
+            <span className="flex-1 min-w-0 truncate text-xs font-mono">{describe(rule)}</span>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => remove(rule.id)}
+              aria-label={t("settings.privacy.ignoreDelete")}
+              className="text-destructive hover:bg-destructive/10"
+            >
+              <Trash2 size={13} />
+            </Button>
+          </div>
+        ))}
+      </div>
+
+      <div className="flex items-center gap-2">
+        <div className="relative">
+          <select
+            value={draftKind}
+            onChange={(e) => setDraftKind(e.target.value as CaptureIgnoreKind)}
+            className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-2 pr-6 py-1 text-xs cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring"
+          >
+            {IGNORE_KINDS.map(({ value, label }) => (
+              <option key={value} value={value}>
+                {t(label)}
+              </option>
+            ))}
+          </select>
+          <ChevronDown
+            size={12}
+            className="absolute right-1.5 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+          />
+        </div>
+        {draftKind !== "whitespace" && (
+          <input
+            type={draftKind === "min_length" || draftKind === "max_length" ? "number" : "text"}
+            min="0"
+            value={draftValue}
+            placeholder={t(`settings.privacy.ignoreValue.${draftKind}`)}
+            onChange={(e) => setDraftValue(e.target.value)}
+            className={inputClass}
+          />
+        )}
+        <Button
+          variant="ghost"
+          size="sm"
+          disabled={draftKind !== "whitespace" && !draftValue.trim()}
+          onClick={async () => {
+            const saved = await save({
+              id: 0,
+              kind: draftKind,
+              value: draftKind === "whitespace" ? "" : draftValue,
+              enabled: true,
+            });
+            if (saved) setDraftValue("");
+          }}
+        >
+          <Plus size={13} />
+          {t("settings.privacy.ignoreAdd")}
+        </Button>
+      </div>
+      {error && <p className="text-xs text-destructive">{error}</p>}
+
+      <div className="flex items-center gap-2 pt-1">
+        <div className="relative">
+          <select
+            value={sampleFile ? "file" : "text"}
+            onChange={(e) => {
+              setSampleFile(e.target.value === "file");
+              setTestResult(undefined);
+            }}
+            className="appearance-none bg-input/60 text-foreground border border-border/50 rounded-lg pl-2 pr-6 py-1 text-xs cursor-pointer focus:outline-none focus:ring-1 focus:ring-ring"
+          >
+            <option value="text">{t("settings.privacy.ignoreSampleText")}</option>
+            <option value="file">{t("settings.privacy.ignoreSampleFile")}</option>
+          </select>
+          <ChevronDown
+            size={12}
+            className="absolute right-1.5 top-1/2 -translate-y-1/2 text-muted-foreground pointer-events-none"
+          />
+        </div>
+        <input
+          type="text"
+          value={sample}
+          placeholder={t("settings.privacy.ignoreSample")}
+          onChange={(e) => {
+            setSample(e.target.value);
+            setTestResult(undefined);
+          }}
+          className={inputClass}
+        />
+        <Button variant="ghost" size="sm" onClick={runTest}>
+          {t("settings.privacy.ignoreTest")}
+        </Button>
+      </div>
+      {testResult !== undefined && (
+        <p className="text-xs text-muted-foreground">
+          {testResult
+            ? t("settings.privacy.ignoreTestIgnored", { rule: describe(testResult) })
+            : t("settings.privacy.ignoreTestRecorded")}
+        </p>
+      )}
     </div>
   );
 }
@@ -2,7 +2,7 @@ import { useTranslation } from "react-i18next";
 import type { ClipboardItem } from "../lib/types";
 import { relativeTime } from "../lib/time";
 import { createPressActionHandlers } from "../lib/press-action";
-import { Type } from "lucide-react";
+import { Lock, Type } from "lucide-react";
 
 interface TextCardProps {
   item: ClipboardItem;
@@ -12,10 +12,11 @@ interface TextCardProps {
 
 const MAX_LINES = 6;
 const MAX_CHARS = 300;
+const MASK = "\u2022".repeat(8);
 
 export function TextCard({ item, selected, onClick }: TextCardProps) {
   const { t } = useTranslation();
-  const preview = truncateText(item.plain_text, MAX_CHARS, MAX_LINES);
+  const preview = item.is_sensitive ? MASK : truncateText(item.plain_text, MAX_CHARS, MAX_LINES);
   const pressHandlers = createPressActionHandlers<HTMLDivElement>(onClick, {
     enableKeyboardHandler: true,
   });
@@ -29,13 +30,18 @@ export function TextCard({ item, selected, onClick }: TextCardProps) {
         ${selected ? "border-primary bg-selected" : "border-border/50 bg-card/60 hover:border-muted-foreground/30 hover:bg-card/80"}`}
     >
       <div className="flex items-center gap-1.5 text-muted-foreground pr-8">
-        <Type size={13} />
+        {item.is_sensitive ? <Lock size={13} /> : <Type size={13} />}
         <span className="text-sm">{t("card.text")}</span>
       </div>
       <pre className="whitespace-pre-wrap break-words text-sm font-mono text-foreground leading-relaxed line-clamp-5 flex-1 min-h-0">
         {preview}
       </pre>
       <div className="flex items-center justify-end text-sm text-muted-foreground mt-auto pt-1.5">
+        {item.is_sensitive ? (
+          <span className="mr-auto">{t("card.sensitive")}</span>
+        ) : (
+          item.selection === "primary" && <span className="mr-auto">{t("card.selection")}</span>
+        )}
         <span>{relativeTime(item.updated_at)}</span>
       </div>
     </div>
@@ -0,0 +1,60 @@
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Lock } from "lucide-react";
+import { useLockStore } from "../stores/lock-store";
+import { useClipboardStore } from "../stores/clipboard-store";
+import { Button } from "./ui/button";
+import { Input } from "./ui/input";
+
+/** Shown in place of the history while the app lock is engaged. */
+export function UnlockPrompt() {
+  const { t } = useTranslation();
+  const unlock = useLockStore((s) => s.unlock);
+  const fetchItems = useClipboardStore((s) => s.fetchItems);
+  const [secret, setSecret] = useState("");
+  const [failed, setFailed] = useState(false);
+  const [busy, setBusy] = useState(false);
+
+  const submit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!secret || busy) return;
+    setBusy(true);
+    const ok = await unlock(secret);
+    setBusy(false);
+    setSecret("");
+    setFailed(!ok);
+    if (ok) fetchItems();
+  };
+
+  return (
+    <form
+      onSubmit={submit}
+      // Keep Enter/Tab/Backspace away from the list shortcuts; Escape still hides the panel
+      onKeyDown={(e) => {
+        if (e.key !== "Escape") e.stopPropagation();
+      }}
+      className="flex flex-col items-center justify-center h-full gap-3 text-muted-foreground"
+    >
+      <Lock size={32} strokeWidth={1.5} />
+      <p className="text-sm">{t("lock.locked")}</p>
+      <div className="flex items-center gap-2">
+        <Input
+          type="password"
+          autoFocus
+          value={secret}
+          placeholder={t("lock.secret")}
+          aria-label={t("lock.secret")}
+          onChange={(e) => {
+            setSecret(e.target.value);
+            setFailed(false);
+          }}
+          className="h-8 w-48"
+        />
+        <Button type="submit" size="sm" disabled={!secret || busy}>
+          {t("lock.unlock")}
+        </Button>
+      </div>
+      {failed && <p className="text-xs text-destructive">{t("lock.incorrect")}</p>}
+    </form>
+  );
+}
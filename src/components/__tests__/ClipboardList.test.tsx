@@ -66,12 +66,30 @@ const DEFAULT_SETTINGS: Settings = {
   retention_policy: "unlimited",
   retention_days: "0",
   retention_count: "0",
+  retention_size_mb: "1024",
+  maintenance_interval_hours: "6",
+  maintenance_vacuum: "false",
   max_item_size_mb: "10",
   close_on_blur: "true",
   update_check_interval: "weekly",
   panel_position: "bottom",
   flat_mode_tb: "false",
   show_tray_icon: "true",
+  strip_image_metadata: "false",
+  text_dedup_mode: "exact",
+  near_duplicate_mode: "off",
+  image_similarity_threshold: "4",
+  linux_paste_backend: "auto",
+  linux_paste_keys: "auto",
+  linux_paste_command: "",
+  primary_capture: "false",
+  primary_sync: "off",
+  clipboard_owner: "false",
+  concealed_mode: "skip",
+  sensitive_ttl_secs: "60",
+  sensitive_detection: "false",
+  sensitive_patterns: "",
+  app_lock_idle_minutes: "5",
 };
 
 const scrollIntoViewSpy = vi.fn();
@@ -87,6 +105,9 @@ const mockItem = (overrides: Partial<ClipboardItem> = {}): ClipboardItem => ({
   is_favorited: false,
   created_at: "2026-03-18 10:00:00",
   updated_at: "2026-03-18 10:00:00",
+  selection: "clipboard",
+  is_sensitive: false,
+  variant_count: 0,
   ...overrides,
 });
 
@@ -17,9 +17,17 @@ const mockItem = (overrides: Partial<ClipboardItem> = {}): ClipboardItem => ({
   is_favorited: false,
   created_at: "2026-02-23 10:00:00",
   updated_at: "2026-02-23 10:00:00",
+  selection: "clipboard",
+  is_sensitive: false,
+  variant_count: 0,
   ...overrides,
 });
 
+// `thumb://` protocol responses: 404 until the thumbnail exists
+const fetchMock = vi.fn();
+globalThis.fetch = fetchMock as unknown as typeof fetch;
+const notFound = () => Promise.resolve({ ok: false, status: 404 });
+
 // Mock URL.createObjectURL for jsdom
 if (!globalThis.URL.createObjectURL) {
   globalThis.URL.createObjectURL = vi.fn(() => "blob:mock-url");
@@ -29,6 +37,7 @@ describe("ImageCard", () => {
   beforeEach(() => {
     vi.clearAllMocks();
     mockedInvoke.mockResolvedValue(null);
+    fetchMock.mockImplementation(notFound);
   });
 
   it("renders image type label and size", () => {
@@ -37,20 +46,36 @@ describe("ImageCard", () => {
     expect(screen.getByText("100.0 KB")).toBeInTheDocument();
   });
 
+  it("shows the number of grouped near-duplicates", () => {
+    const { rerender } = render(
+      <ImageCard item={mockItem()} selected={false} onClick={vi.fn()} />,
+    );
+    expect(screen.queryByText(/similar/)).toBeNull();
+
+    rerender(<ImageCard item={mockItem({ variant_count: 2 })} selected={false} onClick={vi.fn()} />);
+    expect(screen.getByText("+2 similar")).toBeInTheDocument();
+  });
+
   it("renders placeholder when no thumbnail", () => {
     const { container } = render(
       <ImageCard item={mockItem()} selected={false} onClick={vi.fn()} />,
     );
-    // No img element (thumbnail not loaded yet, invoke returns null)
+    // No img element (thumbnail not loaded yet, protocol returns 404)
     expect(container.querySelector("img")).toBeNull();
   });
 
   it("renders thumbnail image when loaded", async () => {
     // 1x1 transparent PNG bytes
     const pngBytes = [137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82];
-    mockedInvoke.mockImplementation((cmd: string) => {
-      if (cmd === "get_thumbnail") return Promise.resolve(pngBytes);
-      return Promise.resolve(null);
+    fetchMock.mockImplementation((url: string) => {
+      if (url.includes("img-1")) {
+        return Promise.resolve({
+          ok: true,
+          status: 200,
+          blob: () => Promise.resolve(new Blob([new Uint8Array(pngBytes)], { type: "image/png" })),
+        });
+      }
+      return notFound();
     });
 
     const { container } = render(
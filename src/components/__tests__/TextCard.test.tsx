@@ -14,6 +14,9 @@ const mockItem = (overrides: Partial<ClipboardItem> = {}): ClipboardItem => ({
   is_favorited: false,
   created_at: "2026-02-23 10:00:00",
   updated_at: "2026-02-23 10:00:00",
+  selection: "clipboard",
+  is_sensitive: false,
+  variant_count: 0,
   ...overrides,
 });
 
@@ -41,6 +44,27 @@ describe("TextCard", () => {
     expect(svg).not.toBeInTheDocument();
   });
 
+  it("marks items captured from the PRIMARY selection", () => {
+    const { rerender } = render(<TextCard item={mockItem()} selected={false} onClick={vi.fn()} />);
+    expect(screen.queryByText("Selection")).not.toBeInTheDocument();
+    rerender(
+      <TextCard item={mockItem({ selection: "primary" })} selected={false} onClick={vi.fn()} />,
+    );
+    expect(screen.getByText("Selection")).toBeInTheDocument();
+  });
+
+  it("masks sensitive items", () => {
+    render(
+      <TextCard
+        item={mockItem({ plain_text: "", is_sensitive: true })}
+        selected={false}
+        onClick={vi.fn()}
+      />,
+    );
+    expect(screen.getByText("\u2022".repeat(8))).toBeInTheDocument();
+    expect(screen.getByText("Concealed")).toBeInTheDocument();
+  });
+
   it("applies selected styles", () => {
     render(<TextCard item={mockItem()} selected={true} onClick={vi.fn()} />);
     const card = screen.getByRole("button");
@@ -1,14 +1,26 @@
 import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
 import { renderHook, act, cleanup } from "@testing-library/react";
-import { invoke } from "@tauri-apps/api/core";
-
-const mockedInvoke = vi.mocked(invoke);
 
+let fetchMock: ReturnType<typeof vi.fn>;
 let createObjectURLMock: ReturnType<typeof vi.fn>;
 let revokeObjectURLMock: ReturnType<typeof vi.fn>;
 
+/** A `thumb://` protocol response: bytes → 200, null or empty → 404. */
+function thumbResponse(data: number[] | null) {
+  if (!data || data.length === 0) {
+    return { ok: false, status: 404, blob: () => Promise.resolve(new Blob([])) };
+  }
+  return {
+    ok: true,
+    status: 200,
+    blob: () => Promise.resolve(new Blob([new Uint8Array(data)], { type: "image/jpeg" })),
+  };
+}
+
 beforeEach(() => {
   vi.clearAllMocks();
+  fetchMock = vi.fn(() => Promise.resolve(thumbResponse(null)));
+  globalThis.fetch = fetchMock as unknown as typeof fetch;
   createObjectURLMock = vi.fn((blob: Blob) => `blob:mock-url-${blob.size}`);
   revokeObjectURLMock = vi.fn();
   globalThis.URL.createObjectURL = createObjectURLMock;
@@ -39,23 +51,23 @@ describe("useThumbnail", () => {
     const { result } = renderHook(() => useThumbnail(null));
 
     expect(result.current).toBeNull();
-    expect(mockedInvoke).not.toHaveBeenCalled();
+    expect(fetchMock).not.toHaveBeenCalled();
   });
 
-  it("should call invoke with correct item id", async () => {
+  it("should fetch the thumb:// URL for the item id", async () => {
     const { useThumbnail } = await importFresh();
-    mockedInvoke.mockResolvedValueOnce([1, 2, 3]);
+    fetchMock.mockResolvedValueOnce(thumbResponse([1, 2, 3]));
 
     renderHook(() => useThumbnail("test-id-42"));
 
     await vi.waitFor(() => {
-      expect(mockedInvoke).toHaveBeenCalledWith("get_thumbnail", { id: "test-id-42" });
+      expect(fetchMock).toHaveBeenCalledWith("asset://localhost/test-id-42?size=400");
     });
   });
 
   it("should return object URL after successful fetch", async () => {
     const { useThumbnail } = await importFresh();
-    mockedInvoke.mockResolvedValueOnce([1, 2, 3]);
+    fetchMock.mockResolvedValueOnce(thumbResponse([1, 2, 3]));
 
     const { result } = renderHook(() => useThumbnail("item-1"));
 
@@ -67,7 +79,7 @@ describe("useThumbnail", () => {
 
   it("should return cached thumbnail URL on subsequent renders", async () => {
     const { useThumbnail } = await importFresh();
-    mockedInvoke.mockResolvedValueOnce([1, 2, 3]);
+    fetchMock.mockResolvedValueOnce(thumbResponse([1, 2, 3]));
 
     const { result, unmount } = renderHook(() => useThumbnail("item-1"));
     await vi.waitFor(() => {
@@ -79,17 +91,17 @@ describe("useThumbnail", () => {
 
     const { result: result2 } = renderHook(() => useThumbnail("item-1"));
     expect(result2.current).toBe(cachedUrl);
-    expect(mockedInvoke).toHaveBeenCalledTimes(1);
+    expect(fetchMock).toHaveBeenCalledTimes(1);
   });
 
-  it("should retry on null response up to 3 times with 500ms intervals", async () => {
+  it("should retry on 404 response up to 3 times with 500ms intervals", async () => {
     vi.useFakeTimers();
     const { useThumbnail } = await importFresh();
 
-    mockedInvoke
-      .mockResolvedValueOnce(null)
-      .mockResolvedValueOnce(null)
-      .mockResolvedValueOnce([10, 20, 30]);
+    fetchMock
+      .mockResolvedValueOnce(thumbResponse(null))
+      .mockResolvedValueOnce(thumbResponse(null))
+      .mockResolvedValueOnce(thumbResponse([10, 20, 30]));
 
     const { result } = renderHook(() => useThumbnail("retry-id"));
 
@@ -107,7 +119,7 @@ describe("useThumbnail", () => {
     await vi.waitFor(() => {
       expect(result.current).toMatch(/^blob:mock-url/);
     });
-    expect(mockedInvoke).toHaveBeenCalledTimes(3);
+    expect(fetchMock).toHaveBeenCalledTimes(3);
 
     vi.useRealTimers();
   });
@@ -116,7 +128,7 @@ describe("useThumbnail", () => {
     vi.useFakeTimers();
     const { useThumbnail } = await importFresh();
 
-    mockedInvoke.mockResolvedValue(null);
+    fetchMock.mockResolvedValue(thumbResponse(null));
 
     const { result } = renderHook(() => useThumbnail("exhaust-id"));
 
@@ -133,7 +145,7 @@ describe("useThumbnail", () => {
     });
 
     // 1 initial + 3 retries = 4 total
-    expect(mockedInvoke).toHaveBeenCalledTimes(4);
+    expect(fetchMock).toHaveBeenCalledTimes(4);
     expect(result.current).toBeNull();
 
     vi.useRealTimers();
@@ -143,7 +155,7 @@ describe("useThumbnail", () => {
     vi.useFakeTimers();
     const { useThumbnail } = await importFresh();
 
-    mockedInvoke.mockResolvedValue(null);
+    fetchMock.mockResolvedValue(thumbResponse(null));
 
     const { unmount } = renderHook(() => useThumbnail("cancel-id"));
 
@@ -151,7 +163,7 @@ describe("useThumbnail", () => {
     await act(async () => {
       await vi.advanceTimersByTimeAsync(500);
     });
-    expect(mockedInvoke).toHaveBeenCalledTimes(2);
+    expect(fetchMock).toHaveBeenCalledTimes(2);
 
     unmount();
 
@@ -160,16 +172,16 @@ describe("useThumbnail", () => {
       await vi.advanceTimersByTimeAsync(500);
     });
 
-    expect(mockedInvoke).toHaveBeenCalledTimes(2);
+    expect(fetchMock).toHaveBeenCalledTimes(2);
 
     vi.useRealTimers();
   });
 
-  it("should handle invoke rejection gracefully and log error", async () => {
+  it("should handle fetch rejection gracefully and log error", async () => {
     const { useThumbnail } = await importFresh();
     const errorSpy = vi.spyOn(console, "error").mockImplementation(() => {});
 
-    mockedInvoke.mockRejectedValueOnce(new Error("backend error"));
+    fetchMock.mockRejectedValueOnce(new Error("backend error"));
 
     const { result } = renderHook(() => useThumbnail("error-id"));
 
@@ -183,11 +195,13 @@ describe("useThumbnail", () => {
     errorSpy.mockRestore();
   });
 
-  it("should retry on empty array response", async () => {
+  it("should retry on empty body response", async () => {
     vi.useFakeTimers();
     const { useThumbnail } = await importFresh();
 
-    mockedInvoke.mockResolvedValueOnce([]).mockResolvedValueOnce([5, 6, 7]);
+    fetchMock
+      .mockResolvedValueOnce(thumbResponse([]))
+      .mockResolvedValueOnce(thumbResponse([5, 6, 7]));
 
     const { result } = renderHook(() => useThumbnail("empty-arr-id"));
 
@@ -198,7 +212,7 @@ describe("useThumbnail", () => {
     await vi.waitFor(() => {
       expect(result.current).toMatch(/^blob:mock-url/);
     });
-    expect(mockedInvoke).toHaveBeenCalledTimes(2);
+    expect(fetchMock).toHaveBeenCalledTimes(2);
 
     vi.useRealTimers();
   });
@@ -1,5 +1,13 @@
 import { useState, useEffect, useRef } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { convertFileSrc } from "@tauri-apps/api/core";
+
+// Thumbnail width requested from the backend disk cache (see thumbnails.rs)
+const THUMBNAIL_SIZE = 400;
+
+/** URL of an item's thumbnail on the `thumb://` protocol served by the backend. */
+export function thumbnailUrl(id: string, size = THUMBNAIL_SIZE): string {
+  return `${convertFileSrc(id, "thumb")}?size=${size}`;
+}
 
 // LRU cache capacity — holds ~200 blob URLs (≈10MB worst case at 50KB/thumbnail)
 const LRU_CAPACITY = 200;
@@ -41,8 +49,9 @@ function lruSet(key: string, value: string): void {
  * Pass null to skip fetching.
  * Returns an object URL or null while loading.
  *
- * When the first fetch returns null (thumbnail not yet generated, e.g. async
- * file-thumbnail), retries up to 3 times with 500ms intervals.
+ * Thumbnails are loaded from the backend's on-disk cache via the `thumb://`
+ * protocol. When the first fetch returns 404 (thumbnail not yet generated,
+ * e.g. async file-thumbnail), retries up to 3 times with 500ms intervals.
  */
 export function useThumbnail(id: string | null): string | null {
   // Pure read — no LRU promotion during render (React 19 may speculatively render)
@@ -62,12 +71,11 @@ export function useThumbnail(id: string | null): string | null {
     retryRef.current = 0;
 
     const fetchThumbnail = () => {
-      invoke<number[] | null>("get_thumbnail", { id })
-        .then((data) => {
+      fetch(thumbnailUrl(id))
+        .then((res) => (res.ok ? res.blob() : null))
+        .then((blob) => {
           if (cancelled) return;
-          if (data && data.length > 0) {
-            const bytes = new Uint8Array(data);
-            const blob = new Blob([bytes], { type: "image/png" });
+          if (blob && blob.size > 0) {
             const objectUrl = URL.createObjectURL(blob);
             lruSet(id, objectUrl);
             setUrl(objectUrl);
@@ -4,7 +4,6 @@ export interface ClipboardItem {
   id: string;
   content_type: ContentType;
   plain_text: string;
-  thumbnail?: number[];
   image_path?: string;
   file_path?: string;
   file_name?: string;
@@ -15,6 +14,12 @@ export interface ClipboardItem {
   is_favorited: boolean;
   created_at: string;
   updated_at: string;
+  /** Selection the item was captured from; `primary` is Linux select-to-copy. */
+  selection: "clipboard" | "primary";
+  /** Secret kept masked (`plain_text` is empty) until it expires. */
+  is_sensitive: boolean;
+  /** Near-duplicate images grouped under this item. */
+  variant_count: number;
 }
 
 export interface ItemDetail {
@@ -23,9 +28,105 @@ export interface ItemDetail {
   plain_text: string;
   rich_content?: string;
   image_path?: string;
+  image_mime?: string;
   file_path?: string;
   file_name?: string;
   content_size: number;
+  image_metadata?: ImageMetadata;
+}
+
+export interface ImageMetadata {
+  width: number;
+  height: number;
+  format: string;
+  color_type: string;
+  camera?: string;
+  taken_at?: string;
+  gps_latitude?: number;
+  gps_longitude?: number;
+}
+
+export interface RetentionRule {
+  id: number;
+  content_type: string;
+  /** Empty matches every source app. */
+  source_app: string;
+  max_days: number | null;
+  max_count: number | null;
+  max_size_mb: number | null;
+}
+
+export interface AppCaptureRule {
+  id: number;
+  /** Source app id, executable name or display name (case-insensitive). */
+  app: string;
+  action: "exclude" | "include";
+}
+
+export type CaptureIgnoreKind = "regex" | "min_length" | "max_length" | "whitespace" | "extension";
+
+export interface CaptureIgnoreRule {
+  id: number;
+  kind: CaptureIgnoreKind;
+  /** Pattern, character count or file extension; unused for `whitespace`. */
+  value: string;
+  enabled: boolean;
+}
+
+/** Whether clipboard capture is paused (incognito), from `get_capture_status`. */
+export interface CaptureStatus {
+  paused: boolean;
+  /** UTC time capture resumes on its own; null while paused until resumed by hand. */
+  resumes_at: string | null;
+}
+
+/** PIN/passphrase app lock state, from `get_app_lock_status`. */
+export interface AppLockStatus {
+  enabled: boolean;
+  /** History reads are refused until `unlock` succeeds. */
+  locked: boolean;
+  idle_minutes: number;
+}
+
+/** Filter shared by search and filtered clear; unset fields match everything. */
+export interface ItemFilter {
+  content_type?: string;
+  favorites_only?: boolean;
+  source_app?: string;
+  group_id?: string;
+  /** Only items that belong to no group. */
+  ungrouped?: boolean;
+  /** Only items created more than this many days ago. */
+  older_than_days?: number;
+  /** Only items captured from this selection. */
+  selection?: "clipboard" | "primary";
+}
+
+export interface RetentionTypeSummary {
+  content_type: string;
+  items: number;
+  bytes: number;
+}
+
+/** What the current retention settings would delete; nothing is removed. */
+export interface RetentionPreview {
+  total_items: number;
+  total_bytes: number;
+  by_type: RetentionTypeSummary[];
+  /** Newest affected items; empty while the app is locked. */
+  sample: ClipboardItem[];
+}
+
+export interface MaintenanceStatus {
+  running: boolean;
+  last_run_at?: string;
+  last_duration_ms: number;
+  retention_deleted: number;
+  orphan_images_removed: number;
+  orphan_thumbnails_removed: number;
+  vacuumed: boolean;
+  errors: string[];
+  next_run_at?: string;
 }
 
 export interface PreviewResponse {
@@ -0,0 +1,43 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+import { useCaptureStore } from "../capture-store";
+
+const mockedInvoke = vi.mocked(invoke);
+
+describe("useCaptureStore", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    useCaptureStore.setState({ paused: false, resumesAt: null });
+  });
+
+  it("pauses for a duration and keeps the backend's resume time", async () => {
+    mockedInvoke.mockResolvedValueOnce({ paused: true, resumes_at: "2026-10-19 12:15:00" });
+
+    await useCaptureStore.getState().pause(900);
+
+    expect(mockedInvoke).toHaveBeenCalledWith("pause_capture", { durationSecs: 900 });
+    expect(useCaptureStore.getState().paused).toBe(true);
+    expect(useCaptureStore.getState().resumesAt).toBe("2026-10-19 12:15:00");
+  });
+
+  it("resumes capture", async () => {
+    useCaptureStore.setState({ paused: true, resumesAt: null });
+    mockedInvoke.mockResolvedValueOnce({ paused: false, resumes_at: null });
+
+    await useCaptureStore.getState().resume();
+
+    expect(mockedInvoke).toHaveBeenCalledWith("resume_capture");
+    expect(useCaptureStore.getState().paused).toBe(false);
+  });
+
+  it("keeps the current state when the backend call fails", async () => {
+    useCaptureStore.setState({ paused: true, resumesAt: null });
+    mockedInvoke.mockRejectedValueOnce(new Error("boom"));
+    const spy = vi.spyOn(console, "error").mockImplementation(() => {});
+
+    await useCaptureStore.getState().fetchStatus();
+
+    expect(useCaptureStore.getState().paused).toBe(true);
+    spy.mockRestore();
+  });
+});
@@ -16,6 +16,9 @@ const mockItem = (overrides: Partial<ClipboardItem> = {}): ClipboardItem => ({
   is_favorited: false,
   created_at: "2026-02-23 10:00:00",
   updated_at: "2026-02-23 10:00:00",
+  selection: "clipboard",
+  is_sensitive: false,
+  variant_count: 0,
   ...overrides,
 });
 
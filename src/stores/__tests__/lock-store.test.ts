@@ -0,0 +1,54 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+import { useLockStore } from "../lock-store";
+
+const mockedInvoke = vi.mocked(invoke);
+
+describe("useLockStore", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    useLockStore.setState({ enabled: true, locked: true });
+  });
+
+  it("unlocks with the right secret", async () => {
+    mockedInvoke.mockResolvedValueOnce(undefined);
+
+    const ok = await useLockStore.getState().unlock("1234");
+
+    expect(ok).toBe(true);
+    expect(mockedInvoke).toHaveBeenCalledWith("unlock", { secret: "1234" });
+    expect(useLockStore.getState().locked).toBe(false);
+  });
+
+  it("stays locked when the secret is rejected", async () => {
+    mockedInvoke.mockRejectedValueOnce("Incorrect PIN or passphrase");
+
+    const ok = await useLockStore.getState().unlock("0000");
+
+    expect(ok).toBe(false);
+    expect(useLockStore.getState().locked).toBe(true);
+  });
+
+  it("removes the lock with the current secret and refreshes the status", async () => {
+    mockedInvoke
+      .mockResolvedValueOnce(undefined)
+      .mockResolvedValueOnce({ enabled: false, locked: false, idle_minutes: 5 });
+
+    await useLockStore.getState().setLock(null, "1234");
+
+    expect(mockedInvoke).toHaveBeenCalledWith("set_app_lock", {
+      secret: null,
+      currentSecret: "1234",
+    });
+    expect(useLockStore.getState().enabled).toBe(false);
+    expect(useLockStore.getState().locked).toBe(false);
+  });
+
+  it("follows the backend's lock event", () => {
+    useLockStore.setState({ enabled: true, locked: false });
+
+    useLockStore.getState().markLocked();
+
+    expect(useLockStore.getState().locked).toBe(true);
+  });
+});
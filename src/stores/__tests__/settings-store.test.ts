@@ -351,6 +351,26 @@ describe("useSettingsStore", () => {
     });
   });
 
+  describe("clearHistoryFiltered", () => {
+    it("should invoke clear_history_filtered with the filter", async () => {
+      mockedInvoke.mockResolvedValueOnce(7);
+
+      const filter = { content_type: "image", older_than_days: 3 };
+      const count = await useSettingsStore.getState().clearHistoryFiltered(filter);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("clear_history_filtered", { filter });
+      expect(count).toBe(7);
+    });
+
+    it("should return 0 on failure", async () => {
+      mockedInvoke.mockRejectedValueOnce(new Error("fail"));
+
+      const count = await useSettingsStore.getState().clearHistoryFiltered({ ungrouped: true });
+
+      expect(count).toBe(0);
+    });
+  });
+
   describe("runRetentionCleanup", () => {
     it("should invoke run_retention_cleanup and return count", async () => {
       mockedInvoke.mockResolvedValueOnce(15);
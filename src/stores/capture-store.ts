@@ -0,0 +1,46 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { CaptureStatus } from "../lib/types";
+
+interface CaptureState {
+  paused: boolean;
+  /** UTC time capture resumes on its own; null while paused until resumed by hand. */
+  resumesAt: string | null;
+
+  fetchStatus: () => Promise<void>;
+  /** Pause for `durationSecs`, or until resumed when null. */
+  pause: (durationSecs: number | null) => Promise<void>;
+  resume: () => Promise<void>;
+  syncStatus: (status: CaptureStatus) => void;
+}
+
+export const useCaptureStore = create<CaptureState>((set, get) => ({
+  paused: false,
+  resumesAt: null,
+
+  fetchStatus: async () => {
+    try {
+      get().syncStatus(await invoke<CaptureStatus>("get_capture_status"));
+    } catch (e) {
+      console.error("Failed to get capture status:", e);
+    }
+  },
+
+  pause: async (durationSecs) => {
+    try {
+      get().syncStatus(await invoke<CaptureStatus>("pause_capture", { durationSecs }));
+    } catch (e) {
+      console.error("Failed to pause capture:", e);
+    }
+  },
+
+  resume: async () => {
+    try {
+      get().syncStatus(await invoke<CaptureStatus>("resume_capture"));
+    } catch (e) {
+      console.error("Failed to resume capture:", e);
+    }
+  },
+
+  syncStatus: (status) => set({ paused: status.paused, resumesAt: status.resumes_at }),
+}));
@@ -0,0 +1,58 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { AppLockStatus } from "../lib/types";
+
+interface LockState {
+  /** Whether a PIN/passphrase is configured. */
+  enabled: boolean;
+  locked: boolean;
+
+  fetchStatus: () => Promise<void>;
+  /** Returns false when the secret is rejected. */
+  unlock: (secret: string) => Promise<boolean>;
+  lockNow: () => Promise<void>;
+  /** Set, change or (with `secret: null`) remove the lock. Rejects with the backend error. */
+  setLock: (secret: string | null, currentSecret: string | null) => Promise<void>;
+  /** Follow the backend's `app-locked` event (idle timeout or "Lock now"). */
+  markLocked: () => void;
+}
+
+export const useLockStore = create<LockState>((set, get) => ({
+  enabled: false,
+  locked: false,
+
+  fetchStatus: async () => {
+    try {
+      const status = await invoke<AppLockStatus>("get_app_lock_status");
+      set({ enabled: status.enabled, locked: status.locked });
+    } catch (e) {
+      console.error("Failed to get app lock status:", e);
+    }
+  },
+
+  unlock: async (secret) => {
+    try {
+      await invoke("unlock", { secret });
+      set({ locked: false });
+      return true;
+    } catch {
+      return false;
+    }
+  },
+
+  lockNow: async () => {
+    try {
+      await invoke("lock_app");
+      await get().fetchStatus();
+    } catch (e) {
+      console.error("Failed to lock app:", e);
+    }
+  },
+
+  setLock: async (secret, currentSecret) => {
+    await invoke("set_app_lock", { secret, currentSecret });
+    await get().fetchStatus();
+  },
+
+  markLocked: () => set({ enabled: true, locked: true }),
+}));
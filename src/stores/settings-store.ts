@@ -6,6 +6,7 @@ import {
   isEnabled as isAutostartEnabled,
 } from "@tauri-apps/plugin-autostart";
 import i18n from "../i18n";
+import type { ItemFilter } from "../lib/types";
 
 export type Theme = "dark" | "light" | "system";
 
@@ -17,12 +18,30 @@ export interface Settings {
   retention_policy: string;
   retention_days: string;
   retention_count: string;
+  retention_size_mb: string;
+  maintenance_interval_hours: string;
+  maintenance_vacuum: string;
   max_item_size_mb: string;
   close_on_blur: string;
   update_check_interval: string;
   panel_position: string;
   flat_mode_tb: string;
   show_tray_icon: string;
+  strip_image_metadata: string;
+  text_dedup_mode: string;
+  near_duplicate_mode: string;
+  image_similarity_threshold: string;
+  linux_paste_backend: string;
+  linux_paste_keys: string;
+  linux_paste_command: string;
+  primary_capture: string;
+  primary_sync: string;
+  clipboard_owner: string;
+  concealed_mode: string;
+  sensitive_ttl_secs: string;
+  sensitive_detection: string;
+  sensitive_patterns: string;
+  app_lock_idle_minutes: string;
 }
 
 const DEFAULT_SETTINGS: Settings = {
@@ -33,12 +52,30 @@ const DEFAULT_SETTINGS: Settings = {
   retention_policy: "unlimited",
   retention_days: "0",
   retention_count: "0",
+  retention_size_mb: "1024",
+  maintenance_interval_hours: "6",
+  maintenance_vacuum: "false",
   max_item_size_mb: "10",
   close_on_blur: "true",
   update_check_interval: "weekly",
   panel_position: "bottom",
   flat_mode_tb: "false",
   show_tray_icon: "true",
+  strip_image_metadata: "false",
+  text_dedup_mode: "exact",
+  near_duplicate_mode: "off",
+  image_similarity_threshold: "4",
+  linux_paste_backend: "auto",
+  linux_paste_keys: "auto",
+  linux_paste_command: "",
+  primary_capture: "false",
+  primary_sync: "off",
+  clipboard_owner: "false",
+  concealed_mode: "skip",
+  sensitive_ttl_secs: "60",
+  sensitive_detection: "false",
+  sensitive_patterns: "",
+  app_lock_idle_minutes: "5",
 };
 
 export interface ShowEventPayload {
@@ -59,6 +96,7 @@ interface SettingsState {
   updateSetting: (key: keyof Settings, value: string) => Promise<void>;
   syncSettingsFromEvent: (payload: ShowEventPayload) => void;
   clearHistory: () => Promise<number>;
+  clearHistoryFiltered: (filter: ItemFilter) => Promise<number>;
   runRetentionCleanup: () => Promise<number>;
 }
 
@@ -98,12 +136,32 @@ export const useSettingsStore = create<SettingsState>((set) => ({
         retention_policy: raw.retention_policy ?? DEFAULT_SETTINGS.retention_policy,
         retention_days: raw.retention_days ?? DEFAULT_SETTINGS.retention_days,
         retention_count: raw.retention_count ?? DEFAULT_SETTINGS.retention_count,
+        retention_size_mb: raw.retention_size_mb ?? DEFAULT_SETTINGS.retention_size_mb,
+        maintenance_interval_hours:
+          raw.maintenance_interval_hours ?? DEFAULT_SETTINGS.maintenance_interval_hours,
+        maintenance_vacuum: raw.maintenance_vacuum ?? DEFAULT_SETTINGS.maintenance_vacuum,
         max_item_size_mb: raw.max_item_size_mb ?? DEFAULT_SETTINGS.max_item_size_mb,
         close_on_blur: raw.close_on_blur ?? DEFAULT_SETTINGS.close_on_blur,
         update_check_interval: raw.update_check_interval ?? DEFAULT_SETTINGS.update_check_interval,
         panel_position: raw.panel_position ?? DEFAULT_SETTINGS.panel_position,
         flat_mode_tb: raw.flat_mode_tb ?? DEFAULT_SETTINGS.flat_mode_tb,
         show_tray_icon: raw.show_tray_icon ?? DEFAULT_SETTINGS.show_tray_icon,
+        strip_image_metadata: raw.strip_image_metadata ?? DEFAULT_SETTINGS.strip_image_metadata,
+        text_dedup_mode: raw.text_dedup_mode ?? DEFAULT_SETTINGS.text_dedup_mode,
+        near_duplicate_mode: raw.near_duplicate_mode ?? DEFAULT_SETTINGS.near_duplicate_mode,
+        image_similarity_threshold:
+          raw.image_similarity_threshold ?? DEFAULT_SETTINGS.image_similarity_threshold,
+        linux_paste_backend: raw.linux_paste_backend ?? DEFAULT_SETTINGS.linux_paste_backend,
+        linux_paste_keys: raw.linux_paste_keys ?? DEFAULT_SETTINGS.linux_paste_keys,
+        linux_paste_command: raw.linux_paste_command ?? DEFAULT_SETTINGS.linux_paste_command,
+        primary_capture: raw.primary_capture ?? DEFAULT_SETTINGS.primary_capture,
+        primary_sync: raw.primary_sync ?? DEFAULT_SETTINGS.primary_sync,
+        clipboard_owner: raw.clipboard_owner ?? DEFAULT_SETTINGS.clipboard_owner,
+        concealed_mode: raw.concealed_mode ?? DEFAULT_SETTINGS.concealed_mode,
+        sensitive_ttl_secs: raw.sensitive_ttl_secs ?? DEFAULT_SETTINGS.sensitive_ttl_secs,
+        sensitive_detection: raw.sensitive_detection ?? DEFAULT_SETTINGS.sensitive_detection,
+        sensitive_patterns: raw.sensitive_patterns ?? DEFAULT_SETTINGS.sensitive_patterns,
+        app_lock_idle_minutes: raw.app_lock_idle_minutes ?? DEFAULT_SETTINGS.app_lock_idle_minutes,
       };
       set({ settings, loaded: true });
       applyTheme(settings.theme);
@@ -214,6 +272,15 @@ export const useSettingsStore = create<SettingsState>((set) => ({
     }
   },
 
+  clearHistoryFiltered: async (filter) => {
+    try {
+      return await invoke<number>("clear_history_filtered", { filter });
+    } catch (e) {
+      console.error("Failed to clear filtered history:", e);
+      return 0;
+    }
+  },
+
   runRetentionCleanup: async () => {
     try {
       const count = await invoke<number>("run_retention_cleanup");